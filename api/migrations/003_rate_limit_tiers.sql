@@ -0,0 +1,17 @@
+-- Rate limit tier assignment per user
+
+-- Keyed by the authenticated user id: `users.id` of the session user or
+-- key owner, or the wallet address for dashboard keys without a user;
+-- users without a row are on the free tier
+CREATE TABLE IF NOT EXISTS rate_limit_tiers (
+    user_id VARCHAR(255) PRIMARY KEY,
+    tier VARCHAR(50) NOT NULL DEFAULT 'free'
+        CHECK (tier IN ('free', 'pro', 'enterprise')),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE TRIGGER rate_limit_tiers_updated_at
+    BEFORE UPDATE ON rate_limit_tiers
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at();
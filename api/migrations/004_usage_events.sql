@@ -0,0 +1,34 @@
+-- Usage metering: one row per authenticated API call
+
+CREATE TABLE IF NOT EXISTS usage_events (
+    id BIGSERIAL PRIMARY KEY,
+    -- The authenticated user id (the API key's wallet address)
+    user_id VARCHAR(255) NOT NULL,
+    api_key_id UUID NOT NULL,
+    endpoint VARCHAR(255) NOT NULL,
+    network VARCHAR(64),
+    -- Rate limit / quota units the call cost
+    units INTEGER NOT NULL DEFAULT 1,
+    gas_used BIGINT,
+    prover_cpu_secs DOUBLE PRECISION,
+    status SMALLINT NOT NULL,
+    latency_ms INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Indexes for per-user and per-key aggregates
+CREATE INDEX IF NOT EXISTS idx_usage_events_user_created ON usage_events(user_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_usage_events_key_created ON usage_events(api_key_id, created_at);
+
+-- Usage is billing data; rows are never changed once written
+CREATE OR REPLACE FUNCTION reject_usage_event_changes()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'usage_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER usage_events_append_only
+    BEFORE UPDATE OR DELETE ON usage_events
+    FOR EACH ROW
+    EXECUTE FUNCTION reject_usage_event_changes();
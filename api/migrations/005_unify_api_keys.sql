@@ -0,0 +1,29 @@
+-- One API key table for keys minted by the API and by the dashboard
+
+-- Dashboard keys belong to a wallet rather than a Clerk user
+ALTER TABLE api_keys ALTER COLUMN user_id DROP NOT NULL;
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS wallet_address TEXT;
+ALTER TABLE api_keys ADD CONSTRAINT api_keys_owner_check
+    CHECK (user_id IS NOT NULL OR wallet_address IS NOT NULL);
+
+-- key_prefix is "stl_live_" plus 8 random characters and is what keys are
+-- looked up by; key_hash is the hex SHA-256 of the whole key
+ALTER TABLE api_keys ALTER COLUMN key_prefix TYPE VARCHAR(32);
+
+-- Argon2 hashes from the previous issuer cannot be looked up by prefix and
+-- never authenticated; 006 revokes them once revocation exists
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_api_keys_key_hash ON api_keys(key_hash);
+CREATE INDEX IF NOT EXISTS idx_api_keys_wallet_address ON api_keys(wallet_address);
+
+-- Carry over keys minted by the dashboard into sentinel_api_keys, which
+-- already use the same format and hash
+DO $$
+BEGIN
+    IF to_regclass('sentinel_api_keys') IS NOT NULL THEN
+        INSERT INTO api_keys (id, wallet_address, name, key_hash, key_prefix, last_used_at, created_at)
+        SELECT id, wallet_address, name, key_hash, key_prefix, last_used_at, created_at
+        FROM sentinel_api_keys
+        ON CONFLICT DO NOTHING;
+    END IF;
+END $$;
@@ -0,0 +1,37 @@
+-- API key revocation and audit trail
+
+-- Revoked keys are kept so their history stays readable
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS revoked_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS revoked_reason TEXT;
+
+-- Key events: created, used_from_new_ip, revoked, expired
+-- Not a foreign key: the trail outlives the key's owner
+CREATE TABLE IF NOT EXISTS key_events (
+    id BIGSERIAL PRIMARY KEY,
+    api_key_id UUID NOT NULL,
+    event VARCHAR(32) NOT NULL
+        CHECK (event IN ('created', 'used_from_new_ip', 'revoked', 'expired')),
+    ip_address TEXT,
+    user_agent TEXT,
+    detail TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_key_events_api_key_id ON key_events(api_key_id, created_at);
+
+-- Each IP is announced once per key, and expiry once per key
+CREATE UNIQUE INDEX IF NOT EXISTS idx_key_events_new_ip
+    ON key_events(api_key_id, ip_address) WHERE event = 'used_from_new_ip';
+CREATE UNIQUE INDEX IF NOT EXISTS idx_key_events_expired
+    ON key_events(api_key_id) WHERE event = 'expired';
+
+-- Keys left from the Argon2 issuer (see 005) are revoked, not deleted, so
+-- their owners can see why they stopped working
+WITH retired AS (
+    UPDATE api_keys
+    SET revoked_at = NOW(), revoked_reason = 'hash format retired'
+    WHERE key_hash LIKE '$argon2%' AND revoked_at IS NULL
+    RETURNING id
+)
+INSERT INTO key_events (api_key_id, event, detail)
+SELECT id, 'revoked', 'hash format retired' FROM retired;
@@ -0,0 +1,41 @@
+-- API key scopes; NULL allows everything, so existing keys keep working
+
+-- Endpoint groups a key may call: simulate, trace, prove, gas, batch
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS allowed_endpoints TEXT[];
+ALTER TABLE api_keys ADD CONSTRAINT api_keys_allowed_endpoints_check
+    CHECK (allowed_endpoints <@ ARRAY['simulate', 'trace', 'prove', 'gas', 'batch']::TEXT[]);
+
+-- Networks a key may target, e.g. testnet-only CI keys
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS allowed_networks TEXT[];
+
+-- Project a key is bound to; deleting the project revokes the key (below)
+-- before the reference is cleared, so it never outlives it unbound
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS project_id UUID REFERENCES projects(id) ON DELETE SET NULL;
+
+-- Read-only keys may only read through the management routes
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS access VARCHAR(10) NOT NULL DEFAULT 'write'
+    CHECK (access IN ('read', 'write'));
+
+CREATE INDEX IF NOT EXISTS idx_api_keys_project_id ON api_keys(project_id);
+
+-- Keys bound to a deleted project are revoked, not deleted, so their
+-- history stays readable
+CREATE OR REPLACE FUNCTION revoke_project_api_keys()
+RETURNS TRIGGER AS $$
+BEGIN
+    WITH revoked AS (
+        UPDATE api_keys
+        SET revoked_at = NOW(), revoked_reason = 'project deleted'
+        WHERE project_id = OLD.id AND revoked_at IS NULL
+        RETURNING id
+    )
+    INSERT INTO key_events (api_key_id, event, detail)
+    SELECT id, 'revoked', 'project deleted' FROM revoked;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER projects_revoke_api_keys
+    BEFORE DELETE ON projects
+    FOR EACH ROW
+    EXECUTE FUNCTION revoke_project_api_keys();
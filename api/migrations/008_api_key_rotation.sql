@@ -0,0 +1,9 @@
+-- API key rotation: a replacement key overlaps the key it supersedes until
+-- the old one expires at the end of its grace period
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS superseded_by UUID REFERENCES api_keys(id) ON DELETE SET NULL;
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS replaces UUID REFERENCES api_keys(id) ON DELETE SET NULL;
+
+-- Rotations are part of the key audit trail
+ALTER TABLE key_events DROP CONSTRAINT IF EXISTS key_events_event_check;
+ALTER TABLE key_events ADD CONSTRAINT key_events_event_check
+    CHECK (event IN ('created', 'used_from_new_ip', 'revoked', 'expired', 'rotated'));
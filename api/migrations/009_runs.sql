@@ -0,0 +1,17 @@
+-- Prover, batch and gas results, kept so reports can refer to them by id
+
+CREATE TABLE IF NOT EXISTS runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- The authenticated user id, as in usage_events: users.id of the key
+    -- owner, or the wallet address for dashboard keys
+    user_id VARCHAR(255) NOT NULL,
+    -- Project of the key that produced the run; keys bound to a project
+    -- only see its runs
+    project_id UUID REFERENCES projects(id) ON DELETE CASCADE,
+    kind VARCHAR(16) NOT NULL CHECK (kind IN ('prover', 'batch', 'gas')),
+    result JSONB NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_runs_user_created ON runs(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_runs_project_id ON runs(project_id);
@@ -1,71 +1,71 @@
-use argon2::{
-    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
-    Argon2,
-};
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
-use rand::RngCore;
+use rand::{rngs::OsRng, RngCore};
+use sha2::{Digest, Sha256};
 
 const KEY_PREFIX: &str = "stl_live_";
-const RANDOM_BYTES: usize = 18; // Results in 24 base64 chars
+const RANDOM_BYTES: usize = 24; // Results in 32 base64 chars
+/// Random characters kept in `key_prefix`, which doubles as the lookup key
+const LOOKUP_CHARS: usize = 8;
 
 /// Generates a new API key and returns (raw_key, key_hash, key_prefix)
+///
+/// Same format as keys minted by the dashboard, so both verify the same way.
 pub fn generate_api_key() -> Result<(String, String, String), ApiKeyError> {
     // Generate random bytes
     let mut random_bytes = [0u8; RANDOM_BYTES];
-    OsRng.fill_bytes(&mut random_bytes);
+    OsRng
+        .try_fill_bytes(&mut random_bytes)
+        .map_err(|e| ApiKeyError::RandomError(e.to_string()))?;
 
-    // Encode to base64url (24 chars)
+    // Encode to base64url (32 chars)
     let random_part = URL_SAFE_NO_PAD.encode(random_bytes);
 
     // Create full key: stl_live_xxxxxxxxxxxxxxxxxxxx
     let raw_key = format!("{}{}", KEY_PREFIX, random_part);
 
-    // Create prefix for display (first 12 chars)
-    let key_prefix = format!("{}{}...", KEY_PREFIX, &random_part[..4]);
+    // Prefix for display and lookup: stl_live_ plus 8 random chars
+    let key_prefix = format!("{}{}", KEY_PREFIX, &random_part[..LOOKUP_CHARS]);
 
     // Hash the key for storage
-    let key_hash = hash_api_key(&raw_key)?;
+    let key_hash = hash_api_key(&raw_key);
 
     Ok((raw_key, key_hash, key_prefix))
 }
 
-/// Hashes an API key for secure storage
-pub fn hash_api_key(raw_key: &str) -> Result<String, ApiKeyError> {
-    let salt = SaltString::generate(&mut OsRng);
-    let argon2 = Argon2::default();
-
-    let hash = argon2
-        .hash_password(raw_key.as_bytes(), &salt)
-        .map_err(|e| ApiKeyError::HashError(e.to_string()))?;
-
-    Ok(hash.to_string())
+/// Hashes an API key for storage (hex SHA-256)
+///
+/// Keys carry 192 random bits, so a fast unsalted hash is as strong as a
+/// password hash here and lets every request be verified cheaply.
+pub fn hash_api_key(raw_key: &str) -> String {
+    format!("{:x}", Sha256::digest(raw_key.as_bytes()))
 }
 
 /// Verifies an API key against its stored hash using constant-time comparison
 pub fn verify_api_key(raw_key: &str, hash: &str) -> bool {
-    let parsed_hash = match PasswordHash::new(hash) {
-        Ok(h) => h,
-        Err(_) => return false,
-    };
-
-    Argon2::default()
-        .verify_password(raw_key.as_bytes(), &parsed_hash)
-        .is_ok()
+    constant_time_eq(hash_api_key(raw_key).as_bytes(), hash.as_bytes())
+}
+
+/// The stored `key_prefix` a raw key is looked up by, if it is well formed
+pub fn lookup_prefix(raw_key: &str) -> Option<&str> {
+    let random_part = raw_key.strip_prefix(KEY_PREFIX)?;
+    if random_part.len() <= LOOKUP_CHARS || !random_part.is_char_boundary(LOOKUP_CHARS) {
+        return None;
+    }
+    Some(&raw_key[..KEY_PREFIX.len() + LOOKUP_CHARS])
 }
 
-/// Extracts the prefix from a raw API key for display
-pub fn get_key_prefix(raw_key: &str) -> String {
-    if raw_key.len() > 16 {
-        format!("{}...", &raw_key[..16])
-    } else {
-        raw_key.to_string()
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
     }
+    let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    std::hint::black_box(diff) == 0
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum ApiKeyError {
-    #[error("Failed to hash API key: {0}")]
-    HashError(String),
+    #[error("Failed to generate API key: {0}")]
+    RandomError(String),
 }
 
 #[cfg(test)]
@@ -78,14 +78,14 @@ mod tests {
 
         // Check key format
         assert!(raw_key.starts_with("stl_live_"));
-        assert_eq!(raw_key.len(), 33); // 9 prefix + 24 random
+        assert_eq!(raw_key.len(), 41); // 9 prefix + 32 random
 
         // Check prefix format
-        assert!(prefix.starts_with("stl_live_"));
-        assert!(prefix.ends_with("..."));
+        assert_eq!(prefix.len(), 17);
+        assert_eq!(lookup_prefix(&raw_key), Some(prefix.as_str()));
 
-        // Check hash is valid
-        assert!(hash.starts_with("$argon2"));
+        // Check hash is hex SHA-256
+        assert_eq!(hash.len(), 64);
 
         // Verify key against hash
         assert!(verify_api_key(&raw_key, &hash));
@@ -97,22 +97,27 @@ mod tests {
 
         // Wrong key should fail
         assert!(!verify_api_key("stl_live_wrongkey123456789012", &hash));
+        assert!(!verify_api_key(&raw_key, &hash[..63]));
 
         // Correct key should pass
         assert!(verify_api_key(&raw_key, &hash));
     }
 
     #[test]
-    fn test_hash_uniqueness() {
+    fn test_hash_matches_dashboard_keys() {
+        // Dashboard-minted keys are stored as hex SHA-256 too
         let key = "stl_live_test123456789012345678";
-        let hash1 = hash_api_key(key).unwrap();
-        let hash2 = hash_api_key(key).unwrap();
-
-        // Same key should produce different hashes (due to unique salts)
-        assert_ne!(hash1, hash2);
+        assert_eq!(hash_api_key(key), hash_api_key(key));
+        assert_eq!(
+            hash_api_key("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
 
-        // But both should verify correctly
-        assert!(verify_api_key(key, &hash1));
-        assert!(verify_api_key(key, &hash2));
+    #[test]
+    fn test_lookup_prefix() {
+        assert_eq!(lookup_prefix("stl_live_abcdefghijkl"), Some("stl_live_abcdefgh"));
+        assert_eq!(lookup_prefix("stl_live_abcdefgh"), None);
+        assert_eq!(lookup_prefix("sk_live_abcdefghijkl"), None);
     }
 }
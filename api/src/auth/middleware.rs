@@ -1,26 +1,122 @@
 use axum::{
-    extract::{Request, State},
-    http::{header, StatusCode},
+    extract::{ConnectInfo, FromRequestParts, Request, State},
+    http::{header, request::Parts, Extensions, HeaderMap, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
     Json,
 };
+use chrono::{Duration, Utc};
 use serde_json::json;
 use sqlx::PgPool;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
 use uuid::Uuid;
 
-// API key verification now uses SHA-256 hash matching
+use super::{lookup_prefix, verify_api_key, KeyScopes};
+use crate::cache::{self, ttl, CacheBackend};
+use crate::db::{self, ApiKey, CreateKeyEvent, KeyEventKind};
+use crate::AppState;
+
+/// How stale a key's `last_used_at` may get before a call refreshes it
+const LAST_USED_RESOLUTION_SECS: i64 = 60;
 
 /// User info extracted from API key authentication
 #[derive(Clone, Debug)]
 pub struct AuthenticatedUser {
     pub user_id: String,
     pub api_key_id: Uuid,
+    pub scopes: KeyScopes,
+}
+
+/// Proxies in front of the API that append to `X-Forwarded-For`; 0 when
+/// clients connect directly
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TrustedProxies(pub usize);
+
+/// Where a request came from, for the key audit trail
+#[derive(Clone, Debug, Default)]
+pub struct ClientInfo {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl ClientInfo {
+    /// The address the outermost trusted proxy saw, else the peer address
+    fn new(headers: &HeaderMap, extensions: &Extensions, proxies: TrustedProxies) -> Self {
+        let peer = || {
+            extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip().to_string())
+        };
+
+        Self {
+            ip: forwarded_ip(headers, proxies).or_else(peer),
+            user_agent: headers
+                .get(header::USER_AGENT)
+                .and_then(|value| value.to_str().ok())
+                .map(String::from),
+        }
+    }
+
+    /// An audit event for a key, from this client
+    pub fn event(&self, api_key_id: Uuid, kind: KeyEventKind, detail: Option<String>) -> CreateKeyEvent {
+        CreateKeyEvent {
+            api_key_id,
+            kind,
+            ip_address: self.ip.clone(),
+            user_agent: self.user_agent.clone(),
+            detail,
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(ClientInfo::of(&parts.headers, &parts.extensions))
+    }
+}
+
+impl ClientInfo {
+    /// As resolved by `client_info`, or from the peer address without it
+    fn of(headers: &HeaderMap, extensions: &Extensions) -> Self {
+        extensions
+            .get::<ClientInfo>()
+            .cloned()
+            .unwrap_or_else(|| ClientInfo::new(headers, extensions, TrustedProxies::default()))
+    }
+}
+
+/// Proxies append the address they received from, so only the last
+/// `proxies` entries are trustworthy; anything left of them came from the
+/// client. The entry the outermost trusted proxy added is the client's.
+fn forwarded_ip(headers: &HeaderMap, TrustedProxies(proxies): TrustedProxies) -> Option<String> {
+    if proxies == 0 {
+        return None;
+    }
+    let hops: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+    // Fewer hops than proxies: the request did not come through them all
+    let hop = hops.len().checked_sub(proxies).map(|i| hops[i])?;
+    hop.parse::<IpAddr>().ok().map(|ip| ip.to_string())
+}
+
+/// Middleware resolving the client's address once for the whole request
+pub async fn client_info(State(proxies): State<TrustedProxies>, mut request: Request, next: Next) -> Response {
+    let client = ClientInfo::new(request.headers(), request.extensions(), proxies);
+    request.extensions_mut().insert(client);
+    next.run(request).await
 }
 
 /// Middleware to authenticate requests using API key
 pub async fn api_key_auth(
-    State(pool): State<PgPool>,
+    State(state): State<AppState>,
     mut request: Request,
     next: Next,
 ) -> Response {
@@ -39,7 +135,8 @@ pub async fn api_key_auth(
     };
 
     // Look up the key in the database
-    match authenticate_key(&pool, &api_key).await {
+    let client = ClientInfo::of(request.headers(), request.extensions());
+    match authenticate_key(&state, &api_key, &client).await {
         Ok(user) => {
             // Add user info to request extensions
             request.extensions_mut().insert(user);
@@ -55,6 +152,11 @@ pub async fn api_key_auth(
             Json(json!({ "error": "API key has expired" })),
         )
             .into_response(),
+        Err(AuthError::Revoked) => (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "API key has been revoked" })),
+        )
+            .into_response(),
         Err(AuthError::DatabaseError(e)) => {
             tracing::error!("Database error during auth: {}", e);
             (
@@ -86,51 +188,78 @@ fn extract_api_key(request: &Request) -> Option<String> {
     None
 }
 
-async fn authenticate_key(pool: &PgPool, raw_key: &str) -> Result<AuthenticatedUser, AuthError> {
-    // Hash the incoming key with SHA-256 to match against stored hash
-    use sha2::{Sha256, Digest};
-    let mut hasher = Sha256::new();
-    hasher.update(raw_key.as_bytes());
-    let key_hash = format!("{:x}", hasher.finalize());
-
-    // Look up in sentinel_api_keys table (matching frontend schema)
-    let key: Option<ApiKeyRow> = sqlx::query_as::<_, ApiKeyRow>(
-        r#"
-        SELECT id, wallet_address as user_id, key_hash
-        FROM sentinel_api_keys
-        WHERE key_hash = $1
-        "#
-    )
-    .bind(&key_hash)
-    .fetch_optional(pool)
-    .await
-    .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
-
-    match key {
-        Some(key) => {
-            // Update last_used_at
-            sqlx::query(
-                r#"UPDATE sentinel_api_keys SET last_used_at = NOW() WHERE id = $1"#
-            )
-            .bind(key.id)
-            .execute(pool)
-            .await
-            .ok();
-
-            Ok(AuthenticatedUser {
-                user_id: key.user_id,
-                api_key_id: key.id,
-            })
+async fn authenticate_key(
+    state: &AppState,
+    raw_key: &str,
+    client: &ClientInfo,
+) -> Result<AuthenticatedUser, AuthError> {
+    let pool = &state.db;
+    let Some(prefix) = lookup_prefix(raw_key) else {
+        return Err(AuthError::NotFound);
+    };
+
+    let candidates = db::get_api_keys_by_prefix(pool, prefix)
+        .await
+        .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+    let key = match_key(raw_key, candidates)?;
+    if db::is_api_key_expired(&key) {
+        // Recorded on the first rejected use only
+        let detail = key.expires_at.map(|at| format!("Expired at {}", at.to_rfc3339()));
+        record_event(pool, client.event(key.id, KeyEventKind::Expired, detail)).await;
+        return Err(AuthError::Expired);
+    }
+
+    // Keys belong to a Clerk user or, when minted by the dashboard, a wallet
+    let user_id = match (key.user_id, key.wallet_address.clone()) {
+        (Some(user_id), _) => user_id.to_string(),
+        (None, Some(wallet_address)) => wallet_address,
+        (None, None) => return Err(AuthError::NotFound),
+    };
+
+    // Keep cheap calls off the database: `last_used_at` is only as fresh as
+    // LAST_USED_RESOLUTION_SECS, and IPs seen lately are not announced again
+    if key.last_used_at.is_none_or(|at| Utc::now() - at > Duration::seconds(LAST_USED_RESOLUTION_SECS)) {
+        db::update_api_key_last_used(pool, key.id).await.ok();
+    }
+    if let Some(ip) = &client.ip {
+        let seen = cache::key_ip_key(key.id, ip);
+        if !matches!(state.cache.get(&seen).await, Ok(Some(_))) {
+            record_event(pool, client.event(key.id, KeyEventKind::UsedFromNewIp, None)).await;
+            if let Err(e) = state.cache.set(&seen, "1", ttl::KEY_IP).await {
+                tracing::warn!("Failed to cache IP seen for key {}: {}", key.id, e);
+            }
         }
-        None => Err(AuthError::NotFound),
     }
+
+    Ok(AuthenticatedUser {
+        user_id,
+        api_key_id: key.id,
+        scopes: KeyScopes::from_key(&key),
+    })
+}
+
+/// The candidate `raw_key` hashes to, unless it has been revoked
+fn match_key(raw_key: &str, candidates: Vec<ApiKey>) -> Result<ApiKey, AuthError> {
+    let key = candidates
+        .into_iter()
+        .find(|key| verify_api_key(raw_key, &key.key_hash))
+        .ok_or(AuthError::NotFound)?;
+    if key.revoked_at.is_some() {
+        return Err(AuthError::Revoked);
+    }
+    Ok(key)
 }
 
-#[derive(Debug, sqlx::FromRow)]
-struct ApiKeyRow {
-    id: Uuid,
-    user_id: String,
-    key_hash: String,
+/// Audit failures are logged rather than failing the request
+async fn record_event(pool: &PgPool, event: CreateKeyEvent) {
+    let (api_key_id, kind) = (event.api_key_id, event.kind);
+    match db::record_key_event(pool, event).await {
+        Ok(true) if kind == KeyEventKind::UsedFromNewIp => {
+            tracing::info!("API key {} used from a new IP", api_key_id);
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to record {} event for key {}: {}", kind.as_str(), api_key_id, e),
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -139,6 +268,102 @@ pub enum AuthError {
     NotFound,
     #[error("API key has expired")]
     Expired,
+    #[error("API key has been revoked")]
+    Revoked,
     #[error("Database error: {0}")]
     DatabaseError(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::auth::generate_api_key;
+
+    fn forwarded(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_forwarded_ip_trusts_only_proxy_hops() {
+        let headers = forwarded("6.6.6.6, 203.0.113.7, 10.0.0.1");
+        assert_eq!(forwarded_ip(&headers, TrustedProxies(0)), None);
+        // The client can prepend anything; the proxies' entries are on the right
+        assert_eq!(forwarded_ip(&headers, TrustedProxies(1)).as_deref(), Some("10.0.0.1"));
+        assert_eq!(forwarded_ip(&headers, TrustedProxies(2)).as_deref(), Some("203.0.113.7"));
+        assert_eq!(forwarded_ip(&headers, TrustedProxies(4)), None);
+
+        assert_eq!(forwarded_ip(&forwarded("not-an-ip"), TrustedProxies(1)), None);
+    }
+
+    /// A freshly minted key as it would be stored, and its raw value
+    fn stored_key() -> (String, ApiKey) {
+        let (raw_key, key_hash, key_prefix) = generate_api_key().unwrap();
+        let key = ApiKey {
+            id: Uuid::new_v4(),
+            user_id: Some(Uuid::new_v4()),
+            wallet_address: None,
+            name: "ci".to_string(),
+            key_hash,
+            key_prefix,
+            last_used_at: None,
+            created_at: Utc::now(),
+            expires_at: None,
+            revoked_at: None,
+            revoked_reason: None,
+            allowed_endpoints: None,
+            allowed_networks: None,
+            project_id: None,
+            access: "write".to_string(),
+            superseded_by: None,
+            replaces: None,
+        };
+        (raw_key, key)
+    }
+
+    #[test]
+    fn test_match_key() {
+        let (raw_key, key) = stored_key();
+        assert_eq!(lookup_prefix(&raw_key), Some(key.key_prefix.as_str()));
+
+        // Another key sharing the lookup prefix does not match
+        let (_, mut other) = stored_key();
+        other.key_prefix = key.key_prefix.clone();
+        let matched = match_key(&raw_key, vec![other.clone(), key.clone()]).unwrap();
+        assert_eq!(matched.id, key.id);
+
+        let forged = format!("{}{}", key.key_prefix, "x".repeat(raw_key.len() - key.key_prefix.len()));
+        assert_eq!(lookup_prefix(&forged), Some(key.key_prefix.as_str()));
+        assert!(matches!(match_key(&forged, vec![key.clone()]), Err(AuthError::NotFound)));
+        assert!(matches!(match_key(&raw_key, vec![other]), Err(AuthError::NotFound)));
+
+        let revoked = ApiKey {
+            revoked_at: Some(Utc::now()),
+            ..key
+        };
+        assert!(matches!(match_key(&raw_key, vec![revoked]), Err(AuthError::Revoked)));
+    }
+
+    #[test]
+    fn test_key_expiry() {
+        let (_, key) = stored_key();
+        assert!(!db::is_api_key_expired(&key));
+
+        let expiring = |at| ApiKey {
+            expires_at: Some(at),
+            ..key.clone()
+        };
+        assert!(!db::is_api_key_expired(&expiring(Utc::now() + Duration::minutes(1))));
+        assert!(db::is_api_key_expired(&expiring(Utc::now() - Duration::minutes(1))));
+    }
+
+    #[test]
+    fn test_client_info_falls_back_to_peer() {
+        let mut extensions = Extensions::new();
+        extensions.insert(ConnectInfo(SocketAddr::from(([192, 0, 2, 1], 443))));
+        let client = ClientInfo::new(&forwarded("203.0.113.7"), &extensions, TrustedProxies(0));
+        assert_eq!(client.ip.as_deref(), Some("192.0.2.1"));
+    }
+}
@@ -1,5 +1,11 @@
 mod api_key;
 mod middleware;
+mod rate_limit;
+mod scopes;
+mod session;
 
 pub use api_key::*;
 pub use middleware::*;
+pub use rate_limit::*;
+pub use scopes::*;
+pub use session::*;
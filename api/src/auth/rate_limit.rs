@@ -0,0 +1,215 @@
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use super::AuthenticatedUser;
+use crate::cache::{self, limits, ttl, CacheBackend, RateLimitResult};
+use crate::db;
+use crate::error::ApiError;
+use crate::usage::{self, QuotaStatus, UsageMeter};
+use crate::AppState;
+
+/// Rate limit tier, assigned per user in `rate_limit_tiers`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateLimitTier {
+    Free,
+    Pro,
+    Enterprise,
+}
+
+impl RateLimitTier {
+    /// Unknown names fall back to the free tier
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "pro" => RateLimitTier::Pro,
+            "enterprise" => RateLimitTier::Enterprise,
+            _ => RateLimitTier::Free,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateLimitTier::Free => "free",
+            RateLimitTier::Pro => "pro",
+            RateLimitTier::Enterprise => "enterprise",
+        }
+    }
+
+    /// Request units per window shared by all of a user's keys
+    pub fn user_limit(&self) -> u32 {
+        match self {
+            RateLimitTier::Free => limits::FREE_TIER,
+            RateLimitTier::Pro => limits::PRO_TIER,
+            RateLimitTier::Enterprise => limits::ENTERPRISE_TIER,
+        }
+    }
+
+    /// Request units per window for any single key, so one leaked or
+    /// runaway key cannot use up the whole account
+    pub fn key_limit(&self) -> u32 {
+        self.user_limit() / 2
+    }
+
+    /// Request units per calendar month (UTC); `None` is unmetered
+    pub fn monthly_quota(&self) -> Option<u64> {
+        match self {
+            RateLimitTier::Free => Some(limits::FREE_MONTHLY_QUOTA),
+            RateLimitTier::Pro => Some(limits::PRO_MONTHLY_QUOTA),
+            RateLimitTier::Enterprise => None,
+        }
+    }
+}
+
+/// Request units an endpoint costs, by how much work it does
+pub fn endpoint_weight(path: &str) -> u32 {
+    let path = path.strip_prefix("/api/v1").unwrap_or(path);
+    match path {
+        "/prove" | "/prove/stream" => 10,
+        "/compile" | "/test" | "/test/stream" => 5,
+        "/simulate/batch" | "/simulate/batch/stream" | "/scenarios/run" => 5,
+        "/trace" | "/analyze-gas" | "/prove/coverage" => 2,
+        _ => 1,
+    }
+}
+
+/// Middleware enforcing per-key and per-user rate limits and monthly quotas
+///
+/// Runs after `api_key_auth`. Each request spends its endpoint's weight
+/// from both the key's and the user's sliding window, then from the user's
+/// monthly quota; the tighter window is reported in the `X-RateLimit-*`
+/// headers. If the cache fails the request is let through rather than
+/// taking the API down with it.
+pub async fn rate_limit(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let Some(user) = request.extensions().get::<AuthenticatedUser>().cloned() else {
+        return next.run(request).await;
+    };
+
+    let weight = endpoint_weight(request.uri().path());
+    let tier = user_tier(&state, &user.user_id).await;
+    let cache = state.cache.as_ref();
+    let key_subject = format!("key:{}", user.api_key_id);
+    let user_subject = format!("user:{}", user.user_id);
+
+    let key = match check(cache, &key_subject, tier.key_limit(), weight).await {
+        Some(result) if !result.allowed => return limited(&result),
+        result => result,
+    };
+    let user_window = match check(cache, &user_subject, tier.user_limit(), weight).await {
+        Some(result) if !result.allowed => {
+            // The key's share was spent on a request that is not going ahead
+            if key.is_some() {
+                release(cache, &key_subject, weight).await;
+            }
+            return limited(&result);
+        }
+        result => result,
+    };
+    let quota = match usage::consume_quota(&state, &user.user_id, tier, weight).await {
+        Ok(quota) => quota,
+        Err(e) => {
+            for (subject, result) in [(&key_subject, &key), (&user_subject, &user_window)] {
+                if result.is_some() {
+                    release(cache, subject, weight).await;
+                }
+            }
+            return e.into_response();
+        }
+    };
+    if let Some(meter) = request.extensions().get::<UsageMeter>() {
+        meter.charge(weight);
+    }
+
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    let tightest = [key, user_window]
+        .into_iter()
+        .flatten()
+        .min_by_key(|result| result.remaining);
+    if let Some(result) = tightest {
+        set_headers(headers, &result);
+    }
+    if let Some(QuotaStatus { limit: Some(limit), remaining: Some(remaining), .. }) = quota {
+        headers.insert("x-quota-limit", HeaderValue::from(limit));
+        headers.insert("x-quota-remaining", HeaderValue::from(remaining));
+    }
+    response
+}
+
+async fn release(cache: &dyn CacheBackend, subject: &str, weight: u32) {
+    if let Err(e) = cache::release_rate_limit(cache, subject, weight, limits::WINDOW_SECS).await {
+        tracing::warn!("Failed to release rate limit for {}: {}", subject, e);
+    }
+}
+
+async fn check(cache: &dyn CacheBackend, subject: &str, limit: u32, weight: u32) -> Option<RateLimitResult> {
+    match cache::check_rate_limit(cache, subject, limit, weight, limits::WINDOW_SECS).await {
+        Ok(result) => Some(result),
+        Err(e) => {
+            tracing::warn!("Rate limit check failed for {}, allowing request: {}", subject, e);
+            None
+        }
+    }
+}
+
+/// The user's tier, cached briefly so it is not a query per request
+pub async fn user_tier(state: &AppState, user_id: &str) -> RateLimitTier {
+    let cache_key = cache::rate_limit_tier_key(user_id);
+    if let Ok(Some(name)) = state.cache.get(&cache_key).await {
+        return RateLimitTier::parse(&name);
+    }
+
+    let tier = match db::get_rate_limit_tier(&state.db, user_id).await {
+        Ok(name) => name.as_deref().map(RateLimitTier::parse).unwrap_or(RateLimitTier::Free),
+        Err(e) => {
+            tracing::warn!("Failed to load rate limit tier for {}: {}", user_id, e);
+            return RateLimitTier::Free;
+        }
+    };
+    if let Err(e) = state
+        .cache
+        .set(&cache_key, tier.as_str(), ttl::RATE_LIMIT_TIER)
+        .await
+    {
+        tracing::warn!("Failed to cache rate limit tier for {}: {}", user_id, e);
+    }
+    tier
+}
+
+fn limited(result: &RateLimitResult) -> Response {
+    let mut response = ApiError::RateLimited(result.retry_after).into_response();
+    set_headers(response.headers_mut(), result);
+    response
+}
+
+fn set_headers(headers: &mut HeaderMap, result: &RateLimitResult) {
+    headers.insert("x-ratelimit-limit", HeaderValue::from(result.limit));
+    headers.insert("x-ratelimit-remaining", HeaderValue::from(result.remaining));
+    headers.insert("x-ratelimit-reset", HeaderValue::from(result.reset_at));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_weight() {
+        assert_eq!(endpoint_weight("/simulate"), 1);
+        assert_eq!(endpoint_weight("/api/v1/prove"), 10);
+        assert!(endpoint_weight("/prove") > endpoint_weight("/simulate"));
+        assert_eq!(endpoint_weight("/simulate/batch"), 5);
+        assert_eq!(endpoint_weight("/networks"), 1);
+    }
+
+    #[test]
+    fn test_tier_limits() {
+        assert_eq!(RateLimitTier::parse("pro"), RateLimitTier::Pro);
+        assert_eq!(RateLimitTier::parse("gold"), RateLimitTier::Free);
+        assert_eq!(RateLimitTier::Free.user_limit(), limits::FREE_TIER);
+        assert!(RateLimitTier::Free.key_limit() < RateLimitTier::Free.user_limit());
+        // The heaviest endpoint fits in every key's budget
+        assert!(RateLimitTier::Free.key_limit() >= endpoint_weight("/prove"));
+    }
+}
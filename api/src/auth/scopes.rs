@@ -0,0 +1,323 @@
+//! API key scopes
+//!
+//! A key can be limited to some endpoint groups, some networks and one
+//! project, and to read-only use of the management routes. Unset limits
+//! allow everything, so keys minted before scopes existed keep working.
+//! `require_scopes` checks what the route alone decides; networks are in
+//! request bodies, so handlers check them through the `KeyScopes` extractor.
+
+use axum::{
+    extract::{FromRequestParts, MatchedPath, Request},
+    http::{request::Parts, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use uuid::Uuid;
+
+use super::AuthenticatedUser;
+use crate::db::ApiKey;
+use crate::error::ApiError;
+use crate::network::NetworkRegistry;
+
+/// Endpoint groups a key can be allowed to call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EndpointScope {
+    Simulate,
+    Trace,
+    /// The prover and everything else that runs the Move toolchain
+    Prove,
+    Gas,
+    Batch,
+}
+
+impl EndpointScope {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "simulate" => Some(EndpointScope::Simulate),
+            "trace" => Some(EndpointScope::Trace),
+            "prove" => Some(EndpointScope::Prove),
+            "gas" => Some(EndpointScope::Gas),
+            "batch" => Some(EndpointScope::Batch),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EndpointScope::Simulate => "simulate",
+            EndpointScope::Trace => "trace",
+            EndpointScope::Prove => "prove",
+            EndpointScope::Gas => "gas",
+            EndpointScope::Batch => "batch",
+        }
+    }
+}
+
+/// Access to the management routes (usage, networks, caches, reports)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyAccess {
+    Read,
+    #[default]
+    Write,
+}
+
+impl KeyAccess {
+    /// Unknown names fall back to read-only
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "write" => KeyAccess::Write,
+            _ => KeyAccess::Read,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyAccess::Read => "read",
+            KeyAccess::Write => "write",
+        }
+    }
+}
+
+/// What a route needs from a key
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RouteScope {
+    Endpoint(EndpointScope),
+    Read,
+    Write,
+}
+
+/// The scope a protected route needs
+pub fn route_scope(method: &Method, path: &str) -> RouteScope {
+    let path = path.strip_prefix("/api/v1").unwrap_or(path);
+    match path {
+        "/simulate" => RouteScope::Endpoint(EndpointScope::Simulate),
+        "/simulate/batch" | "/simulate/batch/stream" | "/scenarios/run" => {
+            RouteScope::Endpoint(EndpointScope::Batch)
+        }
+        "/trace" => RouteScope::Endpoint(EndpointScope::Trace),
+        "/prove" | "/prove/stream" | "/prove/coverage" | "/compile" | "/test" | "/test/stream" => {
+            RouteScope::Endpoint(EndpointScope::Prove)
+        }
+        "/analyze-gas" => RouteScope::Endpoint(EndpointScope::Gas),
+        // Only renders what it is given or what the key can already read
+        "/reports/markdown" => RouteScope::Read,
+        _ if method == Method::GET || method == Method::HEAD => RouteScope::Read,
+        _ => RouteScope::Write,
+    }
+}
+
+/// What an API key may do; `None` limits allow everything
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KeyScopes {
+    #[serde(default)]
+    pub endpoints: Option<Vec<EndpointScope>>,
+    #[serde(default)]
+    pub networks: Option<Vec<String>>,
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+    #[serde(default)]
+    pub access: KeyAccess,
+}
+
+impl KeyScopes {
+    pub fn from_key(key: &ApiKey) -> Self {
+        Self::from_columns(
+            key.allowed_endpoints.as_deref(),
+            key.allowed_networks.clone(),
+            key.project_id,
+            &key.access,
+        )
+    }
+
+    /// From the `api_keys` scope columns
+    pub fn from_columns(
+        endpoints: Option<&[String]>,
+        networks: Option<Vec<String>>,
+        project_id: Option<Uuid>,
+        access: &str,
+    ) -> Self {
+        Self {
+            // The column is constrained to known names
+            endpoints: endpoints.map(|names| names.iter().filter_map(|n| EndpointScope::parse(n)).collect()),
+            networks,
+            project_id,
+            access: KeyAccess::parse(access),
+        }
+    }
+
+    /// `allowed_endpoints` column value
+    pub fn endpoint_names(&self) -> Option<Vec<&'static str>> {
+        self.endpoints
+            .as_ref()
+            .map(|endpoints| endpoints.iter().map(EndpointScope::as_str).collect())
+    }
+
+    /// Rejects scopes that could never be used, before a key is minted
+    pub fn validate(&self, networks: &NetworkRegistry) -> Result<(), ApiError> {
+        if self.endpoints.as_ref().is_some_and(Vec::is_empty) {
+            return Err(ApiError::BadRequest(
+                "scopes.endpoints must not be empty; omit it to allow every endpoint".to_string(),
+            ));
+        }
+        match &self.networks {
+            Some(names) if names.is_empty() => Err(ApiError::BadRequest(
+                "scopes.networks must not be empty; omit it to allow every network".to_string(),
+            )),
+            Some(names) => networks.check(names.iter().map(String::as_str)),
+            None => Ok(()),
+        }
+    }
+
+    pub fn check_route(&self, route: RouteScope) -> Result<(), ApiError> {
+        match route {
+            RouteScope::Endpoint(endpoint) => match &self.endpoints {
+                Some(allowed) if !allowed.contains(&endpoint) => Err(ApiError::Forbidden(format!(
+                    "API key is not allowed to call {} endpoints",
+                    endpoint.as_str()
+                ))),
+                _ => Ok(()),
+            },
+            RouteScope::Write if self.access == KeyAccess::Read => {
+                Err(ApiError::Forbidden("API key is read-only".to_string()))
+            }
+            RouteScope::Read | RouteScope::Write => Ok(()),
+        }
+    }
+
+    pub fn check_network(&self, network: &str) -> Result<(), ApiError> {
+        match &self.networks {
+            Some(allowed) if !allowed.iter().any(|n| n == network) => Err(ApiError::Forbidden(format!(
+                "API key is not allowed to use network '{}'",
+                network
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Rejects the first network the key may not use
+    pub fn check_networks<'a>(&self, networks: impl IntoIterator<Item = &'a str>) -> Result<(), ApiError> {
+        networks.into_iter().try_for_each(|network| self.check_network(network))
+    }
+}
+
+/// The calling key's scopes; unrestricted outside `api_key_auth`
+impl<S: Send + Sync> FromRequestParts<S> for KeyScopes {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<AuthenticatedUser>()
+            .map(|user| user.scopes.clone())
+            .unwrap_or_default())
+    }
+}
+
+/// Middleware rejecting routes outside the API key's scopes
+///
+/// Runs after `api_key_auth` and `track_usage`, and before `rate_limit`,
+/// so a forbidden call is recorded but does not spend the key's budget.
+pub async fn require_scopes(request: Request, next: Next) -> Response {
+    let Some(user) = request.extensions().get::<AuthenticatedUser>() else {
+        return next.run(request).await;
+    };
+
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str())
+        .unwrap_or_else(|| request.uri().path());
+    let route = route_scope(request.method(), path);
+    if let Err(e) = user.scopes.check_route(route) {
+        tracing::info!("API key {} denied {} {}: {}", user.api_key_id, request.method(), path, e);
+        return e.into_response();
+    }
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ci_key() -> KeyScopes {
+        KeyScopes {
+            endpoints: Some(vec![EndpointScope::Simulate, EndpointScope::Batch]),
+            networks: Some(vec!["testnet".to_string()]),
+            project_id: None,
+            access: KeyAccess::Read,
+        }
+    }
+
+    #[test]
+    fn test_route_scope() {
+        assert_eq!(
+            route_scope(&Method::POST, "/api/v1/prove/stream"),
+            RouteScope::Endpoint(EndpointScope::Prove)
+        );
+        assert_eq!(
+            route_scope(&Method::POST, "/scenarios/run"),
+            RouteScope::Endpoint(EndpointScope::Batch)
+        );
+        assert_eq!(route_scope(&Method::GET, "/api/v1/usage"), RouteScope::Read);
+        assert_eq!(
+            route_scope(&Method::DELETE, "/api/v1/cache/modules/{network}/{address}"),
+            RouteScope::Write
+        );
+    }
+
+    #[test]
+    fn test_scoped_key() {
+        let scopes = ci_key();
+        assert!(scopes.check_route(RouteScope::Endpoint(EndpointScope::Simulate)).is_ok());
+        assert!(matches!(
+            scopes.check_route(RouteScope::Endpoint(EndpointScope::Prove)),
+            Err(ApiError::Forbidden(_))
+        ));
+        assert!(scopes.check_route(RouteScope::Read).is_ok());
+        assert!(scopes.check_route(RouteScope::Write).is_err());
+
+        assert!(scopes.check_networks(["testnet", "testnet"]).is_ok());
+        assert!(scopes.check_networks(["testnet", "mainnet"]).is_err());
+    }
+
+    #[test]
+    fn test_unscoped_key_allows_everything() {
+        let scopes = KeyScopes::from_columns(None, None, None, "write");
+        assert_eq!(scopes, KeyScopes::default());
+        assert!(scopes.check_route(RouteScope::Endpoint(EndpointScope::Prove)).is_ok());
+        assert!(scopes.check_route(RouteScope::Write).is_ok());
+        assert!(scopes.check_network("mainnet").is_ok());
+    }
+
+    #[test]
+    fn test_validate() {
+        let networks = NetworkRegistry::builtin(None);
+        assert!(ci_key().validate(&networks).is_ok());
+
+        let unknown = KeyScopes {
+            networks: Some(vec!["devnet-42".to_string()]),
+            ..KeyScopes::default()
+        };
+        assert!(unknown.validate(&networks).is_err());
+
+        let empty = KeyScopes {
+            endpoints: Some(Vec::new()),
+            ..KeyScopes::default()
+        };
+        assert!(empty.validate(&networks).is_err());
+    }
+
+    #[test]
+    fn test_scopes_from_json() {
+        let scopes: KeyScopes =
+            serde_json::from_str(r#"{"endpoints": ["simulate", "batch"], "networks": ["testnet"], "access": "read"}"#)
+                .unwrap();
+        assert_eq!(scopes, ci_key());
+        assert!(serde_json::from_str::<KeyScopes>(r#"{"endpoints": ["deploy"]}"#).is_err());
+    }
+}
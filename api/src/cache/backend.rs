@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+
+#[derive(Debug, thiserror::Error)]
+#[error("Cache error: {0}")]
+pub struct CacheError(pub String);
+
+impl From<redis::RedisError> for CacheError {
+    fn from(e: redis::RedisError) -> Self {
+        CacheError(e.to_string())
+    }
+}
+
+/// Key-value storage with expiry, shared by every cache and the rate limiter
+///
+/// Values are strings (callers store JSON); every key has a TTL.
+#[async_trait]
+pub trait CacheBackend: Send + Sync {
+    async fn get(&self, key: &str) -> Result<Option<String>, CacheError>;
+
+    async fn set(&self, key: &str, value: &str, ttl_secs: u64) -> Result<(), CacheError>;
+
+    /// Removes keys, returning how many existed
+    async fn delete(&self, keys: &[String]) -> Result<usize, CacheError>;
+
+    /// Removes every key starting with `prefix`, returning how many existed
+    async fn delete_prefix(&self, prefix: &str) -> Result<usize, CacheError>;
+
+    /// Adds `amount` (which may be negative) to a counter that expires
+    /// `window_secs` after its first increment, returning the new count and
+    /// the seconds left in the window
+    async fn incr(&self, key: &str, amount: i64, window_secs: u64) -> Result<(i64, u64), CacheError>;
+
+    async fn ping(&self) -> Result<(), CacheError>;
+}
@@ -1,10 +1,16 @@
 //! Cache key generation utilities
 //!
 //! Key patterns:
-//! - sim:{network}:{hash}    - Simulation result (24h TTL)
-//! - mod:{network}:{address} - Module ABI (1h TTL)
-//! - acc:{network}:{address} - Account resources (5min TTL)
-//! - rate:{user_id}          - Rate limit counter (1min TTL)
+//! - sim:{network}:{hash}    - Ledger-pinned simulation result (24h TTL)
+//! - mod:{network}:{address}::{module} - Module ABI (1h TTL)
+//! - acc:{network}:{address} - Account sequence number (5min TTL)
+//! - prove:{hash}            - Prover result (24h TTL)
+//! - rate:{subject}:{window} - Sliding-window rate limit counter (2 windows TTL)
+//! - tier:{user_id}          - Rate limit tier assignment (5min TTL)
+//! - quota:{user_id}:{month} - Monthly quota units used (1h TTL, then recounted)
+//! - keyip:{key_id}:{ip}     - IP an API key was used from lately (10min TTL)
+
+use uuid::Uuid;
 
 /// TTL values in seconds
 pub mod ttl {
@@ -12,7 +18,10 @@ pub mod ttl {
     pub const SIMULATION_FAILED: u64 = 300; // 5 minutes
     pub const MODULE_ABI: u64 = 3600; // 1 hour
     pub const ACCOUNT_STATE: u64 = 300; // 5 minutes
-    pub const RATE_LIMIT: u64 = 60; // 1 minute
+    pub const RATE_LIMIT_TIER: u64 = 300; // 5 minutes
+    pub const QUOTA: u64 = 3600; // 1 hour
+    pub const PROVER_RESULT: u64 = 86400; // 24 hours
+    pub const KEY_IP: u64 = 600; // 10 minutes
 }
 
 /// Generates a cache key for simulation results
@@ -30,11 +39,31 @@ pub fn account_key(network: &str, address: &str) -> String {
     format!("acc:{}:{}", network, address)
 }
 
+/// Generates a cache key for prover results
+pub fn prover_key(request_hash: &str) -> String {
+    format!("prove:{}", request_hash)
+}
+
 /// Generates a cache key for rate limiting
 pub fn rate_limit_key(user_id: &str) -> String {
     format!("rate:{}", user_id)
 }
 
+/// Generates a cache key for a user's quota usage in a month ("2026-10")
+pub fn quota_key(user_id: &str, month: &str) -> String {
+    format!("quota:{}:{}", user_id, month)
+}
+
+/// Generates a cache key for a user's rate limit tier
+pub fn rate_limit_tier_key(user_id: &str) -> String {
+    format!("tier:{}", user_id)
+}
+
+/// Generates a cache key for an IP an API key was used from
+pub fn key_ip_key(key_id: Uuid, ip: &str) -> String {
+    format!("keyip:{}:{}", key_id, ip)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -51,6 +80,12 @@ mod tests {
         assert_eq!(key, "mod:mainnet:0x1::coin");
     }
 
+    #[test]
+    fn test_prover_key() {
+        let key = prover_key("abc123");
+        assert_eq!(key, "prove:abc123");
+    }
+
     #[test]
     fn test_rate_limit_key() {
         let key = rate_limit_key("user_abc123");
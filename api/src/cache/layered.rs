@@ -0,0 +1,183 @@
+use async_trait::async_trait;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use super::backend::{CacheBackend, CacheError};
+use super::memory::MemoryCache;
+use super::redis_store::RedisCache;
+use super::create_redis_pool;
+
+/// How often to try reaching Redis again while it is down
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Time allowed for establishing a Redis connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Which backend `CACHE_BACKEND` selects
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CacheMode {
+    /// Redis, with the in-memory cache standing in while it is unreachable
+    Redis,
+    /// In-memory only
+    Memory,
+}
+
+/// The application cache: Redis when reachable, otherwise process memory
+///
+/// A failed Redis call marks Redis as down and is served from memory; a
+/// background task reconnects and switches back once Redis answers again.
+/// Entries written during an outage stay in memory and are not copied over.
+pub struct Cache {
+    redis_url: Option<String>,
+    redis: RwLock<Option<RedisCache>>,
+    memory: MemoryCache,
+}
+
+impl Cache {
+    /// Connects according to the configured mode; an unreachable Redis
+    /// starts in fallback rather than failing startup
+    pub async fn connect(mode: CacheMode, redis_url: &str, memory_capacity: usize) -> Arc<Self> {
+        let redis_url = (mode == CacheMode::Redis).then(|| redis_url.to_string());
+        let redis = match &redis_url {
+            Some(url) => match connect_redis(url).await {
+                Ok(redis) => {
+                    tracing::info!("Redis connection established");
+                    Some(redis)
+                }
+                Err(e) => {
+                    tracing::warn!("Redis not available, using in-memory cache until it is: {}", e);
+                    None
+                }
+            },
+            None => {
+                tracing::info!("Using in-memory cache");
+                None
+            }
+        };
+
+        let cache = Arc::new(Self {
+            redis_url,
+            redis: RwLock::new(redis),
+            memory: MemoryCache::new(memory_capacity),
+        });
+        if cache.redis_url.is_some() {
+            cache.spawn_reconnect();
+        }
+        cache
+    }
+
+    /// A memory-only cache
+    pub fn memory(capacity: usize) -> Arc<Self> {
+        Arc::new(Self {
+            redis_url: None,
+            redis: RwLock::new(None),
+            memory: MemoryCache::new(capacity),
+        })
+    }
+
+    /// "redis", "memory (redis unavailable)" or "memory"
+    pub fn status(&self) -> &'static str {
+        match (&self.redis_url, self.redis().is_some()) {
+            (Some(_), true) => "redis",
+            (Some(_), false) => "memory (redis unavailable)",
+            (None, _) => "memory",
+        }
+    }
+
+    fn redis(&self) -> Option<RedisCache> {
+        self.redis.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn mark_down(&self, error: &CacheError) {
+        let mut redis = self.redis.write().unwrap_or_else(|e| e.into_inner());
+        if redis.take().is_some() {
+            tracing::warn!("Redis failed, falling back to in-memory cache: {}", error);
+        }
+    }
+
+    fn spawn_reconnect(self: &Arc<Self>) {
+        let cache = Arc::downgrade(self);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(RECONNECT_INTERVAL).await;
+                let Some(cache) = cache.upgrade() else {
+                    return;
+                };
+                let Some(url) = cache.redis_url.as_deref().filter(|_| cache.redis().is_none()) else {
+                    continue;
+                };
+
+                if let Ok(redis) = connect_redis(url).await {
+                    tracing::info!("Redis reachable again, switching back from in-memory cache");
+                    *cache.redis.write().unwrap_or_else(|e| e.into_inner()) = Some(redis);
+                }
+            }
+        });
+    }
+}
+
+async fn connect_redis(url: &str) -> Result<RedisCache, CacheError> {
+    let pool = tokio::time::timeout(CONNECT_TIMEOUT, create_redis_pool(url))
+        .await
+        .map_err(|_| CacheError("Timed out connecting to Redis".to_string()))??;
+    let redis = RedisCache::new(pool);
+    redis.ping().await?;
+    Ok(redis)
+}
+
+/// Runs an operation on Redis, or on memory if Redis is down or fails
+macro_rules! with_backend {
+    ($self:ident, $backend:ident => $op:expr) => {{
+        if let Some($backend) = $self.redis() {
+            match $op {
+                Ok(value) => return Ok(value),
+                Err(e) => $self.mark_down(&e),
+            }
+        }
+        let $backend = &$self.memory;
+        $op
+    }};
+}
+
+#[async_trait]
+impl CacheBackend for Cache {
+    async fn get(&self, key: &str) -> Result<Option<String>, CacheError> {
+        with_backend!(self, backend => backend.get(key).await)
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl_secs: u64) -> Result<(), CacheError> {
+        with_backend!(self, backend => backend.set(key, value, ttl_secs).await)
+    }
+
+    async fn delete(&self, keys: &[String]) -> Result<usize, CacheError> {
+        with_backend!(self, backend => backend.delete(keys).await)
+    }
+
+    async fn delete_prefix(&self, prefix: &str) -> Result<usize, CacheError> {
+        with_backend!(self, backend => backend.delete_prefix(prefix).await)
+    }
+
+    async fn incr(&self, key: &str, amount: i64, window_secs: u64) -> Result<(i64, u64), CacheError> {
+        with_backend!(self, backend => backend.incr(key, amount, window_secs).await)
+    }
+
+    async fn ping(&self) -> Result<(), CacheError> {
+        with_backend!(self, backend => backend.ping().await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unreachable_redis_falls_back_to_memory() {
+        // Nothing listens on port 1
+        let cache = Cache::connect(CacheMode::Redis, "redis://127.0.0.1:1", 16).await;
+        assert_eq!(cache.status(), "memory (redis unavailable)");
+
+        cache.set("k", "v", 60).await.unwrap();
+        assert_eq!(cache.get("k").await.unwrap().as_deref(), Some("v"));
+        assert_eq!(Cache::memory(16).status(), "memory");
+    }
+}
@@ -0,0 +1,177 @@
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::backend::{CacheBackend, CacheError};
+
+struct Entry {
+    value: String,
+    expires_at: Instant,
+    /// Position in the recency order
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Lru {
+    entries: HashMap<String, Entry>,
+    /// `last_used` tick to key, oldest first
+    order: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl Lru {
+    /// Live entry for a key, marked as most recently used; expired ones are dropped
+    fn touch(&mut self, key: &str, now: Instant) -> Option<&mut Entry> {
+        let expired = self.entries.get(key)?.expires_at <= now;
+        if expired {
+            self.remove(key);
+            return None;
+        }
+
+        self.tick += 1;
+        let tick = self.tick;
+        let entry = self.entries.get_mut(key)?;
+        self.order.remove(&entry.last_used);
+        self.order.insert(tick, key.to_string());
+        entry.last_used = tick;
+        Some(entry)
+    }
+
+    fn insert(&mut self, key: &str, value: String, expires_at: Instant, capacity: usize) {
+        self.remove(key);
+        self.tick += 1;
+        self.order.insert(self.tick, key.to_string());
+        self.entries.insert(
+            key.to_string(),
+            Entry {
+                value,
+                expires_at,
+                last_used: self.tick,
+            },
+        );
+
+        while self.entries.len() > capacity {
+            let Some((_, oldest)) = self.order.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+
+    fn remove(&mut self, key: &str) -> bool {
+        match self.entries.remove(key) {
+            Some(entry) => {
+                self.order.remove(&entry.last_used);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// In-process LRU cache with per-key TTL
+///
+/// Used when Redis is not configured and as the fallback while it is
+/// unreachable. Data is per process, so multi-node deploys should run Redis.
+pub struct MemoryCache {
+    capacity: usize,
+    lru: Mutex<Lru>,
+}
+
+impl MemoryCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            lru: Mutex::new(Lru::default()),
+        }
+    }
+
+    fn lru(&self) -> std::sync::MutexGuard<'_, Lru> {
+        self.lru.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl CacheBackend for MemoryCache {
+    async fn get(&self, key: &str) -> Result<Option<String>, CacheError> {
+        Ok(self.lru().touch(key, Instant::now()).map(|e| e.value.clone()))
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl_secs: u64) -> Result<(), CacheError> {
+        let expires_at = Instant::now() + Duration::from_secs(ttl_secs);
+        self.lru().insert(key, value.to_string(), expires_at, self.capacity);
+        Ok(())
+    }
+
+    async fn delete(&self, keys: &[String]) -> Result<usize, CacheError> {
+        let mut lru = self.lru();
+        Ok(keys.iter().filter(|key| lru.remove(key)).count())
+    }
+
+    async fn delete_prefix(&self, prefix: &str) -> Result<usize, CacheError> {
+        let mut lru = self.lru();
+        let matched: Vec<String> = lru
+            .entries
+            .keys()
+            .filter(|key| key.starts_with(prefix))
+            .cloned()
+            .collect();
+        Ok(matched.iter().filter(|key| lru.remove(key)).count())
+    }
+
+    async fn incr(&self, key: &str, amount: i64, window_secs: u64) -> Result<(i64, u64), CacheError> {
+        let now = Instant::now();
+        let mut lru = self.lru();
+
+        let (count, expires_at) = match lru.touch(key, now) {
+            Some(entry) => (entry.value.parse::<i64>().unwrap_or(0) + amount, entry.expires_at),
+            None => (amount, now + Duration::from_secs(window_secs)),
+        };
+        lru.insert(key, count.to_string(), expires_at, self.capacity);
+
+        Ok((count, expires_at.duration_since(now).as_secs_f64().ceil() as u64))
+    }
+
+    async fn ping(&self) -> Result<(), CacheError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_lru_eviction_and_ttl() {
+        let cache = MemoryCache::new(2);
+        cache.set("a", "1", 60).await.unwrap();
+        cache.set("b", "2", 60).await.unwrap();
+
+        // Reading "a" makes "b" the least recently used
+        assert_eq!(cache.get("a").await.unwrap().as_deref(), Some("1"));
+        cache.set("c", "3", 60).await.unwrap();
+        assert!(cache.get("b").await.unwrap().is_none());
+        assert_eq!(cache.get("c").await.unwrap().as_deref(), Some("3"));
+
+        assert_eq!(cache.delete_prefix("c").await.unwrap(), 1);
+        assert_eq!(cache.delete(&["a".to_string(), "z".to_string()]).await.unwrap(), 1);
+
+        cache.set("expired", "x", 0).await.unwrap();
+        assert!(cache.get("expired").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_incr_window() {
+        let cache = MemoryCache::new(10);
+        assert_eq!(cache.incr("rate", 1, 60).await.unwrap(), (1, 60));
+        let (count, ttl) = cache.incr("rate", 5, 60).await.unwrap();
+        assert_eq!(count, 6);
+        assert!(ttl <= 60 && ttl > 0);
+        assert_eq!(cache.incr("rate", -5, 60).await.unwrap().0, 1);
+
+        // An elapsed window starts over
+        cache.incr("burst", 1, 0).await.unwrap();
+        assert_eq!(cache.incr("burst", 1, 0).await.unwrap().0, 1);
+    }
+}
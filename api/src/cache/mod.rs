@@ -1,13 +1,25 @@
 //! Redis caching module for Sentinel API
 //!
-//! Provides caching for simulation results, module ABIs, and rate limiting.
+//! Provides caching for simulation results, prover results, module ABIs, and
+//! rate limiting. Everything goes through `CacheBackend`; the app uses
+//! `Cache`, which prefers Redis and falls back to an in-memory LRU.
 
+mod backend;
 mod keys;
+mod layered;
+mod memory;
 mod pool;
+mod prover;
 mod rate_limit;
+mod redis_store;
 mod simulation;
 
+pub use backend::*;
 pub use keys::*;
+pub use layered::*;
+#[cfg(test)]
+pub use memory::MemoryCache;
 pub use pool::*;
+pub use prover::*;
 pub use rate_limit::*;
 pub use simulation::*;
@@ -1,40 +1,28 @@
-use redis::aio::MultiplexedConnection;
+use redis::aio::{ConnectionManager, ConnectionManagerConfig};
 use redis::Client;
-use std::sync::Arc;
-use tokio::sync::{Mutex, OnceCell};
+use std::time::Duration;
 
-/// Redis connection type with interior mutability
-pub type RedisPool = Arc<Mutex<MultiplexedConnection>>;
-
-static REDIS_POOL: OnceCell<RedisPool> = OnceCell::const_new();
-
-/// Creates a Redis connection from environment
-pub async fn create_redis_pool() -> Result<RedisPool, redis::RedisError> {
-    let redis_url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".into());
+/// Redis connection shared by every task
+///
+/// A multiplexed connection that reconnects on its own; clones are cheap and
+/// share the connection, so callers clone instead of locking.
+pub type RedisPool = ConnectionManager;
 
+/// Creates a Redis connection
+pub async fn create_redis_pool(redis_url: &str) -> Result<RedisPool, redis::RedisError> {
     let client = Client::open(redis_url)?;
-    let conn = client.get_multiplexed_async_connection().await?;
-
-    Ok(Arc::new(Mutex::new(conn)))
-}
+    let config = ConnectionManagerConfig::new()
+        .set_connection_timeout(Duration::from_secs(5))
+        // Fail fast so callers fall back to the in-memory cache
+        .set_response_timeout(Duration::from_secs(2))
+        .set_number_of_retries(2);
 
-/// Gets or creates the global Redis pool
-pub async fn get_redis_pool() -> Result<RedisPool, redis::RedisError> {
-    match REDIS_POOL.get() {
-        Some(pool) => Ok(pool.clone()),
-        None => {
-            let pool = create_redis_pool().await?;
-            let _ = REDIS_POOL.set(pool.clone());
-            Ok(pool)
-        }
-    }
+    ConnectionManager::new_with_config(client, config).await
 }
 
 /// Health check for Redis connection
 pub async fn redis_health_check(pool: &RedisPool) -> bool {
-    let mut conn = pool.lock().await;
-    let result: Result<String, _> = redis::cmd("PING")
-        .query_async(&mut *conn)
-        .await;
+    let mut conn = pool.clone();
+    let result: Result<String, _> = redis::cmd("PING").query_async(&mut conn).await;
     result.is_ok()
 }
@@ -0,0 +1,144 @@
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+use super::backend::{CacheBackend, CacheError};
+use super::keys;
+use super::simulation::hex;
+use crate::move_package::move_toml_for;
+use crate::prover::{ProverRequest, ProverResult};
+
+/// Bump to invalidate every cached prover result after output format changes
+const PROVER_CACHE_VERSION: &str = "v1";
+
+/// Generates a deterministic hash of a prover request for caching
+///
+/// Covers the module sources, the generated Move.toml (which pins the
+/// framework revision), injected specs and prover options.
+pub fn hash_prover_request(request: &ProverRequest) -> String {
+    let mut hasher = Sha256::new();
+
+    hasher.update(PROVER_CACHE_VERSION.as_bytes());
+    hasher.update(request.module_name.as_bytes());
+    hasher.update(move_toml_for(&request.move_code).as_bytes());
+    hasher.update(request.move_code.as_bytes());
+
+    // Hash specs as JSON
+    if let Ok(specs_json) = serde_json::to_string(&request.specs) {
+        hasher.update(specs_json.as_bytes());
+    }
+
+    // Hash prover options as JSON
+    if let Ok(options_json) = serde_json::to_string(&request.options) {
+        hasher.update(options_json.as_bytes());
+    }
+
+    let result = hasher.finalize();
+    hex::encode(result)
+}
+
+/// Gets a cached prover result
+pub async fn get_cached_prover_result(cache: &dyn CacheBackend, request_hash: &str) -> Option<String> {
+    let key = keys::prover_key(request_hash);
+    cache.get(&key).await.ok().flatten()
+}
+
+/// Caches a prover result
+pub async fn cache_prover_result(
+    cache: &dyn CacheBackend,
+    request_hash: &str,
+    result: &str,
+) -> Result<(), CacheError> {
+    let key = keys::prover_key(request_hash);
+    cache.set(&key, result, keys::ttl::PROVER_RESULT).await
+}
+
+/// Typed prover result cache
+pub struct ProverCache {
+    cache: Arc<dyn CacheBackend>,
+}
+
+impl ProverCache {
+    pub fn new(cache: Arc<dyn CacheBackend>) -> Self {
+        Self { cache }
+    }
+
+    /// Looks up a previously stored result for the given request hash
+    pub async fn get(&self, request_hash: &str) -> Option<ProverResult> {
+        let cached = get_cached_prover_result(self.cache.as_ref(), request_hash).await?;
+        match serde_json::from_str(&cached) {
+            Ok(result) => Some(result),
+            Err(e) => {
+                tracing::warn!("Discarding unreadable cached prover result: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Stores a result for the given request hash
+    pub async fn put(&self, request_hash: &str, result: &ProverResult) {
+        let json = match serde_json::to_string(result) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::warn!("Failed to serialize prover result for cache: {}", e);
+                return;
+            }
+        };
+
+        if let Err(e) = cache_prover_result(self.cache.as_ref(), request_hash, &json).await {
+            tracing::warn!("Prover cache write failed: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::MemoryCache;
+    use crate::prover::ProverStatus;
+
+    fn request(code: &str) -> ProverRequest {
+        serde_json::from_value(serde_json::json!({
+            "move_code": code,
+            "module_name": "counter",
+        }))
+        .unwrap()
+    }
+
+    fn result() -> ProverResult {
+        ProverResult {
+            status: ProverStatus::Passed,
+            duration_ms: 1200,
+            modules: vec![],
+            summary: "Module counter verified successfully".to_string(),
+            raw_output: None,
+            cached: false,
+            run_id: None,
+        }
+    }
+
+    #[test]
+    fn test_hash_prover_request() {
+        let a = hash_prover_request(&request("module 0x1::counter {}"));
+        let b = hash_prover_request(&request("module 0x1::counter {}"));
+        let c = hash_prover_request(&request("module 0x1::counter { fun f() {} }"));
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(a.len(), 64);
+
+        let mut with_options = request("module 0x1::counter {}");
+        with_options.options.vc_timeout = Some(30);
+        assert_ne!(a, hash_prover_request(&with_options));
+    }
+
+    #[tokio::test]
+    async fn test_local_fallback() {
+        let cache = ProverCache::new(Arc::new(MemoryCache::new(16)));
+        assert!(cache.get("abc").await.is_none());
+
+        cache.put("abc", &result()).await;
+        let cached = cache.get("abc").await.unwrap();
+        assert_eq!(cached.status, ProverStatus::Passed);
+        assert_eq!(cached.duration_ms, 1200);
+    }
+}
@@ -1,87 +1,176 @@
-use redis::AsyncCommands;
-
-use super::{keys, RedisPool};
+use super::backend::{CacheBackend, CacheError};
+use super::keys;
 
 /// Result of a rate limit check
 #[derive(Debug, Clone)]
 pub struct RateLimitResult {
     /// Whether the request is allowed
     pub allowed: bool,
-    /// Remaining requests in the current window
+    /// Remaining request units in the sliding window
     pub remaining: u32,
     /// Total limit for the window
     pub limit: u32,
-    /// Unix timestamp when the window resets
+    /// Unix timestamp when the current window ends
     pub reset_at: u64,
+    /// Seconds until a denied request would fit; 0 when allowed
+    pub retry_after: u64,
 }
 
-/// Checks rate limit for a user
+/// Checks and consumes `weight` units of a subject's budget
 ///
-/// Uses Redis INCR with EXPIRE for atomic counter
+/// Uses a sliding window: the count is the current fixed window plus the
+/// previous one scaled by how much of it still overlaps the last
+/// `window_secs`. Denied requests are not counted, so a client that backs
+/// off for `retry_after` seconds gets through.
 pub async fn check_rate_limit(
-    pool: &RedisPool,
-    user_id: &str,
+    cache: &dyn CacheBackend,
+    subject: &str,
     limit: u32,
+    weight: u32,
     window_secs: u64,
-) -> Result<RateLimitResult, redis::RedisError> {
-    let key = keys::rate_limit_key(user_id);
-    let mut conn = pool.lock().await;
-    let conn_ref = &mut *conn;
+) -> Result<RateLimitResult, CacheError> {
+    let window_secs = window_secs.max(1);
+    let now_ms = now_millis();
+    let window = now_ms / (window_secs * 1000);
+    let elapsed = (now_ms % (window_secs * 1000)) as f64 / (window_secs * 1000) as f64;
 
-    // Increment counter
-    let count: u32 = conn_ref.incr(&key, 1).await?;
+    let current_key = window_key(subject, window);
+    // Counters outlive their window so the next one can read them
+    let (current, _) = cache.incr(&current_key, weight as i64, window_secs * 2).await?;
+    let previous = read_count(cache, &window_key(subject, window.saturating_sub(1))).await?;
 
-    // Set expiry on first request
-    if count == 1 {
-        let _: () = conn_ref.expire(&key, window_secs as i64).await?;
+    let decision = sliding_window(previous, current, weight, limit, elapsed, window_secs);
+    if !decision.allowed {
+        cache.incr(&current_key, -(weight as i64), window_secs * 2).await?;
     }
 
-    // Get TTL for reset time
-    let ttl: i64 = conn_ref.ttl(&key).await.unwrap_or(window_secs as i64);
-    let reset_at = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs()
-        + ttl as u64;
-
-    let allowed = count <= limit;
-    let remaining = if count > limit { 0 } else { limit - count };
-
     Ok(RateLimitResult {
-        allowed,
-        remaining,
+        allowed: decision.allowed,
+        remaining: decision.remaining,
         limit,
-        reset_at,
+        reset_at: (window + 1) * window_secs,
+        retry_after: decision.retry_after,
     })
 }
 
-/// Gets the current rate limit count for a user without incrementing
-pub async fn get_rate_limit_count(pool: &RedisPool, user_id: &str) -> Result<u32, redis::RedisError> {
-    let key = keys::rate_limit_key(user_id);
-    let mut conn = pool.lock().await;
+/// Gives back units consumed by a request that was not carried out
+pub async fn release_rate_limit(
+    cache: &dyn CacheBackend,
+    subject: &str,
+    weight: u32,
+    window_secs: u64,
+) -> Result<(), CacheError> {
+    let window_secs = window_secs.max(1);
+    let window = now_millis() / (window_secs * 1000);
+    let key = window_key(subject, window);
+    cache.incr(&key, -(weight as i64), window_secs * 2).await.map(|_| ())
+}
+
+/// Gets a subject's current sliding-window count without consuming any
+pub async fn get_rate_limit_count(
+    cache: &dyn CacheBackend,
+    subject: &str,
+    window_secs: u64,
+) -> Result<u32, CacheError> {
+    let window_secs = window_secs.max(1);
+    let now_ms = now_millis();
+    let window = now_ms / (window_secs * 1000);
+    let elapsed = (now_ms % (window_secs * 1000)) as f64 / (window_secs * 1000) as f64;
+
+    let current = read_count(cache, &window_key(subject, window)).await?;
+    let previous = read_count(cache, &window_key(subject, window.saturating_sub(1))).await?;
+    Ok((previous as f64 * (1.0 - elapsed) + current as f64).ceil() as u32)
+}
+
+/// Resets rate limit for a subject (admin use only)
+pub async fn reset_rate_limit(cache: &dyn CacheBackend, subject: &str) -> Result<(), CacheError> {
+    let prefix = format!("{}:", keys::rate_limit_key(subject));
+    cache.delete_prefix(&prefix).await.map(|_| ())
+}
+
+fn window_key(subject: &str, window: u64) -> String {
+    format!("{}:{}", keys::rate_limit_key(subject), window)
+}
+
+async fn read_count(cache: &dyn CacheBackend, key: &str) -> Result<i64, CacheError> {
+    Ok(cache.get(key).await?.and_then(|c| c.parse().ok()).unwrap_or(0).max(0))
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
 
-    let count: Option<u32> = (&mut *conn).get(&key).await?;
-    Ok(count.unwrap_or(0))
+#[derive(Debug, PartialEq)]
+struct Decision {
+    allowed: bool,
+    remaining: u32,
+    retry_after: u64,
 }
 
-/// Resets rate limit for a user (admin use only)
-pub async fn reset_rate_limit(pool: &RedisPool, user_id: &str) -> Result<(), redis::RedisError> {
-    let key = keys::rate_limit_key(user_id);
-    let mut conn = pool.lock().await;
+/// Decides a request whose `weight` is already included in `current`
+///
+/// `elapsed` is the fraction of the current window that has passed.
+fn sliding_window(
+    previous: i64,
+    current: i64,
+    weight: u32,
+    limit: u32,
+    elapsed: f64,
+    window_secs: u64,
+) -> Decision {
+    let (previous, limit, weight) = (previous as f64, limit as f64, weight as f64);
+    let window = window_secs as f64;
+    let estimate = previous * (1.0 - elapsed) + current as f64;
 
-    (&mut *conn).del(&key).await
+    if estimate <= limit {
+        return Decision {
+            allowed: true,
+            remaining: (limit - estimate).floor() as u32,
+            retry_after: 0,
+        };
+    }
+
+    let before = current as f64 - weight;
+    let remaining = (limit - (previous * (1.0 - elapsed) + before)).max(0.0).floor() as u32;
+    let wait = if current as f64 <= limit {
+        // Fits once enough of the previous window has slid out
+        let needed = 1.0 - (limit - current as f64) / previous;
+        (needed - elapsed) * window
+    } else if weight <= limit {
+        // Wait for the next window, then for this one to slide out enough
+        let needed = if before > 0.0 { (1.0 - (limit - weight) / before).max(0.0) } else { 0.0 };
+        (1.0 - elapsed) * window + needed * window
+    } else {
+        // Heavier than the whole budget; never fits
+        window
+    };
+
+    Decision {
+        allowed: false,
+        remaining,
+        retry_after: (wait.ceil() as u64).max(1),
+    }
 }
 
-/// Default rate limits by tier
+/// Default rate limits by tier, in request units per window
 pub mod limits {
     pub const FREE_TIER: u32 = 100; // 100 requests per minute
     pub const PRO_TIER: u32 = 1000; // 1000 requests per minute
+    pub const ENTERPRISE_TIER: u32 = 10_000; // 10000 requests per minute
     pub const WINDOW_SECS: u64 = 60; // 1 minute window
+
+    /// Monthly quotas in request units; enterprise is unmetered
+    pub const FREE_MONTHLY_QUOTA: u64 = 10_000;
+    pub const PRO_MONTHLY_QUOTA: u64 = 1_000_000;
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cache::MemoryCache;
 
     #[test]
     fn test_rate_limit_result() {
@@ -90,8 +179,52 @@ mod tests {
             remaining: 99,
             limit: 100,
             reset_at: 1234567890,
+            retry_after: 0,
         };
         assert!(result.allowed);
         assert_eq!(result.remaining, 99);
     }
+
+    #[tokio::test]
+    async fn test_check_rate_limit_in_memory() {
+        let cache = MemoryCache::new(16);
+        for _ in 0..2 {
+            assert!(check_rate_limit(&cache, "user_abc", 2, 1, 60).await.unwrap().allowed);
+        }
+        let limited = check_rate_limit(&cache, "user_abc", 2, 1, 60).await.unwrap();
+        assert!(!limited.allowed);
+        assert_eq!(limited.remaining, 0);
+        assert!(limited.retry_after > 0);
+        // The denied request was not counted
+        assert_eq!(get_rate_limit_count(&cache, "user_abc", 60).await.unwrap(), 2);
+
+        reset_rate_limit(&cache, "user_abc").await.unwrap();
+        assert_eq!(get_rate_limit_count(&cache, "user_abc", 60).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_weighted_requests() {
+        let cache = MemoryCache::new(16);
+        assert_eq!(check_rate_limit(&cache, "key", 10, 8, 60).await.unwrap().remaining, 2);
+        assert!(!check_rate_limit(&cache, "key", 10, 8, 60).await.unwrap().allowed);
+        assert!(check_rate_limit(&cache, "key", 10, 2, 60).await.unwrap().allowed);
+    }
+
+    #[test]
+    fn test_sliding_window() {
+        // Half of a full previous window still counts
+        assert_eq!(
+            sliding_window(100, 51, 1, 100, 0.5, 60),
+            Decision { allowed: false, remaining: 0, retry_after: 1 }
+        );
+        assert_eq!(
+            sliding_window(100, 40, 1, 100, 0.5, 60),
+            Decision { allowed: true, remaining: 10, retry_after: 0 }
+        );
+
+        // Over budget within the current window alone: wait for it to end
+        let decision = sliding_window(0, 101, 1, 100, 0.25, 60);
+        assert!(!decision.allowed);
+        assert_eq!(decision.retry_after, 46);
+    }
 }
@@ -0,0 +1,131 @@
+use async_trait::async_trait;
+use redis::AsyncCommands;
+
+use super::backend::{CacheBackend, CacheError};
+use super::RedisPool;
+
+/// Redis-backed cache shared by every API instance
+#[derive(Clone)]
+pub struct RedisCache {
+    pool: RedisPool,
+}
+
+impl RedisCache {
+    pub fn new(pool: RedisPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl CacheBackend for RedisCache {
+    async fn get(&self, key: &str) -> Result<Option<String>, CacheError> {
+        let mut conn = self.pool.clone();
+        Ok(conn.get(key).await?)
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl_secs: u64) -> Result<(), CacheError> {
+        let mut conn = self.pool.clone();
+        Ok(conn.set_ex(key, value, ttl_secs).await?)
+    }
+
+    async fn delete(&self, keys: &[String]) -> Result<usize, CacheError> {
+        if keys.is_empty() {
+            return Ok(0);
+        }
+        let mut conn = self.pool.clone();
+        Ok(conn.del(keys).await?)
+    }
+
+    async fn delete_prefix(&self, prefix: &str) -> Result<usize, CacheError> {
+        let mut matched: Vec<String> = Vec::new();
+        {
+            let mut conn = self.pool.clone();
+            let mut iter = conn.scan_match::<_, String>(format!("{}*", prefix)).await?;
+            while let Some(key) = iter.next_item().await {
+                matched.push(key);
+            }
+        }
+        self.delete(&matched).await
+    }
+
+    async fn incr(&self, key: &str, amount: i64, window_secs: u64) -> Result<(i64, u64), CacheError> {
+        let mut conn = self.pool.clone();
+
+        // One transaction: create the counter with its expiry if missing,
+        // then increment, so a counter can never be left without a TTL
+        let (count, ttl): (i64, i64) = redis::pipe()
+            .atomic()
+            .cmd("SET")
+            .arg(key)
+            .arg(0)
+            .arg("EX")
+            .arg(window_secs.max(1))
+            .arg("NX")
+            .ignore()
+            .incr(key, amount)
+            .ttl(key)
+            .query_async(&mut conn)
+            .await?;
+
+        Ok((count, ttl.max(0) as u64))
+    }
+
+    async fn ping(&self) -> Result<(), CacheError> {
+        let mut conn = self.pool.clone();
+        redis::cmd("PING").query_async::<String>(&mut conn).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    use crate::cache::{create_redis_pool, redis_health_check};
+
+    /// Cache on `TEST_REDIS_URL`; tests using it are `#[ignore]`d, run them
+    /// with `TEST_REDIS_URL=redis://... cargo test -- --ignored`
+    async fn test_cache() -> (RedisPool, RedisCache) {
+        let redis_url = std::env::var("TEST_REDIS_URL").expect("TEST_REDIS_URL must be set to run Redis tests");
+        let pool = create_redis_pool(&redis_url)
+            .await
+            .expect("TEST_REDIS_URL is not reachable");
+        (pool.clone(), RedisCache::new(pool))
+    }
+
+    #[tokio::test]
+    #[ignore = "needs Redis; set TEST_REDIS_URL and run with --ignored"]
+    async fn test_incr_window() {
+        let (pool, cache) = test_cache().await;
+        assert!(redis_health_check(&pool).await);
+        let rate = format!("test:rate:{}", Uuid::new_v4());
+        let burst = format!("test:burst:{}", Uuid::new_v4());
+        let unset = format!("test:unset:{}", Uuid::new_v4());
+
+        // The first increment starts the window
+        let (count, ttl) = cache.incr(&rate, 1, 60).await.unwrap();
+        assert_eq!(count, 1);
+        assert!((59..=60).contains(&ttl));
+
+        // Later ones count within it without extending it
+        let (count, ttl) = cache.incr(&rate, 5, 120).await.unwrap();
+        assert_eq!(count, 6);
+        assert!(ttl > 0 && ttl <= 60);
+        assert_eq!(cache.incr(&rate, -5, 60).await.unwrap().0, 1);
+
+        // A counter always gets an expiry, even when its first change is a refund
+        let (count, ttl) = cache.incr(&unset, -1, 60).await.unwrap();
+        assert_eq!(count, -1);
+        assert!(ttl > 0);
+
+        // An elapsed window starts over
+        cache.incr(&burst, 1, 1).await.unwrap();
+        assert_eq!(cache.incr(&burst, 1, 1).await.unwrap().0, 2);
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        assert_eq!(cache.incr(&burst, 1, 1).await.unwrap(), (1, 1));
+
+        cache.delete(&[rate, burst, unset]).await.unwrap();
+    }
+}
@@ -1,9 +1,32 @@
-use redis::AsyncCommands;
+use axum::http::{HeaderName, HeaderValue};
 use sha2::{Digest, Sha256};
 
-use super::{keys, RedisPool};
+use super::backend::{CacheBackend, CacheError};
+use super::keys;
 use crate::simulation::SimulationRequest;
 
+/// Header telling clients whether a response came from the cache
+pub const CACHE_STATUS_HEADER: HeaderName = HeaderName::from_static("x-cache");
+
+/// How a cacheable request was served
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CacheStatus {
+    Hit,
+    Miss,
+    /// Not cacheable, caching disabled, or `no_cache` was set
+    Bypass,
+}
+
+impl CacheStatus {
+    pub fn header_value(self) -> HeaderValue {
+        HeaderValue::from_static(match self {
+            CacheStatus::Hit => "HIT",
+            CacheStatus::Miss => "MISS",
+            CacheStatus::Bypass => "BYPASS",
+        })
+    }
+}
+
 /// Generates a deterministic hash of a simulation request for caching
 pub fn hash_simulation_request(request: &SimulationRequest) -> String {
     let mut hasher = Sha256::new();
@@ -25,31 +48,33 @@ pub fn hash_simulation_request(request: &SimulationRequest) -> String {
         hasher.update(args_json.as_bytes());
     }
 
+    hasher.update(request.max_gas.to_le_bytes());
+    hasher.update([request.is_view as u8]);
+    hasher.update(request.public_key.as_deref().unwrap_or_default().as_bytes());
+    hasher.update(request.ledger_version.unwrap_or_default().to_le_bytes());
+
     let result = hasher.finalize();
     hex::encode(result)
 }
 
 /// Gets a cached simulation result
 pub async fn get_cached_simulation(
-    pool: &RedisPool,
+    cache: &dyn CacheBackend,
     network: &str,
     payload_hash: &str,
 ) -> Option<String> {
     let key = keys::simulation_key(network, payload_hash);
-    let mut conn = pool.lock().await;
-
-    let result: Result<Option<String>, _> = (&mut *conn).get(&key).await;
-    result.ok().flatten()
+    cache.get(&key).await.ok().flatten()
 }
 
 /// Caches a simulation result
 pub async fn cache_simulation(
-    pool: &RedisPool,
+    cache: &dyn CacheBackend,
     network: &str,
     payload_hash: &str,
     result: &str,
     success: bool,
-) -> Result<(), redis::RedisError> {
+) -> Result<(), CacheError> {
     let key = keys::simulation_key(network, payload_hash);
     let ttl = if success {
         keys::ttl::SIMULATION_SUCCESS
@@ -57,64 +82,84 @@ pub async fn cache_simulation(
         keys::ttl::SIMULATION_FAILED
     };
 
-    let mut conn = pool.lock().await;
-    (&mut *conn).set_ex(&key, result, ttl).await
+    cache.set(&key, result, ttl).await
 }
 
 /// Gets a cached module ABI
 pub async fn get_cached_module(
-    pool: &RedisPool,
+    cache: &dyn CacheBackend,
     network: &str,
     address: &str,
 ) -> Option<String> {
     let key = keys::module_key(network, address);
-    let mut conn = pool.lock().await;
-
-    let result: Result<Option<String>, _> = (&mut *conn).get(&key).await;
-    result.ok().flatten()
+    cache.get(&key).await.ok().flatten()
 }
 
 /// Caches a module ABI
 pub async fn cache_module(
-    pool: &RedisPool,
+    cache: &dyn CacheBackend,
     network: &str,
     address: &str,
     abi: &str,
-) -> Result<(), redis::RedisError> {
+) -> Result<(), CacheError> {
     let key = keys::module_key(network, address);
-    let mut conn = pool.lock().await;
+    cache.set(&key, abi, keys::ttl::MODULE_ABI).await
+}
 
-    (&mut *conn).set_ex(&key, abi, keys::ttl::MODULE_ABI).await
+/// Drops one cached module ABI, e.g. when it no longer matches the chain
+pub async fn invalidate_module(
+    cache: &dyn CacheBackend,
+    network: &str,
+    module_id: &str,
+) -> Result<(), CacheError> {
+    let key = keys::module_key(network, module_id);
+    cache.delete(&[key]).await.map(|_| ())
+}
+
+/// Drops every cached module ABI under an address after a republish,
+/// returning how many were removed
+pub async fn invalidate_modules(
+    cache: &dyn CacheBackend,
+    network: &str,
+    address: &str,
+) -> Result<usize, CacheError> {
+    let prefix = keys::module_key(network, &format!("{}::", address));
+    cache.delete_prefix(&prefix).await
 }
 
 /// Gets cached account state
 pub async fn get_cached_account(
-    pool: &RedisPool,
+    cache: &dyn CacheBackend,
     network: &str,
     address: &str,
 ) -> Option<String> {
     let key = keys::account_key(network, address);
-    let mut conn = pool.lock().await;
-
-    let result: Result<Option<String>, _> = (&mut *conn).get(&key).await;
-    result.ok().flatten()
+    cache.get(&key).await.ok().flatten()
 }
 
 /// Caches account state
 pub async fn cache_account(
-    pool: &RedisPool,
+    cache: &dyn CacheBackend,
     network: &str,
     address: &str,
     state: &str,
-) -> Result<(), redis::RedisError> {
+) -> Result<(), CacheError> {
     let key = keys::account_key(network, address);
-    let mut conn = pool.lock().await;
+    cache.set(&key, state, keys::ttl::ACCOUNT_STATE).await
+}
 
-    (&mut *conn).set_ex(&key, state, keys::ttl::ACCOUNT_STATE).await
+/// Drops cached account state, e.g. after a stale sequence number
+pub async fn invalidate_account(
+    cache: &dyn CacheBackend,
+    network: &str,
+    address: &str,
+) -> Result<(), CacheError> {
+    let key = keys::account_key(network, address);
+    cache.delete(&[key]).await.map(|_| ())
 }
 
 // Hex encoding utility
-mod hex {
+pub(super) mod hex {
     const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
 
     pub fn encode(bytes: impl AsRef<[u8]>) -> String {
@@ -0,0 +1,319 @@
+//! Source-derived module ABIs
+//!
+//! The compiled package is thrown away with the temp project, so ABIs are
+//! read from the submitted source instead of the bytecode. Exposed functions
+//! follow the node's definition: public, friend/package and entry functions.
+
+use regex::Regex;
+
+use super::types::{FieldAbi, FunctionAbi, GenericTypeParam, ModuleAbi, StructAbi};
+use crate::move_package::{matching_brace, strip_comments, PACKAGE_NAME};
+
+/// Derives the ABI of every module declared in the source
+pub fn derive_abis(move_code: &str) -> Vec<ModuleAbi> {
+    let code = strip_comments(move_code);
+    let module_re = Regex::new(r"\bmodule\s+(?:(\w+)::)?(\w+)\s*\{").unwrap();
+    let address_re = Regex::new(r"\baddress\s+(\w+)\s*\{").unwrap();
+
+    let mut abis = Vec::new();
+    for cap in module_re.captures_iter(&code) {
+        let whole = cap.get(0).unwrap();
+        let open = whole.end() - 1;
+        let Some(close) = matching_brace(&code, open) else {
+            continue;
+        };
+
+        let address = match cap.get(1) {
+            Some(addr) => addr.as_str().to_string(),
+            None => address_re
+                .captures_iter(&code[..whole.start()])
+                .last()
+                .map(|c| c[1].to_string())
+                .unwrap_or_default(),
+        };
+        let address = if address == PACKAGE_NAME {
+            "0x1".to_string()
+        } else {
+            address
+        };
+
+        let body = blank_spec_blocks(&code[open + 1..close]);
+        abis.push(ModuleAbi {
+            address,
+            name: cap[2].to_string(),
+            exposed_functions: parse_functions(&body),
+            structs: parse_structs(&body),
+        });
+    }
+
+    abis
+}
+
+/// Blanks out `spec` blocks so spec helper functions are not picked up
+fn blank_spec_blocks(body: &str) -> String {
+    let spec_re = Regex::new(r"\bspec\b[^{;]*\{").unwrap();
+    let mut out = body.to_string();
+
+    let mut from = 0;
+    while let Some(m) = spec_re.find_at(body, from) {
+        let open = m.end() - 1;
+        let close = matching_brace(body, open).unwrap_or(body.len() - 1);
+        out.replace_range(m.start()..=close, &" ".repeat(close + 1 - m.start()));
+        from = close + 1;
+    }
+
+    out
+}
+
+fn parse_functions(body: &str) -> Vec<FunctionAbi> {
+    let fun_re = Regex::new(
+        r"(?m)^\s*((?:#\[[^\]]*\]\s*)*)(public(?:\s*\(\s*(?:friend|package)\s*\))?\s+)?(entry\s+)?(?:(?:native|inline)\s+)*fun\s+(\w+)",
+    )
+    .unwrap();
+
+    let mut functions = Vec::new();
+    for cap in fun_re.captures_iter(body) {
+        let visibility = match cap.get(2).map(|m| m.as_str().trim()) {
+            None => "private",
+            Some("public") => "public",
+            Some(_) => "friend",
+        };
+        let is_entry = cap.get(3).is_some();
+        if visibility == "private" && !is_entry {
+            continue;
+        }
+
+        let rest = &body[cap.get(0).unwrap().end()..];
+        let (generics, rest) = take_delimited(rest, '<', '>');
+        let (params, rest) = take_delimited(rest, '(', ')');
+
+        // Return type runs up to the body, `acquires` clause or `;` for natives
+        let end = rest.find(['{', ';']).unwrap_or(rest.len());
+        let signature_tail = rest[..end].trim();
+        let signature_tail = signature_tail
+            .split_once("acquires")
+            .map(|(ret, _)| ret.trim())
+            .unwrap_or(signature_tail);
+        let return_types = match signature_tail.strip_prefix(':').map(str::trim) {
+            Some(ret) if ret.starts_with('(') => {
+                split_top_level(&ret[1..ret.len().saturating_sub(1)])
+            }
+            Some(ret) if !ret.is_empty() => vec![normalize(ret)],
+            _ => vec![],
+        };
+
+        functions.push(FunctionAbi {
+            name: cap[4].to_string(),
+            visibility: visibility.to_string(),
+            is_entry,
+            is_view: cap[1].contains("view"),
+            generic_type_params: generics
+                .map(|g| split_top_level(&g).iter().map(|p| generic_param(p)).collect())
+                .unwrap_or_default(),
+            params: params
+                .map(|p| {
+                    split_top_level(&p)
+                        .iter()
+                        .map(|param| {
+                            param
+                                .split_once(':')
+                                .map(|(_, ty)| normalize(ty))
+                                .unwrap_or_else(|| param.clone())
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            return_types,
+        });
+    }
+
+    functions
+}
+
+fn parse_structs(body: &str) -> Vec<StructAbi> {
+    let struct_re =
+        Regex::new(r"(?m)^\s*(?:#\[[^\]]*\]\s*)*(?:public\s+)?struct\s+(\w+)").unwrap();
+    let ability_re = Regex::new(r"\bhas\s+([\w\s,]+)").unwrap();
+
+    let mut structs = Vec::new();
+    for cap in struct_re.captures_iter(body) {
+        let rest = &body[cap.get(0).unwrap().end()..];
+        let (generics, rest) = take_delimited(rest, '<', '>');
+
+        let end = rest.find(['{', ';', '(']).unwrap_or(rest.len());
+        let abilities = ability_re
+            .captures(&rest[..end])
+            .map(|a| split_top_level(&a[1]))
+            .unwrap_or_default();
+
+        let fields = if rest[end..].starts_with('{') {
+            let open = (body.len() - rest.len()) + end;
+            let close = matching_brace(body, open).unwrap_or(body.len());
+            split_top_level(&body[open + 1..close])
+                .iter()
+                .filter_map(|field| field.split_once(':'))
+                .map(|(name, ty)| FieldAbi {
+                    name: name.trim().to_string(),
+                    field_type: normalize(ty),
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+
+        structs.push(StructAbi {
+            name: cap[1].to_string(),
+            abilities,
+            generic_type_params: generics
+                .map(|g| split_top_level(&g).iter().map(|p| generic_param(p)).collect())
+                .unwrap_or_default(),
+            fields,
+        });
+    }
+
+    structs
+}
+
+/// Takes a leading `open ... close` group, returning its contents and the remainder
+fn take_delimited(text: &str, open: char, close: char) -> (Option<String>, &str) {
+    let trimmed = text.trim_start();
+    if !trimmed.starts_with(open) {
+        return (None, text);
+    }
+
+    let mut depth = 0usize;
+    for (i, c) in trimmed.char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return (Some(trimmed[1..i].to_string()), &trimmed[i + 1..]);
+            }
+        }
+    }
+
+    (None, text)
+}
+
+/// Splits on commas that are not nested inside `<>` or `()`
+fn split_top_level(text: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut current = String::new();
+
+    for c in text.chars() {
+        match c {
+            '<' | '(' => depth += 1,
+            '>' | ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(normalize(&current));
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    parts.push(normalize(&current));
+
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+/// Parses `phantom T: store + drop` into its ability constraints
+fn generic_param(param: &str) -> GenericTypeParam {
+    let constraints = param
+        .split_once(':')
+        .map(|(_, bounds)| {
+            bounds
+                .split('+')
+                .map(|b| b.trim().to_string())
+                .filter(|b| !b.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    GenericTypeParam { constraints }
+}
+
+/// Collapses whitespace in a type as written in source
+fn normalize(ty: &str) -> String {
+    ty.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = r#"module sentinel_verify::vault {
+    use std::signer;
+
+    struct Vault<phantom CoinType> has key, store {
+        balance: u64,
+        owners: vector<address>,
+    }
+
+    struct Receipt has drop { amount: u64 }
+
+    /// Deposits into the caller's vault
+    public entry fun deposit<CoinType: store>(account: &signer, amount: u64) acquires Vault {
+        let _ = signer::address_of(account);
+        let _ = amount;
+    }
+
+    #[view]
+    public fun balance(owner: address): (u64, bool) {
+        (0, true)
+    }
+
+    public(friend) fun internal_mint(amount: u64): Receipt {
+        Receipt { amount }
+    }
+
+    fun helper(): u64 { 1 }
+
+    spec module {
+        fun total(): u64 { 0 }
+    }
+}
+"#;
+
+    #[test]
+    fn test_derive_abi() {
+        let abis = derive_abis(CODE);
+        assert_eq!(abis.len(), 1);
+
+        let abi = &abis[0];
+        assert_eq!(abi.address, "0x1");
+        assert_eq!(abi.name, "vault");
+
+        let names: Vec<&str> = abi.exposed_functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["deposit", "balance", "internal_mint"]);
+
+        let deposit = &abi.exposed_functions[0];
+        assert!(deposit.is_entry);
+        assert!(!deposit.is_view);
+        assert_eq!(deposit.params, vec!["&signer", "u64"]);
+        assert_eq!(deposit.generic_type_params[0].constraints, vec!["store"]);
+        assert!(deposit.return_types.is_empty());
+
+        let balance = &abi.exposed_functions[1];
+        assert!(balance.is_view);
+        assert_eq!(balance.return_types, vec!["u64", "bool"]);
+        assert_eq!(abi.exposed_functions[2].visibility, "friend");
+    }
+
+    #[test]
+    fn test_derive_struct_abi() {
+        let abi = &derive_abis(CODE)[0];
+        assert_eq!(abi.structs.len(), 2);
+
+        let vault = &abi.structs[0];
+        assert_eq!(vault.abilities, vec!["key", "store"]);
+        assert_eq!(vault.generic_type_params.len(), 1);
+        assert_eq!(vault.fields.len(), 2);
+        assert_eq!(vault.fields[1].field_type, "vector<address>");
+        assert_eq!(abi.structs[1].fields[0].name, "amount");
+    }
+}
@@ -0,0 +1,148 @@
+//! Parsing of compiler and linter diagnostics
+//!
+//! The Move compiler renders diagnostics with codespan:
+//!
+//! ```text
+//! warning[W09002]: unused variable
+//!   ┌─ /tmp/.tmpAbC123/sources/counter.move:4:13
+//!   │
+//! 4 │         let x = 1;
+//!   │             ^ Unused local variable 'x'
+//! ```
+//!
+//! The header gives severity, code and message, the `┌─` line the primary
+//! location, and the first caret line the span width and label.
+
+use regex::Regex;
+
+use super::types::{Diagnostic, DiagnosticOrigin, DiagnosticSeverity, SourceSpan};
+
+/// Extracts every diagnostic from CLI output
+pub fn parse_diagnostics(output: &str, origin: DiagnosticOrigin) -> Vec<Diagnostic> {
+    let header = Regex::new(r"^(bug|error|warning|note)(?:\[(\w+)\])?:\s*(.+)$").unwrap();
+    let location = Regex::new(r"^\s*┌─\s*(.+?):(\d+):(\d+)\s*$").unwrap();
+    let carets = Regex::new(r"^\s*│(\s*)(\^+)\s*(.*)$").unwrap();
+
+    let lines: Vec<&str> = output.lines().collect();
+    let mut diagnostics = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let Some(cap) = header.captures(lines[i]) else {
+            i += 1;
+            continue;
+        };
+
+        let severity = match &cap[1] {
+            "bug" => DiagnosticSeverity::Bug,
+            "error" => DiagnosticSeverity::Error,
+            "warning" => DiagnosticSeverity::Warning,
+            _ => DiagnosticSeverity::Note,
+        };
+
+        let mut diagnostic = Diagnostic {
+            severity,
+            origin: origin.clone(),
+            code: cap.get(2).map(|m| m.as_str().to_string()),
+            file: None,
+            span: None,
+            message: cap[3].trim().to_string(),
+            label: None,
+        };
+
+        // Body runs until the next header or the blank line closing the snippet
+        i += 1;
+        while i < lines.len() && !header.is_match(lines[i]) && !lines[i].trim().is_empty() {
+            if let Some(loc) = location.captures(lines[i]) {
+                if diagnostic.file.is_none() {
+                    let line: u32 = loc[2].parse().unwrap_or(0);
+                    let column: u32 = loc[3].parse().unwrap_or(0);
+                    diagnostic.file = Some(relative_path(&loc[1]));
+                    diagnostic.span = Some(SourceSpan {
+                        start_line: line,
+                        start_column: column,
+                        end_line: line,
+                        end_column: column,
+                    });
+                }
+            } else if let Some(caret) = carets.captures(lines[i]) {
+                if diagnostic.label.is_none() {
+                    if let Some(span) = diagnostic.span.as_mut() {
+                        span.end_column = span.start_column + caret[2].chars().count() as u32 - 1;
+                    }
+                    let label = caret[3].trim();
+                    if !label.is_empty() {
+                        diagnostic.label = Some(label.to_string());
+                    }
+                }
+            }
+            i += 1;
+        }
+
+        diagnostics.push(diagnostic);
+    }
+
+    diagnostics
+}
+
+/// Strips the temp project prefix so paths read `sources/<module>.move`
+fn relative_path(path: &str) -> String {
+    match path.rfind("/sources/") {
+        Some(pos) => path[pos + 1..].to_string(),
+        None => path.rsplit('/').next().unwrap_or(path).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "Compiling, may take a little while to download git dependencies...
+warning[W09002]: unused variable
+  ┌─ /tmp/.tmpAbC123/sources/counter.move:4:13
+  │
+4 │         let x = 1;
+  │             ^ Unused local variable 'x'
+
+error[E03003]: unbound module
+  ┌─ /tmp/.tmpAbC123/sources/counter.move:2:9
+  │
+2 │     use std::foo;
+  │         ^^^^^^^^ Unbound module 'std::foo'
+
+{
+  \"Error\": \"Move compilation failed\"
+}
+";
+
+    #[test]
+    fn test_parse_diagnostics() {
+        let diagnostics = parse_diagnostics(OUTPUT, DiagnosticOrigin::Compiler);
+        assert_eq!(diagnostics.len(), 2);
+
+        let warning = &diagnostics[0];
+        assert_eq!(warning.severity, DiagnosticSeverity::Warning);
+        assert_eq!(warning.code.as_deref(), Some("W09002"));
+        assert_eq!(warning.file.as_deref(), Some("sources/counter.move"));
+        assert_eq!(warning.label.as_deref(), Some("Unused local variable 'x'"));
+        assert_eq!(
+            warning.span,
+            Some(SourceSpan { start_line: 4, start_column: 13, end_line: 4, end_column: 13 })
+        );
+
+        let error = &diagnostics[1];
+        assert_eq!(error.severity, DiagnosticSeverity::Error);
+        assert_eq!(error.message, "unbound module");
+        assert_eq!(error.span.as_ref().unwrap().end_column, 16);
+    }
+
+    #[test]
+    fn test_parse_diagnostic_without_code() {
+        let output = "warning: [lint] Needless reference\n  ┌─ /tmp/x/sources/a.move:7:5\n";
+        let diagnostics = parse_diagnostics(output, DiagnosticOrigin::Lint);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, None);
+        assert_eq!(diagnostics[0].origin, DiagnosticOrigin::Lint);
+        assert_eq!(diagnostics[0].message, "[lint] Needless reference");
+    }
+}
@@ -0,0 +1,178 @@
+use std::path::Path;
+use std::time::Instant;
+use tokio::time::timeout;
+
+use super::abi::derive_abis;
+use super::diagnostics::parse_diagnostics;
+use super::types::{
+    CompileRequest, CompileResult, CompiledModule, DiagnosticOrigin, DiagnosticSeverity,
+};
+use crate::error::ApiError;
+use crate::move_package::{
+    cli_timeout, create_temp_project, is_language_version, run_aptos_move, PACKAGE_NAME,
+};
+
+pub struct CompileExecutor;
+
+impl CompileExecutor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn execute(&self, request: CompileRequest) -> Result<CompileResult, ApiError> {
+        let start = Instant::now();
+        let timeout_duration = cli_timeout(request.timeout_seconds);
+
+        let mut args = Vec::new();
+        if let Some(version) = &request.language_version {
+            if !is_language_version(version) {
+                return Err(ApiError::BadRequest(format!(
+                    "Invalid language version `{}`",
+                    version
+                )));
+            }
+            args.push("--language-version".to_string());
+            args.push(version.clone());
+        }
+
+        let temp_dir = create_temp_project(&request.module_name, &request.move_code)?;
+        let temp_path = temp_dir.path().to_path_buf();
+
+        let result = timeout(timeout_duration, async {
+            let compiled = run_aptos_move("compile", &temp_path, &args).await?;
+
+            // Lint only code that compiles; the linter re-runs the compiler
+            let linted = if request.lint && compiled.2 == 0 {
+                Some(run_aptos_move("lint", &temp_path, &args).await?)
+            } else {
+                None
+            };
+
+            Ok::<_, ApiError>((compiled, linted))
+        })
+        .await;
+
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        let ((stdout, stderr, exit_code), linted) = match result {
+            Ok(outputs) => outputs?,
+            Err(_) => {
+                return Ok(CompileResult {
+                    success: false,
+                    duration_ms,
+                    diagnostics: vec![],
+                    modules: vec![],
+                    lint_ran: false,
+                    summary: format!(
+                        "Compilation timed out after {} seconds",
+                        timeout_duration.as_secs()
+                    ),
+                    raw_output: None,
+                });
+            }
+        };
+
+        let compile_output = format!("{}\n{}", stdout, stderr);
+        let mut diagnostics = parse_diagnostics(&compile_output, DiagnosticOrigin::Compiler);
+        let mut raw_output = compile_output;
+
+        let mut lint_ran = false;
+        if let Some((lint_stdout, lint_stderr, _)) = linted {
+            let lint_output = format!("{}\n{}", lint_stdout, lint_stderr);
+            if lint_unavailable(&lint_output) {
+                tracing::warn!("aptos CLI has no lint command, skipping lint");
+            } else {
+                lint_ran = true;
+                // The linter repeats compiler warnings; keep only the new ones
+                for diagnostic in parse_diagnostics(&lint_output, DiagnosticOrigin::Lint) {
+                    let duplicate = diagnostics.iter().any(|d| {
+                        d.message == diagnostic.message && d.span == diagnostic.span
+                    });
+                    if !duplicate {
+                        diagnostics.push(diagnostic);
+                    }
+                }
+                raw_output.push_str(&lint_output);
+            }
+        }
+
+        let success = exit_code == 0;
+        let modules = if success {
+            self.compiled_modules(&temp_path, &request.move_code)
+        } else {
+            vec![]
+        };
+
+        let count = |severity: DiagnosticSeverity| {
+            diagnostics.iter().filter(|d| d.severity == severity).count()
+        };
+        let errors = count(DiagnosticSeverity::Error) + count(DiagnosticSeverity::Bug);
+        let warnings = count(DiagnosticSeverity::Warning);
+
+        let summary = if success {
+            format!(
+                "Compiled {} module(s) with {} warning(s)",
+                modules.len(),
+                warnings
+            )
+        } else {
+            format!(
+                "Compilation of {} failed with {} error(s) and {} warning(s)",
+                request.module_name, errors, warnings
+            )
+        };
+
+        Ok(CompileResult {
+            success,
+            duration_ms,
+            diagnostics,
+            modules,
+            lint_ran,
+            summary,
+            raw_output: Some(raw_output),
+        })
+    }
+
+    /// Reads bytecode sizes from the build directory and attaches source ABIs
+    fn compiled_modules(&self, project_path: &Path, move_code: &str) -> Vec<CompiledModule> {
+        let bytecode_dir = project_path
+            .join("build")
+            .join(PACKAGE_NAME)
+            .join("bytecode_modules");
+
+        let entries = match std::fs::read_dir(&bytecode_dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::warn!("No bytecode found in {}: {}", bytecode_dir.display(), e);
+                return vec![];
+            }
+        };
+
+        let mut abis = derive_abis(move_code);
+        let mut modules: Vec<CompiledModule> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "mv"))
+            .filter_map(|entry| {
+                let name = entry.path().file_stem()?.to_string_lossy().to_string();
+                let bytecode_size = entry.metadata().ok()?.len();
+                let abi = abis
+                    .iter()
+                    .position(|abi| abi.name == name)
+                    .map(|i| abis.remove(i));
+                Some(CompiledModule {
+                    name,
+                    bytecode_size,
+                    abi,
+                })
+            })
+            .collect();
+
+        modules.sort_by(|a, b| a.name.cmp(&b.name));
+        modules
+    }
+}
+
+/// Older CLI releases have no `aptos move lint`
+fn lint_unavailable(output: &str) -> bool {
+    output.contains("unrecognized subcommand") || output.contains("Found argument 'lint'")
+}
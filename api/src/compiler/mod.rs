@@ -0,0 +1,7 @@
+pub mod abi;
+pub mod diagnostics;
+pub mod executor;
+pub mod types;
+
+pub use executor::CompileExecutor;
+pub use types::*;
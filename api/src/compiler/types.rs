@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CompileRequest {
+    pub move_code: String,
+    pub module_name: String,
+    /// Also run `aptos move lint` when the installed CLI supports it
+    #[serde(default = "default_lint")]
+    pub lint: bool,
+    /// Move language version, e.g. "2.0" (`--language-version`)
+    #[serde(default)]
+    pub language_version: Option<String>,
+    /// Capped at the server maximum (`MAX_CLI_TIMEOUT_SECS`)
+    #[serde(default = "default_timeout")]
+    pub timeout_seconds: u32,
+}
+
+fn default_lint() -> bool {
+    true
+}
+
+fn default_timeout() -> u32 {
+    60
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompileResult {
+    pub success: bool,
+    pub duration_ms: u64,
+    pub diagnostics: Vec<Diagnostic>,
+    pub modules: Vec<CompiledModule>,
+    /// False when linting was requested but the CLI has no `lint` command
+    pub lint_ran: bool,
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_output: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    Bug,
+    Error,
+    Warning,
+    Note,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticOrigin {
+    Compiler,
+    Lint,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    pub severity: DiagnosticSeverity,
+    pub origin: DiagnosticOrigin,
+    /// Compiler diagnostic code, e.g. "E03003" or "W09002"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Path relative to the package root, e.g. "sources/counter.move"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<SourceSpan>,
+    pub message: String,
+    /// Label attached to the primary span
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// 1-based, inclusive source span
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SourceSpan {
+    pub start_line: u32,
+    pub start_column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompiledModule {
+    pub name: String,
+    /// Size of the compiled `.mv` bytecode in bytes
+    pub bytecode_size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abi: Option<ModuleAbi>,
+}
+
+/// Module ABI in the shape of the node's `/accounts/{addr}/module/{name}` response
+///
+/// Derived from the source, so types are reported as written there.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleAbi {
+    pub address: String,
+    pub name: String,
+    pub exposed_functions: Vec<FunctionAbi>,
+    pub structs: Vec<StructAbi>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionAbi {
+    pub name: String,
+    pub visibility: String,
+    pub is_entry: bool,
+    pub is_view: bool,
+    pub generic_type_params: Vec<GenericTypeParam>,
+    pub params: Vec<String>,
+    #[serde(rename = "return")]
+    pub return_types: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StructAbi {
+    pub name: String,
+    pub abilities: Vec<String>,
+    pub generic_type_params: Vec<GenericTypeParam>,
+    pub fields: Vec<FieldAbi>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct GenericTypeParam {
+    pub constraints: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldAbi {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: String,
+}
@@ -1,20 +1,29 @@
 use std::env;
 
-/// Shinami Node Service base URLs for Movement
-const SHINAMI_MOVEMENT_MAINNET: &str = "https://api.shinami.com/aptos/node/v1/movement_mainnet";
-const SHINAMI_MOVEMENT_TESTNET: &str = "https://api.shinami.com/aptos/node/v1/movement_testnet";
-
-/// Public Movement RPC fallbacks
-const PUBLIC_MOVEMENT_MAINNET: &str = "https://mainnet.movementnetwork.xyz/v1";
-const PUBLIC_MOVEMENT_TESTNET: &str = "https://testnet.movementnetwork.xyz/v1";
+use crate::auth::SessionConfig;
+use crate::cache::CacheMode;
+use crate::network::NetworkRegistry;
 
 #[derive(Clone, Debug)]
 pub struct Config {
     pub port: u16,
-    pub movement_rpc_mainnet: String,
-    pub movement_rpc_testnet: String,
+    pub networks: NetworkRegistry,
     pub shinami_api_key: Option<String>,
     pub cors_origin: String,
+    /// Upper bound on scenarios simulated in parallel within one batch
+    pub batch_max_concurrency: usize,
+    /// Timeout for a single RPC request
+    pub rpc_timeout_secs: u64,
+    /// Attempts per RPC call across all endpoints of a network
+    pub rpc_max_attempts: u32,
+    pub cache_mode: CacheMode,
+    pub redis_url: String,
+    /// Entries kept by the in-memory cache
+    pub cache_memory_capacity: usize,
+    /// Clerk session token verification for dashboard routes
+    pub session: SessionConfig,
+    /// Proxies in front of the API that append to `X-Forwarded-For`
+    pub trusted_proxy_hops: usize,
 }
 
 impl Config {
@@ -27,22 +36,8 @@ impl Config {
         // Check for Shinami API key
         let shinami_api_key = env::var("SHINAMI_KEY").ok();
 
-        // Use Shinami URLs if API key is present, otherwise fall back to public RPC
-        let (mainnet_url, testnet_url) = if shinami_api_key.is_some() {
-            (
-                env::var("MOVEMENT_RPC_MAINNET")
-                    .unwrap_or_else(|_| SHINAMI_MOVEMENT_MAINNET.to_string()),
-                env::var("MOVEMENT_RPC_TESTNET")
-                    .unwrap_or_else(|_| SHINAMI_MOVEMENT_TESTNET.to_string()),
-            )
-        } else {
-            (
-                env::var("MOVEMENT_RPC_MAINNET")
-                    .unwrap_or_else(|_| PUBLIC_MOVEMENT_MAINNET.to_string()),
-                env::var("MOVEMENT_RPC_TESTNET")
-                    .unwrap_or_else(|_| PUBLIC_MOVEMENT_TESTNET.to_string()),
-            )
-        };
+        let networks = NetworkRegistry::from_env(shinami_api_key.as_deref())
+            .unwrap_or_else(|e| panic!("{}", e));
 
         if shinami_api_key.is_some() {
             tracing::info!("Shinami API key configured - using Shinami Node Service");
@@ -55,26 +50,43 @@ impl Config {
                 .unwrap_or_else(|_| "4004".to_string())
                 .parse()
                 .expect("PORT must be a number"),
-            movement_rpc_mainnet: mainnet_url,
-            movement_rpc_testnet: testnet_url,
+            networks,
             shinami_api_key,
             cors_origin: env::var("CORS_ORIGIN")
                 .unwrap_or_else(|_| "http://localhost:3000".to_string()),
+            batch_max_concurrency: env::var("BATCH_MAX_CONCURRENCY")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&n: &usize| n > 0)
+                .unwrap_or(16),
+            rpc_timeout_secs: env::var("RPC_TIMEOUT_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(10),
+            rpc_max_attempts: env::var("RPC_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&n: &u32| n > 0)
+                .unwrap_or(3),
+            cache_mode: match env::var("CACHE_BACKEND").as_deref() {
+                Ok("memory") => CacheMode::Memory,
+                _ => CacheMode::Redis,
+            },
+            redis_url: env::var("REDIS_URL")
+                .unwrap_or_else(|_| "redis://localhost:6379".to_string()),
+            cache_memory_capacity: env::var("CACHE_MEMORY_CAPACITY")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&n: &usize| n > 0)
+                .unwrap_or(10_000),
+            session: SessionConfig::from_env(),
+            trusted_proxy_hops: env::var("TRUSTED_PROXY_HOPS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
         }
     }
 
-    pub fn get_rpc_url(&self, network: &str) -> &str {
-        match network {
-            "mainnet" => &self.movement_rpc_mainnet,
-            _ => &self.movement_rpc_testnet,
-        }
-    }
-
-    /// Returns the Shinami API key if configured
-    pub fn get_shinami_api_key(&self) -> Option<&str> {
-        self.shinami_api_key.as_deref()
-    }
-
     /// Set up Move Prover environment variables (Boogie and Z3)
     fn setup_prover_env() {
         // Set DOTNET_ROOT for Boogie to find .NET runtime
@@ -134,3 +146,24 @@ impl Config {
         }
     }
 }
+
+#[cfg(test)]
+impl Config {
+    /// Built-in networks, in-memory cache and no session verification
+    pub fn for_tests() -> Self {
+        Self {
+            port: 0,
+            networks: NetworkRegistry::builtin(None),
+            shinami_api_key: None,
+            cors_origin: String::new(),
+            batch_max_concurrency: 4,
+            rpc_timeout_secs: 10,
+            rpc_max_attempts: 3,
+            cache_mode: CacheMode::Memory,
+            redis_url: String::new(),
+            cache_memory_capacity: 100,
+            session: SessionConfig::default(),
+            trusted_proxy_hops: 0,
+        }
+    }
+}
@@ -2,7 +2,7 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, PgExecutor};
 use uuid::Uuid;
 
 use super::DbPool;
@@ -11,64 +11,73 @@ use super::DbPool;
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct ApiKey {
     pub id: Uuid,
-    pub user_id: Uuid,
+    /// Set for keys minted through the API by a Clerk user
+    pub user_id: Option<Uuid>,
+    /// Set for keys minted by the dashboard for a wallet
+    pub wallet_address: Option<String>,
     pub name: String,
     pub key_hash: String,
     pub key_prefix: String,
     pub last_used_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub revoked_reason: Option<String>,
+    /// Scopes; see `auth::KeyScopes`
+    pub allowed_endpoints: Option<Vec<String>>,
+    pub allowed_networks: Option<Vec<String>>,
+    pub project_id: Option<Uuid>,
+    pub access: String,
+    /// The key that replaced this one when it was rotated
+    pub superseded_by: Option<Uuid>,
+    /// The key this one replaced
+    pub replaces: Option<Uuid>,
 }
 
-/// API key for list response (without hash)
-#[derive(Debug, Clone, Serialize)]
-pub struct ApiKeyInfo {
+/// An API key row about to be stored; scope columns as in `ApiKey`
+#[derive(Debug)]
+pub struct CreateApiKey<'a> {
     pub id: Uuid,
-    pub name: String,
-    pub key_prefix: String,
-    pub last_used_at: Option<DateTime<Utc>>,
-    pub created_at: DateTime<Utc>,
-    pub expires_at: Option<DateTime<Utc>>,
-}
-
-impl From<ApiKey> for ApiKeyInfo {
-    fn from(key: ApiKey) -> Self {
-        Self {
-            id: key.id,
-            name: key.name,
-            key_prefix: key.key_prefix,
-            last_used_at: key.last_used_at,
-            created_at: key.created_at,
-            expires_at: key.expires_at,
-        }
-    }
-}
-
-/// Create API key input
-#[derive(Debug, Deserialize)]
-pub struct CreateApiKey {
-    pub name: String,
-    pub key_hash: String,
-    pub key_prefix: String,
+    pub user_id: Uuid,
+    pub name: &'a str,
+    pub key_hash: &'a str,
+    pub key_prefix: &'a str,
     pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub allowed_endpoints: Option<Vec<&'a str>>,
+    pub allowed_networks: Option<&'a [String]>,
+    pub project_id: Option<Uuid>,
+    pub access: &'a str,
+    pub replaces: Option<Uuid>,
 }
 
-/// Create a new API key
-pub async fn create_api_key(pool: &DbPool, user_id: Uuid, input: CreateApiKey) -> Result<ApiKey, sqlx::Error> {
-    sqlx::query_as::<_, ApiKey>(
+/// Create a new API key; takes any executor so rotation can insert in a
+/// transaction
+pub async fn create_api_key<'e>(executor: impl PgExecutor<'e>, key: &CreateApiKey<'_>) -> Result<(), sqlx::Error> {
+    sqlx::query(
         r#"
-        INSERT INTO api_keys (user_id, name, key_hash, key_prefix, expires_at)
-        VALUES ($1, $2, $3, $4, $5)
-        RETURNING *
+        INSERT INTO api_keys (
+            id, user_id, name, key_hash, key_prefix, expires_at, created_at,
+            allowed_endpoints, allowed_networks, project_id, access, replaces
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
         "#,
     )
-    .bind(user_id)
-    .bind(&input.name)
-    .bind(&input.key_hash)
-    .bind(&input.key_prefix)
-    .bind(input.expires_at)
-    .fetch_one(pool)
-    .await
+    .bind(key.id)
+    .bind(key.user_id)
+    .bind(key.name)
+    .bind(key.key_hash)
+    .bind(key.key_prefix)
+    .bind(key.expires_at)
+    .bind(key.created_at)
+    .bind(&key.allowed_endpoints)
+    .bind(key.allowed_networks)
+    .bind(key.project_id)
+    .bind(key.access)
+    .bind(key.replaces)
+    .execute(executor)
+    .await?;
+    Ok(())
 }
 
 /// Get API key by ID
@@ -79,52 +88,96 @@ pub async fn get_api_key_by_id(pool: &DbPool, id: Uuid) -> Result<Option<ApiKey>
         .await
 }
 
-/// Get API key by prefix (for lookup)
-pub async fn get_api_key_by_prefix(pool: &DbPool, prefix: &str) -> Result<Option<ApiKey>, sqlx::Error> {
+/// Get API keys by prefix (for lookup); prefixes are not unique, so
+/// callers verify the hash of each
+pub async fn get_api_keys_by_prefix(pool: &DbPool, prefix: &str) -> Result<Vec<ApiKey>, sqlx::Error> {
     sqlx::query_as::<_, ApiKey>("SELECT * FROM api_keys WHERE key_prefix = $1")
         .bind(prefix)
-        .fetch_optional(pool)
+        .fetch_all(pool)
         .await
 }
 
-/// List API keys for a user
-pub async fn list_api_keys(pool: &DbPool, user_id: Uuid) -> Result<Vec<ApiKeyInfo>, sqlx::Error> {
-    let keys = sqlx::query_as::<_, ApiKey>(
-        "SELECT * FROM api_keys WHERE user_id = $1 ORDER BY created_at DESC"
-    )
-    .bind(user_id)
-    .fetch_all(pool)
-    .await?;
-
-    Ok(keys.into_iter().map(ApiKeyInfo::from).collect())
+/// List API keys for a user, newest first, revoked and expired ones included
+pub async fn list_api_keys(pool: &DbPool, user_id: Uuid) -> Result<Vec<ApiKey>, sqlx::Error> {
+    sqlx::query_as::<_, ApiKey>("SELECT * FROM api_keys WHERE user_id = $1 ORDER BY created_at DESC")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
 }
 
-/// Update last used timestamp
+/// Update last used timestamp, unless another call did within the last minute
 pub async fn update_api_key_last_used(pool: &DbPool, id: Uuid) -> Result<(), sqlx::Error> {
-    sqlx::query("UPDATE api_keys SET last_used_at = NOW() WHERE id = $1")
+    sqlx::query(
+        r#"
+        UPDATE api_keys SET last_used_at = NOW()
+        WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < NOW() - INTERVAL '1 minute')
+        "#,
+    )
         .bind(id)
         .execute(pool)
         .await?;
     Ok(())
 }
 
-/// Delete API key
-pub async fn delete_api_key(pool: &DbPool, id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
-    let result = sqlx::query("DELETE FROM api_keys WHERE id = $1 AND user_id = $2")
-        .bind(id)
-        .bind(user_id)
-        .execute(pool)
-        .await?;
+/// Revoke an API key, keeping it and its history; returns false if the
+/// key does not exist, is not the user's, or was already revoked
+pub async fn revoke_api_key(
+    pool: &DbPool,
+    id: Uuid,
+    user_id: Uuid,
+    reason: Option<&str>,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE api_keys
+        SET revoked_at = NOW(), revoked_reason = $3
+        WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL
+        "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(reason)
+    .execute(pool)
+    .await?;
     Ok(result.rows_affected() > 0)
 }
 
-/// Count API keys for a user
-pub async fn count_api_keys(pool: &DbPool, user_id: Uuid) -> Result<i64, sqlx::Error> {
-    let result: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM api_keys WHERE user_id = $1")
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
-    Ok(result.0)
+/// Mark a key as replaced by `new_id`, ending at `expires_at`; returns
+/// false if it was already rotated or revoked
+pub async fn supersede_api_key<'e>(
+    executor: impl PgExecutor<'e>,
+    id: Uuid,
+    new_id: Uuid,
+    expires_at: DateTime<Utc>,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE api_keys SET superseded_by = $2, expires_at = $3
+        WHERE id = $1 AND superseded_by IS NULL AND revoked_at IS NULL
+        "#,
+    )
+    .bind(id)
+    .bind(new_id)
+    .bind(expires_at)
+    .execute(executor)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Count a user's keys that still authenticate, including rotated keys in
+/// their grace period
+pub async fn count_usable_api_keys(pool: &DbPool, user_id: Uuid) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*) FROM api_keys
+        WHERE user_id = $1
+          AND revoked_at IS NULL
+          AND (expires_at IS NULL OR expires_at > NOW())
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
 }
 
 /// Check if API key is expired
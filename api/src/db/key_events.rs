@@ -0,0 +1,82 @@
+//! API key audit trail database operations
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::DbPool;
+
+/// What happened to a key
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyEventKind {
+    Created,
+    UsedFromNewIp,
+    Revoked,
+    Expired,
+    Rotated,
+}
+
+impl KeyEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyEventKind::Created => "created",
+            KeyEventKind::UsedFromNewIp => "used_from_new_ip",
+            KeyEventKind::Revoked => "revoked",
+            KeyEventKind::Expired => "expired",
+            KeyEventKind::Rotated => "rotated",
+        }
+    }
+}
+
+/// Key event model
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct KeyEvent {
+    pub id: i64,
+    pub api_key_id: Uuid,
+    pub event: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub detail: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Create key event input
+#[derive(Debug, Clone)]
+pub struct CreateKeyEvent {
+    pub api_key_id: Uuid,
+    pub kind: KeyEventKind,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub detail: Option<String>,
+}
+
+/// Record a key event; returns false when it was already recorded (a
+/// known IP, or a key already marked expired)
+pub async fn record_key_event(pool: &DbPool, input: CreateKeyEvent) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO key_events (api_key_id, event, ip_address, user_agent, detail)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(input.api_key_id)
+    .bind(input.kind.as_str())
+    .bind(&input.ip_address)
+    .bind(&input.user_agent)
+    .bind(&input.detail)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// A key's events, newest first
+pub async fn list_key_events(pool: &DbPool, api_key_id: Uuid) -> Result<Vec<KeyEvent>, sqlx::Error> {
+    sqlx::query_as::<_, KeyEvent>(
+        "SELECT * FROM key_events WHERE api_key_id = $1 ORDER BY created_at DESC, id DESC",
+    )
+    .bind(api_key_id)
+    .fetch_all(pool)
+    .await
+}
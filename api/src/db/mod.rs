@@ -8,6 +8,10 @@ mod projects;
 mod simulations;
 mod prover_runs;
 mod api_keys;
+mod key_events;
+mod rate_limit_tiers;
+mod usage;
+mod runs;
 
 pub use pool::*;
 pub use users::*;
@@ -15,3 +19,7 @@ pub use projects::*;
 pub use simulations::*;
 pub use prover_runs::*;
 pub use api_keys::*;
+pub use key_events::*;
+pub use rate_limit_tiers::*;
+pub use usage::*;
+pub use runs::*;
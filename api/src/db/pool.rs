@@ -39,3 +39,20 @@ pub async fn run_migrations(pool: &DbPool) -> Result<(), sqlx::Error> {
     tracing::info!("Database migrations completed");
     Ok(())
 }
+
+/// Migrated pool for tests that need Postgres, from `TEST_DATABASE_URL`
+///
+/// Such tests are `#[ignore]`d; run them with
+/// `TEST_DATABASE_URL=postgres://... cargo test -- --ignored`.
+#[cfg(test)]
+pub async fn test_pool() -> DbPool {
+    let database_url =
+        std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL must be set to run database tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&database_url)
+        .await
+        .expect("TEST_DATABASE_URL is not reachable");
+    run_migrations(&pool).await.expect("Failed to migrate test database");
+    pool
+}
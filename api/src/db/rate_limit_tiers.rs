@@ -0,0 +1,11 @@
+//! Rate limit tier database operations
+
+use super::DbPool;
+
+/// Get the rate limit tier assigned to a user, if any
+pub async fn get_rate_limit_tier(pool: &DbPool, user_id: &str) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>("SELECT tier FROM rate_limit_tiers WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+}
@@ -0,0 +1,76 @@
+//! Stored prover, batch and gas runs, referenced by id from reports
+
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+use super::DbPool;
+
+/// What produced a run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RunKind {
+    Prover,
+    Batch,
+    Gas,
+}
+
+impl RunKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunKind::Prover => "prover",
+            RunKind::Batch => "batch",
+            RunKind::Gas => "gas",
+        }
+    }
+}
+
+/// Create run input
+#[derive(Debug, Clone)]
+pub struct CreateRun<'a> {
+    pub user_id: &'a str,
+    pub project_id: Option<Uuid>,
+    pub kind: RunKind,
+    pub result: JsonValue,
+}
+
+/// Store a run and return its id
+pub async fn create_run(pool: &DbPool, input: CreateRun<'_>) -> Result<Uuid, sqlx::Error> {
+    let row: (Uuid,) = sqlx::query_as(
+        r#"
+        INSERT INTO runs (user_id, project_id, kind, result)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id
+        "#,
+    )
+    .bind(input.user_id)
+    .bind(input.project_id)
+    .bind(input.kind.as_str())
+    .bind(&input.result)
+    .fetch_one(pool)
+    .await?;
+    Ok(row.0)
+}
+
+/// A run's result if it belongs to the user; with a project, only runs
+/// made with that project's keys are found
+pub async fn get_run_result(
+    pool: &DbPool,
+    id: Uuid,
+    kind: RunKind,
+    user_id: &str,
+    project_id: Option<Uuid>,
+) -> Result<Option<JsonValue>, sqlx::Error> {
+    let row: Option<(JsonValue,)> = sqlx::query_as(
+        r#"
+        SELECT result FROM runs
+        WHERE id = $1 AND kind = $2 AND user_id = $3
+          AND ($4::UUID IS NULL OR project_id = $4)
+        "#,
+    )
+    .bind(id)
+    .bind(kind.as_str())
+    .bind(user_id)
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.map(|(result,)| result))
+}
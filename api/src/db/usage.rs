@@ -0,0 +1,161 @@
+//! Usage metering database operations
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::DbPool;
+
+/// One authenticated call, as recorded in `usage_events`
+#[derive(Debug, Clone)]
+pub struct CreateUsageEvent {
+    pub user_id: String,
+    pub api_key_id: Uuid,
+    pub endpoint: String,
+    pub network: Option<String>,
+    pub units: u32,
+    pub gas_used: Option<u64>,
+    pub prover_cpu_secs: Option<f64>,
+    pub status: u16,
+    pub latency_ms: u64,
+}
+
+/// Aggregated usage for one day or month
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct UsageBucket {
+    pub period_start: DateTime<Utc>,
+    pub calls: i64,
+    pub units: i64,
+    pub errors: i64,
+    pub gas_used: i64,
+    pub prover_cpu_secs: f64,
+    pub avg_latency_ms: f64,
+}
+
+/// Aggregated usage for one API key
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct KeyUsage {
+    pub api_key_id: Uuid,
+    pub calls: i64,
+    pub units: i64,
+    pub errors: i64,
+    pub gas_used: i64,
+    pub prover_cpu_secs: f64,
+    pub last_used_at: DateTime<Utc>,
+}
+
+/// Append a usage event
+pub async fn insert_usage_event(pool: &DbPool, event: &CreateUsageEvent) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO usage_events
+            (user_id, api_key_id, endpoint, network, units, gas_used, prover_cpu_secs, status, latency_ms)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#,
+    )
+    .bind(&event.user_id)
+    .bind(event.api_key_id)
+    .bind(&event.endpoint)
+    .bind(&event.network)
+    .bind(event.units as i32)
+    .bind(event.gas_used.map(|gas| gas as i64))
+    .bind(event.prover_cpu_secs)
+    .bind(event.status as i16)
+    .bind(event.latency_ms.min(i32::MAX as u64) as i32)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Units a user has been charged since `since`; calls turned away before
+/// `rate_limit` charged them are recorded with none
+pub async fn get_usage_units_since(
+    pool: &DbPool,
+    user_id: &str,
+    since: DateTime<Utc>,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COALESCE(SUM(units), 0)::BIGINT
+        FROM usage_events
+        WHERE user_id = $1 AND created_at >= $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(since)
+    .fetch_one(pool)
+    .await
+}
+
+/// A user's usage in `[from, to)`, grouped by `period` ("day" or "month", UTC)
+///
+/// With `project_id`, only calls made with keys bound to that project count.
+pub async fn get_usage_buckets(
+    pool: &DbPool,
+    user_id: &str,
+    project_id: Option<Uuid>,
+    period: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<UsageBucket>, sqlx::Error> {
+    sqlx::query_as::<_, UsageBucket>(
+        r#"
+        SELECT date_trunc($2, created_at, 'UTC') AS period_start,
+               COUNT(*) AS calls,
+               COALESCE(SUM(units), 0)::BIGINT AS units,
+               COUNT(*) FILTER (WHERE status >= 400) AS errors,
+               COALESCE(SUM(gas_used), 0)::BIGINT AS gas_used,
+               COALESCE(SUM(prover_cpu_secs), 0)::DOUBLE PRECISION AS prover_cpu_secs,
+               COALESCE(AVG(latency_ms), 0)::DOUBLE PRECISION AS avg_latency_ms
+        FROM usage_events
+        WHERE user_id = $1 AND created_at >= $3 AND created_at < $4
+          AND ($5::UUID IS NULL
+               OR api_key_id IN (SELECT id FROM api_keys WHERE project_id = $5))
+        GROUP BY 1
+        ORDER BY 1
+        "#,
+    )
+    .bind(user_id)
+    .bind(period)
+    .bind(from)
+    .bind(to)
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// A user's usage in `[from, to)`, per API key, heaviest first
+///
+/// With `project_id`, only keys bound to that project are listed.
+pub async fn get_usage_by_key(
+    pool: &DbPool,
+    user_id: &str,
+    project_id: Option<Uuid>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<KeyUsage>, sqlx::Error> {
+    sqlx::query_as::<_, KeyUsage>(
+        r#"
+        SELECT api_key_id,
+               COUNT(*) AS calls,
+               COALESCE(SUM(units), 0)::BIGINT AS units,
+               COUNT(*) FILTER (WHERE status >= 400) AS errors,
+               COALESCE(SUM(gas_used), 0)::BIGINT AS gas_used,
+               COALESCE(SUM(prover_cpu_secs), 0)::DOUBLE PRECISION AS prover_cpu_secs,
+               MAX(created_at) AS last_used_at
+        FROM usage_events
+        WHERE user_id = $1 AND created_at >= $2 AND created_at < $3
+          AND ($4::UUID IS NULL
+               OR api_key_id IN (SELECT id FROM api_keys WHERE project_id = $4))
+        GROUP BY api_key_id
+        ORDER BY units DESC
+        "#,
+    )
+    .bind(user_id)
+    .bind(from)
+    .bind(to)
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+}
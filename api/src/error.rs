@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -13,6 +13,13 @@ pub enum ApiError {
     #[error("Not found: {0}")]
     NotFound(String),
 
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    /// Authenticated, but the API key's scopes do not cover the request
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 
@@ -27,26 +34,71 @@ pub enum ApiError {
 
     #[error("Prover timeout")]
     ProverTimeout,
+
+    /// Over a rate limit; the request can be retried after this many seconds
+    #[error("Rate limited, retry after {0}s")]
+    RateLimited(u64),
+
+    /// Monthly quota used up; it resets in this many seconds
+    #[error("Quota exceeded, resets in {0}s")]
+    QuotaExceeded(u64),
+}
+
+impl ApiError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::SimulationFailed(_) => StatusCode::BAD_REQUEST,
+            ApiError::RpcError(_) => StatusCode::BAD_GATEWAY,
+            ApiError::ProverError(_) => StatusCode::BAD_REQUEST,
+            ApiError::ProverTimeout => StatusCode::REQUEST_TIMEOUT,
+            ApiError::RateLimited(_) | ApiError::QuotaExceeded(_) => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    /// Message returned to clients in the `error` field
+    pub fn message(&self) -> String {
+        match self {
+            ApiError::BadRequest(msg)
+            | ApiError::NotFound(msg)
+            | ApiError::Unauthorized(msg)
+            | ApiError::Forbidden(msg)
+            | ApiError::Internal(msg)
+            | ApiError::SimulationFailed(msg)
+            | ApiError::RpcError(msg)
+            | ApiError::ProverError(msg) => msg.clone(),
+            ApiError::ProverTimeout => "Prover timed out".to_string(),
+            ApiError::RateLimited(retry_after) => {
+                format!("Rate limit exceeded, retry in {} seconds", retry_after)
+            }
+            ApiError::QuotaExceeded(resets_in) => {
+                format!("Monthly quota exceeded, resets in {} seconds", resets_in)
+            }
+        }
+    }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let (status, error_message) = match &self {
-            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
-            ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, msg.clone()),
-            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-            ApiError::SimulationFailed(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
-            ApiError::RpcError(msg) => (StatusCode::BAD_GATEWAY, msg.clone()),
-            ApiError::ProverError(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
-            ApiError::ProverTimeout => (StatusCode::REQUEST_TIMEOUT, "Prover timed out".to_string()),
-        };
+        let status = self.status_code();
+        let error_message = self.message();
 
         let body = Json(json!({
             "error": error_message,
             "code": status.as_u16(),
         }));
 
-        (status, body).into_response()
+        let mut response = (status, body).into_response();
+        if let ApiError::RateLimited(retry_after) | ApiError::QuotaExceeded(retry_after) = self {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        }
+        response
     }
 }
 
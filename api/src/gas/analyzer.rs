@@ -1,5 +1,7 @@
-use crate::config::Config;
+use std::sync::Arc;
+
 use crate::error::ApiError;
+use crate::movement::{prefixed_address, FunctionCall, MovementClient, SimulationTransaction};
 use crate::simulation::SimulationResult;
 
 use super::parser::parse_simulation_result;
@@ -10,58 +12,12 @@ use super::types::{
 };
 
 pub struct GasAnalyzer {
-    http_client: reqwest::Client,
-    config: Config,
+    client: Arc<dyn MovementClient>,
 }
 
 impl GasAnalyzer {
-    pub fn new(config: Config) -> Self {
-        Self {
-            http_client: reqwest::Client::new(),
-            config,
-        }
-    }
-
-    /// Build a GET request with Shinami API key header if configured
-    fn build_get(&self, url: &str) -> reqwest::RequestBuilder {
-        let mut req = self.http_client.get(url);
-        if let Some(api_key) = self.config.get_shinami_api_key() {
-            req = req.header("X-Api-Key", api_key);
-        }
-        req
-    }
-
-    /// Build a POST request with Shinami API key header if configured
-    fn build_post(&self, url: &str) -> reqwest::RequestBuilder {
-        let mut req = self.http_client.post(url);
-        if let Some(api_key) = self.config.get_shinami_api_key() {
-            req = req.header("X-Api-Key", api_key);
-        }
-        req
-    }
-
-    /// Get account sequence number for simulation
-    async fn get_account_sequence(&self, rpc_url: &str, address: &str) -> String {
-        let account_url = format!("{}/accounts/{}", rpc_url, address);
-
-        let response = match self.build_get(&account_url).send().await {
-            Ok(r) => r,
-            Err(_) => return "0".to_string(),
-        };
-
-        if !response.status().is_success() {
-            return "0".to_string();
-        }
-
-        let account_info: serde_json::Value = match response.json().await {
-            Ok(v) => v,
-            Err(_) => return "0".to_string(),
-        };
-
-        account_info.get("sequence_number")
-            .and_then(|v| v.as_str())
-            .unwrap_or("0")
-            .to_string()
+    pub fn new(client: Arc<dyn MovementClient>) -> Self {
+        Self { client }
     }
 
     pub async fn analyze(&self, request: GasAnalysisRequest) -> Result<GasProfile, ApiError> {
@@ -86,93 +42,55 @@ impl GasAnalyzer {
             by_function,
             suggestions,
             steps,
+            run_id: None,
         })
     }
 
     async fn run_simulation(&self, request: &GasAnalysisRequest) -> Result<SimulationResult, ApiError> {
-        let rpc_url = self.config.get_rpc_url(&request.network);
+        let call = FunctionCall::new(
+            &request.module_address,
+            &request.module_name,
+            &request.function_name,
+            &request.type_args,
+            &request.args,
+        );
 
         // Check if this is a view function - use /view endpoint instead of simulation
-        if self.check_if_view_function(&rpc_url, request).await {
-            return self.execute_view_analysis(&rpc_url, request).await;
+        let is_view = self
+            .client
+            .is_view_function(
+                &request.network,
+                &request.module_address,
+                &request.module_name,
+                &request.function_name,
+            )
+            .await;
+        if is_view {
+            return self.execute_view_analysis(request, &call).await;
         }
 
-        // Normalize sender address
-        let sender_normalized = if request.sender.starts_with("0x") {
-            request.sender.clone()
-        } else {
-            format!("0x{}", request.sender)
-        };
-
         // Get sequence number from account
-        let sequence_number = self.get_account_sequence(&rpc_url, &sender_normalized).await;
-
-        // Convert numeric arguments to strings (Movement/Aptos API requires this)
-        let stringified_args: Vec<serde_json::Value> = request.args.iter().map(|arg| {
-            match arg {
-                serde_json::Value::Number(n) => serde_json::Value::String(n.to_string()),
-                other => other.clone(),
-            }
-        }).collect();
-
-        let payload = serde_json::json!({
-            "type": "entry_function_payload",
-            "function": format!(
-                "{}::{}::{}",
-                request.module_address,
-                request.module_name,
-                request.function_name
-            ),
-            "type_arguments": request.type_args,
-            "arguments": stringified_args,
-        });
-
-        // Use a well-known valid Ed25519 public key for simulation
-        // This is the Ed25519 base point which is always a valid curve point
-        // With skip_auth_key_validation=true, the actual key doesn't matter - only format validity
-        let simulation_public_key = "0x3b6a27bcceb6a42d62a3a8d02a6f0d73653215771de243a63ac048a18b59da29";
-        let dummy_signature = serde_json::json!({
-            "type": "ed25519_signature",
-            "public_key": simulation_public_key,
-            "signature": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"
-        });
-
-        let body = serde_json::json!({
-            "sender": sender_normalized,
-            "sequence_number": sequence_number,
-            "max_gas_amount": request.max_gas.to_string(),
-            "gas_unit_price": "100",
-            "expiration_timestamp_secs": (chrono::Utc::now().timestamp() + 600).to_string(),
-            "payload": payload,
-            "signature": dummy_signature,
-        });
-
-        // Use skip_auth_key_validation to allow simulation with dummy signature
-        let url = format!(
-            "{}/transactions/simulate?estimate_gas_unit_price=true&estimate_prioritized_gas_unit_price=false&skip_auth_key_validation=true",
-            rpc_url
-        );
-        tracing::info!("Simulation URL: {}", url);
-        tracing::debug!("Simulation body: {}", serde_json::to_string_pretty(&body).unwrap_or_default());
-
-        let response = self
-            .build_post(&url)
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            tracing::error!("Simulation failed with status {}: {}", status, error_text);
-            return Err(ApiError::SimulationFailed(error_text));
-        }
+        let sequence_number = self
+            .client
+            .get_account(&request.network, &request.sender)
+            .await?
+            .map(|account| account.sequence_number)
+            .unwrap_or(0);
+
+        let transaction = SimulationTransaction {
+            sender: prefixed_address(&request.sender),
+            sequence_number,
+            max_gas_amount: request.max_gas,
+            gas_unit_price: 100,
+            call,
+            public_key: None,
+        };
 
-        let result: Vec<serde_json::Value> = response.json().await?;
-        let tx_result = result.first().ok_or_else(|| {
-            ApiError::SimulationFailed("Empty response from simulation".to_string())
-        })?;
+        let tx_result = self
+            .client
+            .simulate(&request.network, &transaction)
+            .await
+            .inspect_err(|e| tracing::error!("Simulation failed: {}", e))?;
 
         // Check if simulation failed due to auth key issues
         let success = tx_result.get("success").and_then(|v| v.as_bool()).unwrap_or(false);
@@ -184,7 +102,7 @@ impl GasAnalyzer {
             ));
         }
 
-        parse_simulation_result(tx_result)
+        parse_simulation_result(&tx_result)
     }
 
     fn analyze_operations(&self, sim_result: &SimulationResult) -> Vec<OperationGas> {
@@ -298,83 +216,21 @@ impl GasAnalyzer {
         hotspots
     }
 
-    /// Check if a function is a view function by querying the module ABI
-    async fn check_if_view_function(&self, rpc_url: &str, request: &GasAnalysisRequest) -> bool {
-        let module_url = format!(
-            "{}/accounts/{}/module/{}",
-            rpc_url, request.module_address, request.module_name
-        );
-
-        let response = match self.build_get(&module_url).send().await {
-            Ok(r) => r,
-            Err(_) => return false,
-        };
-
-        if !response.status().is_success() {
-            return false;
-        }
-
-        let module_info: serde_json::Value = match response.json().await {
-            Ok(v) => v,
-            Err(_) => return false,
-        };
-
-        // Check exposed_functions for the function
-        if let Some(abi) = module_info.get("abi") {
-            if let Some(functions) = abi.get("exposed_functions").and_then(|f| f.as_array()) {
-                for func in functions {
-                    if let Some(name) = func.get("name").and_then(|n| n.as_str()) {
-                        if name == request.function_name {
-                            let is_view = func.get("is_view").and_then(|v| v.as_bool()).unwrap_or(false);
-                            let is_entry = func.get("is_entry").and_then(|v| v.as_bool()).unwrap_or(false);
-                            return is_view || !is_entry;
-                        }
-                    }
-                }
-            }
-        }
-
-        false
-    }
-
     /// Execute a view function and estimate gas usage
-    async fn execute_view_analysis(&self, rpc_url: &str, request: &GasAnalysisRequest) -> Result<SimulationResult, ApiError> {
-        // Convert numeric arguments to strings
-        let stringified_args: Vec<serde_json::Value> = request.args.iter().map(|arg| {
-            match arg {
-                serde_json::Value::Number(n) => serde_json::Value::String(n.to_string()),
-                other => other.clone(),
-            }
-        }).collect();
-
-        let body = serde_json::json!({
-            "function": format!(
-                "{}::{}::{}",
-                request.module_address,
-                request.module_name,
-                request.function_name
-            ),
-            "type_arguments": request.type_args,
-            "arguments": stringified_args,
-        });
-
-        let response = self
-            .build_post(&format!("{}/view", rpc_url))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ApiError::SimulationFailed(format!(
-                "View function failed: {}",
-                error_text
-            )));
-        }
+    async fn execute_view_analysis(
+        &self,
+        request: &GasAnalysisRequest,
+        call: &FunctionCall,
+    ) -> Result<SimulationResult, ApiError> {
+        self.client
+            .view(&request.network, call)
+            .await
+            .map_err(|e| match e {
+                ApiError::SimulationFailed(error_text) => {
+                    ApiError::SimulationFailed(format!("View function failed: {}", error_text))
+                }
+                other => other,
+            })?;
 
         // View functions are free - they don't consume gas
         // They are read-only operations that don't execute on-chain
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct GasAnalysisRequest {
@@ -19,16 +20,19 @@ fn default_max_gas() -> u64 {
     100_000
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GasProfile {
     pub total_gas: u64,
     pub by_operation: Vec<OperationGas>,
     pub by_function: Vec<FunctionGas>,
     pub suggestions: Vec<GasSuggestion>,
     pub steps: Vec<GasStep>,
+    /// Id to pass as `gas_run_id` to `/reports/markdown`; set once stored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<Uuid>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OperationGas {
     pub operation: String,
     pub count: u32,
@@ -36,7 +40,7 @@ pub struct OperationGas {
     pub percentage: f64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FunctionGas {
     pub module_name: String,
     pub function_name: String,
@@ -45,7 +49,7 @@ pub struct FunctionGas {
     pub hotspots: Vec<Hotspot>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Hotspot {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub line: Option<u32>,
@@ -53,7 +57,7 @@ pub struct Hotspot {
     pub operation: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GasSuggestion {
     pub severity: String, // "info", "warning", "critical"
     pub message: String,
@@ -62,7 +66,7 @@ pub struct GasSuggestion {
     pub estimated_savings: u64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GasStep {
     pub step: u32,
     pub gas: u64,
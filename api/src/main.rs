@@ -3,25 +3,38 @@ use axum::{
     routing::{delete, get, post},
     Router,
 };
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
 
 mod auth;
 mod cache;
+mod compiler;
 mod config;
 mod db;
 mod error;
 mod gas;
+mod move_package;
+mod move_test;
+mod movement;
+mod network;
+mod progress;
 mod prover;
+mod reports;
 mod routes;
+mod scenario_file;
 mod simulation;
 mod trace;
+mod usage;
 
-use cache::RedisPool;
+use cache::{Cache, ProverCache};
+use auth::SessionVerifier;
 use config::Config;
 use db::DbPool;
 use gas::GasAnalyzer;
+use movement::{CachedMovementClient, HttpMovementClient, MovementClient, RpcHealth};
+use network::NetworkRegistry;
 use simulation::SimulationExecutor;
 use trace::TraceExecutor;
 
@@ -30,8 +43,33 @@ pub struct AppState {
     pub simulation: Arc<SimulationExecutor>,
     pub trace: Arc<TraceExecutor>,
     pub gas_analyzer: Arc<GasAnalyzer>,
+    pub prover_cache: Arc<ProverCache>,
     pub db: DbPool,
-    pub redis: Option<RedisPool>,
+    pub cache: Arc<Cache>,
+    pub rpc_health: Arc<RpcHealth>,
+    pub networks: Arc<NetworkRegistry>,
+    pub session: Arc<SessionVerifier>,
+}
+
+#[cfg(test)]
+impl AppState {
+    /// State over a mock node client and an in-memory cache
+    pub fn for_tests(db: DbPool) -> Self {
+        let config = Config::for_tests();
+        let client: Arc<dyn MovementClient> = Arc::new(movement::MockMovementClient::new());
+        let cache = Cache::memory(config.cache_memory_capacity);
+        Self {
+            simulation: Arc::new(SimulationExecutor::new(config.clone(), client.clone())),
+            trace: Arc::new(TraceExecutor::new(client.clone())),
+            gas_analyzer: Arc::new(GasAnalyzer::new(client)),
+            prover_cache: Arc::new(ProverCache::new(cache.clone())),
+            db,
+            cache,
+            rpc_health: Arc::new(RpcHealth::new(&config.networks)),
+            networks: Arc::new(config.networks.clone()),
+            session: Arc::new(SessionVerifier::new(config.session)),
+        }
+    }
 }
 
 #[tokio::main]
@@ -83,25 +121,31 @@ async fn main() {
             .expect("Failed to run database migrations");
     }
 
-    // Initialize Redis pool (optional, graceful degradation if not available)
-    let redis_pool = match cache::create_redis_pool().await {
-        Ok(pool) => {
-            tracing::info!("Redis connection established");
-            Some(pool)
-        }
-        Err(e) => {
-            tracing::warn!("Redis not available, caching disabled: {}", e);
-            None
-        }
-    };
+    // Redis when configured and reachable, otherwise an in-memory cache
+    let cache = Cache::connect(config.cache_mode, &config.redis_url, config.cache_memory_capacity).await;
 
-    // Create executors
+    // Create executors, all sharing one node client
+    let rpc_health = Arc::new(RpcHealth::new(&config.networks));
+    let http_client = Arc::new(HttpMovementClient::new(config.clone(), rpc_health.clone()));
+    let movement: Arc<dyn MovementClient> = http_client.clone();
+
+    // Keep nodes serving the wrong chain out of rotation
+    tokio::spawn(async move { http_client.verify_chain_ids().await });
+
+    let cached: Arc<dyn MovementClient> =
+        Arc::new(CachedMovementClient::new(movement.clone(), cache.clone()));
     let app_state = AppState {
-        simulation: Arc::new(SimulationExecutor::new(config.clone())),
-        trace: Arc::new(TraceExecutor::new(config.clone())),
-        gas_analyzer: Arc::new(GasAnalyzer::new(config.clone())),
+        simulation: Arc::new(
+            SimulationExecutor::new(config.clone(), movement).with_cache(cache.clone()),
+        ),
+        trace: Arc::new(TraceExecutor::new(cached.clone())),
+        gas_analyzer: Arc::new(GasAnalyzer::new(cached)),
+        prover_cache: Arc::new(ProverCache::new(cache.clone())),
         db: db_pool,
-        redis: redis_pool,
+        cache,
+        rpc_health,
+        networks: Arc::new(config.networks.clone()),
+        session: Arc::new(SessionVerifier::new(config.session.clone())),
     };
 
     // Configure CORS
@@ -114,19 +158,48 @@ async fn main() {
     let protected_routes = Router::new()
         .route("/simulate", post(routes::simulate_transaction))
         .route("/simulate/batch", post(routes::simulate_batch))
+        .route("/simulate/batch/stream", post(routes::simulate_batch_stream))
+        .route("/scenarios/run", post(routes::run_scenario_file))
         .route("/trace", post(routes::get_trace))
         .route("/prove", post(routes::run_prover))
+        .route("/prove/stream", post(routes::run_prover_stream))
+        .route("/prove/coverage", post(routes::spec_coverage))
+        .route("/compile", post(routes::compile_package))
+        .route("/test", post(routes::run_tests))
+        .route("/test/stream", post(routes::run_tests_stream))
+        .route("/reports/markdown", post(routes::markdown_report))
         .route("/analyze-gas", post(routes::analyze_gas))
+        .route("/networks", get(routes::list_networks))
+        .route(
+            "/cache/modules/{network}/{address}",
+            delete(routes::invalidate_module_cache),
+        )
+        .route("/usage", get(routes::get_usage))
+        .route_layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            auth::rate_limit,
+        ))
+        .route_layer(middleware::from_fn(auth::require_scopes))
+        .route_layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            usage::track_usage,
+        ))
         .layer(middleware::from_fn_with_state(
-            app_state.db.clone(),
+            app_state.clone(),
             auth::api_key_auth,
         ));
 
-    // API key management routes (require session auth)
+    // API key management routes (require Clerk session auth)
     let api_key_routes = Router::new()
         .route("/", post(routes::create_api_key))
         .route("/", get(routes::list_api_keys))
-        .route("/{id}", delete(routes::delete_api_key));
+        .route("/{id}", delete(routes::delete_api_key))
+        .route("/{id}/events", get(routes::list_api_key_events))
+        .route("/{id}/rotate", post(routes::rotate_api_key))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            auth::session_auth,
+        ));
 
     // Build the main router
     let app = Router::new()
@@ -136,6 +209,10 @@ async fn main() {
         .nest("/api/v1", protected_routes)
         .nest("/api/v1/api-keys", api_key_routes)
         .with_state(app_state)
+        .layer(middleware::from_fn_with_state(
+            auth::TrustedProxies(config.trusted_proxy_hops),
+            auth::client_info,
+        ))
         .layer(TraceLayer::new_for_http())
         .layer(cors);
 
@@ -146,7 +223,8 @@ async fn main() {
 
     tracing::info!("Sentinel API listening on http://0.0.0.0:{}", config.port);
 
-    axum::serve(listener, app)
+    // Peer addresses feed the API key audit trail
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
         .await
         .expect("Failed to start server");
 }
@@ -0,0 +1,10 @@
+//! Shared helpers for working with submitted Move packages
+//!
+//! Used by the prover, compiler and test runner: temp project setup, `aptos`
+//! CLI invocation and lightweight source scanning.
+
+mod project;
+mod source;
+
+pub use project::*;
+pub use source::*;
@@ -0,0 +1,378 @@
+use serde::Deserialize;
+use std::path::{Component, Path};
+use std::process::Stdio;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+
+use crate::error::ApiError;
+
+/// Package name used in every generated Move.toml
+pub const PACKAGE_NAME: &str = "sentinel_verify";
+
+/// Longest one request may keep the `aptos` CLI running, whatever it asks for
+pub const MAX_CLI_TIMEOUT_SECS: u32 = 600;
+
+// Use aptos-framework v1.0.0 which is compatible with Move Prover
+// The mainnet branch uses Move 2.2 features not yet supported by the prover
+const MOVE_TOML_TEMPLATE: &str = r#"[package]
+name = "sentinel_verify"
+version = "1.0.0"
+
+[dependencies]
+AptosFramework = { git = "https://github.com/aptos-labs/aptos-core.git", subdir = "aptos-move/framework/aptos-framework", rev = "aptos-node-v1.8.0" }
+
+[addresses]
+sentinel_verify = "0x1"
+"#;
+
+/// Repositories uploaded Move.toml files may take git dependencies from
+const ALLOWED_DEPENDENCY_REPOS: &[&str] = &[
+    "https://github.com/aptos-labs/aptos-core.git",
+    "https://github.com/movementlabsxyz/aptos-core.git",
+];
+
+// Minimal Move.toml for simple modules that don't need framework dependencies
+const MOVE_TOML_MINIMAL: &str = r#"[package]
+name = "sentinel_verify"
+version = "1.0.0"
+
+[addresses]
+sentinel_verify = "0x1"
+"#;
+
+/// Selects the Move.toml used for a module based on its imports
+pub fn move_toml_for(move_code: &str) -> &'static str {
+    // Check if code uses framework imports - if not, use minimal Move.toml
+    let needs_framework = move_code.contains("use aptos_framework::")
+        || move_code.contains("use std::")
+        || move_code.contains("use aptos_std::");
+
+    if needs_framework {
+        MOVE_TOML_TEMPLATE
+    } else {
+        MOVE_TOML_MINIMAL
+    }
+}
+
+/// Creates a temp directory with a Move project containing a single module
+pub fn create_temp_project(module_name: &str, move_code: &str) -> Result<TempDir, ApiError> {
+    validate_module_name(module_name)?;
+
+    let temp_dir = TempDir::new()
+        .map_err(|e| ApiError::Internal(format!("Failed to create temp directory: {}", e)))?;
+
+    let base_path = temp_dir.path();
+
+    // Create sources directory
+    let sources_dir = base_path.join("sources");
+    std::fs::create_dir_all(&sources_dir)
+        .map_err(|e| ApiError::Internal(format!("Failed to create sources dir: {}", e)))?;
+
+    // Write Move.toml
+    std::fs::write(base_path.join("Move.toml"), move_toml_for(move_code))
+        .map_err(|e| ApiError::Internal(format!("Failed to write Move.toml: {}", e)))?;
+
+    // Write the Move source file
+    let source_file = sources_dir.join(format!("{}.move", module_name));
+    std::fs::write(&source_file, move_code)
+        .map_err(|e| ApiError::Internal(format!("Failed to write source file: {}", e)))?;
+
+    Ok(temp_dir)
+}
+
+/// A file of an uploaded Move package
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackageFile {
+    /// Path relative to the package root, e.g. "sources/counter.move"
+    pub path: String,
+    pub content: String,
+}
+
+/// Creates a temp directory holding an uploaded multi-file package
+///
+/// Without a Move.toml one is generated from the sources' imports, as for
+/// single-module requests.
+pub fn create_temp_package(
+    move_toml: Option<&str>,
+    files: &[PackageFile],
+) -> Result<TempDir, ApiError> {
+    if files.is_empty() {
+        return Err(ApiError::BadRequest("Package has no files".to_string()));
+    }
+    for file in files {
+        validate_package_path(&file.path)?;
+    }
+
+    let temp_dir = TempDir::new()
+        .map_err(|e| ApiError::Internal(format!("Failed to create temp directory: {}", e)))?;
+    let base_path = temp_dir.path();
+
+    let move_toml = match move_toml {
+        Some(toml) => {
+            validate_move_toml(toml)?;
+            toml
+        }
+        None => {
+            let all_code: String = files.iter().map(|f| f.content.as_str()).collect();
+            move_toml_for(&all_code)
+        }
+    };
+    std::fs::write(base_path.join("Move.toml"), move_toml)
+        .map_err(|e| ApiError::Internal(format!("Failed to write Move.toml: {}", e)))?;
+
+    for file in files {
+        let path = base_path.join(&file.path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| ApiError::Internal(format!("Failed to create {}: {}", parent.display(), e)))?;
+        }
+        std::fs::write(&path, &file.content)
+            .map_err(|e| ApiError::Internal(format!("Failed to write {}: {}", file.path, e)))?;
+    }
+
+    Ok(temp_dir)
+}
+
+/// Only `.move` files under `sources/` or `tests/` may be uploaded
+fn validate_package_path(path: &str) -> Result<(), ApiError> {
+    let relative = Path::new(path);
+    let in_package_dir = relative
+        .components()
+        .next()
+        .is_some_and(|c| c == Component::Normal("sources".as_ref()) || c == Component::Normal("tests".as_ref()));
+    let plain = relative.components().all(|c| matches!(c, Component::Normal(_)));
+
+    if in_package_dir && plain && path.ends_with(".move") {
+        Ok(())
+    } else {
+        Err(ApiError::BadRequest(format!(
+            "Invalid package file path `{}`: expected sources/<name>.move or tests/<name>.move",
+            path
+        )))
+    }
+}
+
+/// Uploaded manifests may only depend on the framework repositories
+///
+/// The build runs on the server, so `local` paths would read its files and
+/// arbitrary git URLs would have it fetch and compile anything.
+fn validate_move_toml(move_toml: &str) -> Result<(), ApiError> {
+    let manifest: toml::Table = toml::from_str(move_toml)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid Move.toml: {}", e)))?;
+
+    for section in ["dependencies", "dev-dependencies"] {
+        let Some(dependencies) = manifest.get(section) else {
+            continue;
+        };
+        let dependencies = dependencies
+            .as_table()
+            .ok_or_else(|| ApiError::BadRequest(format!("Invalid Move.toml: [{}] must be a table", section)))?;
+        for (name, dependency) in dependencies {
+            validate_dependency(dependency).map_err(|reason| {
+                ApiError::BadRequest(format!("Move.toml dependency `{}` is not allowed: {}", name, reason))
+            })?;
+        }
+    }
+    Ok(())
+}
+
+fn validate_dependency(dependency: &toml::Value) -> Result<(), &'static str> {
+    let dependency = dependency.as_table().ok_or("expected a table")?;
+    if let Some(key) = dependency
+        .keys()
+        .find(|key| !matches!(key.as_str(), "git" | "rev" | "subdir" | "addr_subst"))
+    {
+        return Err(if key == "local" {
+            "local dependencies are not supported"
+        } else {
+            "only git dependencies on the framework repositories are supported"
+        });
+    }
+
+    let git = dependency.get("git").and_then(|git| git.as_str()).ok_or("missing git URL")?;
+    if !ALLOWED_DEPENDENCY_REPOS.contains(&git) {
+        return Err("only the Aptos and Movement framework repositories are allowed");
+    }
+    if let Some(subdir) = dependency.get("subdir") {
+        let plain = subdir
+            .as_str()
+            .is_some_and(|subdir| Path::new(subdir).components().all(|c| matches!(c, Component::Normal(_))));
+        if !plain {
+            return Err("subdir must be a relative path within the repository");
+        }
+    }
+    Ok(())
+}
+
+/// Module names become file names, so only Move identifiers are accepted
+fn validate_module_name(name: &str) -> Result<(), ApiError> {
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && name.len() <= 255;
+
+    if valid {
+        Ok(())
+    } else {
+        Err(ApiError::BadRequest(format!(
+            "Invalid module name `{}`: expected a Move identifier",
+            name
+        )))
+    }
+}
+
+/// A requested CLI timeout, within 1..=MAX_CLI_TIMEOUT_SECS seconds
+pub fn cli_timeout(requested_secs: u32) -> Duration {
+    Duration::from_secs(requested_secs.clamp(1, MAX_CLI_TIMEOUT_SECS) as u64)
+}
+
+/// Whether a `--language-version` value is only digits and dots, e.g. "2.0"
+pub fn is_language_version(version: &str) -> bool {
+    !version.is_empty()
+        && version
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Runs `aptos move <subcommand> --package-dir <path> [args]`
+///
+/// Returns (stdout, stderr, exit_code).
+pub async fn run_aptos_move(
+    subcommand: &str,
+    project_path: &Path,
+    extra_args: &[String],
+) -> Result<(String, String, i32), ApiError> {
+    run_aptos_move_with_env(subcommand, project_path, extra_args, &[]).await
+}
+
+/// Same as `run_aptos_move` with extra environment variables for the CLI
+pub async fn run_aptos_move_with_env(
+    subcommand: &str,
+    project_path: &Path,
+    extra_args: &[String],
+    envs: &[(&str, &str)],
+) -> Result<(String, String, i32), ApiError> {
+    run_aptos_move_streaming(subcommand, project_path, extra_args, envs, |_| {}).await
+}
+
+/// Same as `run_aptos_move_with_env`, calling `on_line` with each line of
+/// stdout or stderr as soon as the CLI prints it
+pub async fn run_aptos_move_streaming(
+    subcommand: &str,
+    project_path: &Path,
+    extra_args: &[String],
+    envs: &[(&str, &str)],
+    mut on_line: impl FnMut(&str),
+) -> Result<(String, String, i32), ApiError> {
+    let io_error = |e: std::io::Error| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            ApiError::Internal("aptos CLI not found. Please install the Aptos CLI.".to_string())
+        } else {
+            ApiError::Internal(format!("Failed to execute aptos move {}: {}", subcommand, e))
+        }
+    };
+
+    let mut child = Command::new("aptos")
+        .arg("move")
+        .args(subcommand.split_whitespace())
+        .arg("--package-dir")
+        .arg(project_path)
+        .args(extra_args)
+        .envs(envs.iter().copied())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(io_error)?;
+
+    let mut stdout_reader = BufReader::new(child.stdout.take().expect("stdout is piped"));
+    let mut stderr_reader = BufReader::new(child.stderr.take().expect("stderr is piped"));
+    let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+    let (mut stdout_line, mut stderr_line) = (Vec::new(), Vec::new());
+    let (mut stdout_open, mut stderr_open) = (true, true);
+
+    // read_until keeps partial lines in the buffer, so losing the race is safe
+    while stdout_open || stderr_open {
+        let (read, line, output, open) = tokio::select! {
+            read = stdout_reader.read_until(b'\n', &mut stdout_line), if stdout_open => {
+                (read, &mut stdout_line, &mut stdout, &mut stdout_open)
+            }
+            read = stderr_reader.read_until(b'\n', &mut stderr_line), if stderr_open => {
+                (read, &mut stderr_line, &mut stderr, &mut stderr_open)
+            }
+        };
+
+        match read.map_err(io_error)? {
+            0 => *open = false,
+            _ => {
+                on_line(String::from_utf8_lossy(line).trim_end());
+                output.append(line);
+            }
+        }
+    }
+
+    let status = child.wait().await.map_err(io_error)?;
+
+    Ok((
+        String::from_utf8_lossy(&stdout).to_string(),
+        String::from_utf8_lossy(&stderr).to_string(),
+        status.code().unwrap_or(-1),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_package_path() {
+        assert!(validate_package_path("sources/counter.move").is_ok());
+        assert!(validate_package_path("tests/counter_tests.move").is_ok());
+        assert!(validate_package_path("sources/../../etc/passwd.move").is_err());
+        assert!(validate_package_path("/tmp/x.move").is_err());
+        assert!(validate_package_path("Move.toml").is_err());
+        assert!(validate_package_path("scripts/run.move").is_err());
+    }
+
+    #[test]
+    fn test_validate_move_toml() {
+        assert!(validate_move_toml(MOVE_TOML_TEMPLATE).is_ok());
+        assert!(validate_move_toml(MOVE_TOML_MINIMAL).is_ok());
+
+        let with = |dependency: &str| format!("{}\n[dependencies]\nDep = {}\n", MOVE_TOML_MINIMAL, dependency);
+        assert!(validate_move_toml(&with(r#"{ local = "/etc" }"#)).is_err());
+        assert!(validate_move_toml(&with(r#"{ git = "https://evil.example/x.git", rev = "main" }"#)).is_err());
+        assert!(validate_move_toml(&with(
+            r#"{ git = "https://github.com/aptos-labs/aptos-core.git", subdir = "../../..", rev = "main" }"#
+        ))
+        .is_err());
+        assert!(validate_move_toml(&with(r#""1.0.0""#)).is_err());
+        assert!(validate_move_toml("not [valid").is_err());
+    }
+
+    #[test]
+    fn test_validate_module_name() {
+        assert!(validate_module_name("counter").is_ok());
+        assert!(validate_module_name("_Vault2").is_ok());
+        assert!(validate_module_name("../../x").is_err());
+        assert!(validate_module_name("a/b").is_err());
+        assert!(validate_module_name("2fast").is_err());
+        assert!(validate_module_name("").is_err());
+        assert!(create_temp_project("../escape", "module 0x1::escape {}").is_err());
+    }
+
+    #[test]
+    fn test_cli_limits() {
+        assert_eq!(cli_timeout(60), Duration::from_secs(60));
+        assert_eq!(cli_timeout(0), Duration::from_secs(1));
+        assert_eq!(cli_timeout(u32::MAX), Duration::from_secs(MAX_CLI_TIMEOUT_SECS as u64));
+
+        assert!(is_language_version("2.0"));
+        assert!(is_language_version("2"));
+        assert!(!is_language_version(""));
+        assert!(!is_language_version("2."));
+        assert!(!is_language_version("2.0 --skip-fetch-latest-git-deps"));
+    }
+}
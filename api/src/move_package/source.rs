@@ -0,0 +1,102 @@
+//! Lightweight Move source scanning helpers
+//!
+//! These work on raw text rather than a parsed AST, which is enough for
+//! locating declarations and spec blocks in submitted modules.
+
+/// Replaces comments with spaces, keeping byte offsets and line numbers intact
+pub fn strip_comments(code: &str) -> String {
+    let bytes = code.as_bytes();
+    let mut out = bytes.to_vec();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i += 1;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    out[i] = b' ';
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                out[i] = b' ';
+                out[i + 1] = b' ';
+                i += 2;
+                while i < bytes.len() && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                    if bytes[i] != b'\n' {
+                        out[i] = b' ';
+                    }
+                    i += 1;
+                }
+                for byte in out.iter_mut().skip(i).take(2) {
+                    *byte = b' ';
+                }
+                i += 2;
+            }
+            _ => i += 1,
+        }
+    }
+
+    // Only ASCII bytes outside string literals were replaced
+    String::from_utf8(out).unwrap_or_else(|_| code.to_string())
+}
+
+/// Finds the `}` matching the `{` at `open`; `None` if it is unbalanced
+pub fn matching_brace(code: &str, open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, b) in code.as_bytes().iter().enumerate().skip(open) {
+        match b {
+            b'{' => depth += 1,
+            b'}' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 1-based line number of a byte offset
+pub fn line_at(code: &str, pos: usize) -> u32 {
+    code.as_bytes()[..pos.min(code.len())]
+        .iter()
+        .filter(|&&b| b == b'\n')
+        .count() as u32
+        + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_comments_keeps_lines() {
+        let stripped = strip_comments("a // b\n/* c\nd */ e");
+        assert_eq!(stripped.lines().count(), 3);
+        assert!(!stripped.contains('b'));
+        assert!(!stripped.contains('c'));
+        assert!(stripped.ends_with('e'));
+    }
+
+    #[test]
+    fn test_matching_brace() {
+        let code = "module a { fun f() { { } } }";
+        assert_eq!(matching_brace(code, 9), Some(code.len() - 1));
+        assert_eq!(matching_brace("module a { ", 9), None);
+        // Starting past the opening brace meets a `}` first
+        assert_eq!(matching_brace(code, 24), None);
+        assert_eq!(line_at("a\nb\nc", 4), 3);
+    }
+}
@@ -0,0 +1,177 @@
+//! Parsing of `aptos move coverage` reports
+//!
+//! Function coverage comes from `coverage summary --summarize-functions`.
+//! Line coverage comes from `coverage source`, which echoes the module source
+//! with covered segments in green and uncovered segments in red.
+
+use regex::Regex;
+
+use super::parser::shorten_address;
+use super::types::{FunctionTestCoverage, LineCoverage, ModuleTestCoverage, TestCoverage};
+
+/// Parses the summary printed by `aptos move coverage summary --summarize-functions`
+pub fn parse_coverage_summary(output: &str) -> Option<TestCoverage> {
+    let module_re = Regex::new(r"^Module\s+(\S+?::\w+)").unwrap();
+    let fun_re = Regex::new(r"^fun\s+(\w+)").unwrap();
+    let field_re = Regex::new(r"^(total|covered|% coverage):\s*([\d.]+)").unwrap();
+    let module_percent_re = Regex::new(r"% Module coverage:\s*([\d.]+)").unwrap();
+    let total_re = Regex::new(r"% Move Coverage:\s*([\d.]+)").unwrap();
+
+    let mut modules: Vec<ModuleTestCoverage> = Vec::new();
+    let mut total = None;
+
+    for line in output.lines() {
+        let line = line.trim();
+
+        if let Some(cap) = module_re.captures(line) {
+            modules.push(ModuleTestCoverage {
+                name: shorten_address(&cap[1]),
+                percent: 0.0,
+                functions: vec![],
+                lines: None,
+            });
+        } else if let Some(cap) = fun_re.captures(line) {
+            if let Some(module) = modules.last_mut() {
+                module.functions.push(FunctionTestCoverage {
+                    name: cap[1].to_string(),
+                    total: 0,
+                    covered: 0,
+                    percent: 0.0,
+                });
+            }
+        } else if let Some(cap) = field_re.captures(line) {
+            let Some(function) = modules.last_mut().and_then(|m| m.functions.last_mut()) else {
+                continue;
+            };
+            match &cap[1] {
+                "total" => function.total = cap[2].parse().unwrap_or(0),
+                "covered" => function.covered = cap[2].parse().unwrap_or(0),
+                _ => function.percent = cap[2].parse().unwrap_or(0.0),
+            }
+        } else if let Some(cap) = module_percent_re.captures(line) {
+            if let Some(module) = modules.last_mut() {
+                module.percent = cap[1].parse().unwrap_or(0.0);
+            }
+        } else if let Some(cap) = total_re.captures(line) {
+            total = cap[1].parse().ok();
+        }
+    }
+
+    Some(TestCoverage {
+        percent: total?,
+        modules,
+    })
+}
+
+/// Classifies each source line from `aptos move coverage source` output
+///
+/// Lines with any uncovered code count as uncovered; lines without colored
+/// code are not executable and appear in neither list.
+pub fn parse_source_coverage(output: &str) -> LineCoverage {
+    let ansi_re = Regex::new(r"\x1b\[([\d;]*)m").unwrap();
+
+    let mut covered_lines = Vec::new();
+    let mut uncovered_lines = Vec::new();
+    // Colors may span lines, so track the active one across the whole output
+    let mut color = 0u8;
+
+    for (index, line) in output.lines().enumerate() {
+        let mut has_covered = false;
+        let mut has_uncovered = false;
+        let mut last = 0;
+
+        let mut classify = |text: &str, color: u8| {
+            if !text.trim().is_empty() {
+                match color {
+                    31 => has_uncovered = true,
+                    32 => has_covered = true,
+                    _ => {}
+                }
+            }
+        };
+
+        for cap in ansi_re.captures_iter(line) {
+            let whole = cap.get(0).unwrap();
+            classify(&line[last..whole.start()], color);
+            last = whole.end();
+
+            for code in cap[1].split(';') {
+                match code {
+                    "" | "0" | "39" => color = 0,
+                    "31" | "32" => color = code.parse().unwrap_or(0),
+                    _ => {}
+                }
+            }
+        }
+        classify(&line[last..], color);
+
+        let line_number = index as u32 + 1;
+        if has_uncovered {
+            uncovered_lines.push(line_number);
+        } else if has_covered {
+            covered_lines.push(line_number);
+        }
+    }
+
+    let executable = covered_lines.len() + uncovered_lines.len();
+    let percent = if executable == 0 {
+        100.0
+    } else {
+        (covered_lines.len() as f64 / executable as f64 * 10000.0).round() / 100.0
+    };
+
+    LineCoverage {
+        covered_lines,
+        uncovered_lines,
+        percent,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_coverage_summary() {
+        let output = "+-------------------------+
+| Move Coverage Summary   |
++-------------------------+
+Module 0000000000000000000000000000000000000000000000000000000000000001::counter
+\t fun increment
+\t\t total: 12
+\t\t covered: 9
+\t\t % coverage: 75.00
+\t fun get_value
+\t\t total: 4
+\t\t covered: 4
+\t\t % coverage: 100.00
+>>> % Module coverage: 81.25
++-------------------------+
+| % Move Coverage: 81.25  |
++-------------------------+
+";
+        let coverage = parse_coverage_summary(output).unwrap();
+        assert_eq!(coverage.percent, 81.25);
+        assert_eq!(coverage.modules.len(), 1);
+
+        let module = &coverage.modules[0];
+        assert_eq!(module.name, "0x1::counter");
+        assert_eq!(module.percent, 81.25);
+        assert_eq!(module.functions.len(), 2);
+        assert_eq!(module.functions[0].name, "increment");
+        assert_eq!(module.functions[0].total, 12);
+        assert_eq!(module.functions[0].covered, 9);
+        assert_eq!(module.functions[1].percent, 100.0);
+
+        assert!(parse_coverage_summary("no coverage here").is_none());
+    }
+
+    #[test]
+    fn test_parse_source_coverage() {
+        let output = "module 0x1::counter {\n    fun f(x: u64): u64 {\n\x1b[32m        if (x > 0) \x1b[0m{\n\x1b[1;31m            abort 1\x1b[0m\n        };\n\x1b[32m        x\x1b[0m\n    }\n}\n";
+        let lines = parse_source_coverage(output);
+        assert_eq!(lines.covered_lines, vec![3, 6]);
+        assert_eq!(lines.uncovered_lines, vec![4]);
+        assert_eq!(lines.percent, 66.67);
+    }
+}
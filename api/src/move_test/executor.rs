@@ -0,0 +1,206 @@
+use std::path::Path;
+use std::time::Instant;
+use tokio::time::timeout;
+
+use super::coverage::{parse_coverage_summary, parse_source_coverage};
+use super::parser::parse_test_output;
+use super::types::{TestCaseStatus, TestCoverage, TestRequest, TestResult, TestRunStatus};
+use crate::compiler::diagnostics::parse_diagnostics;
+use crate::compiler::DiagnosticOrigin;
+use crate::error::ApiError;
+use crate::move_package::{
+    cli_timeout, create_temp_package, run_aptos_move, run_aptos_move_streaming,
+    run_aptos_move_with_env,
+};
+use crate::progress::{Phase, Progress};
+
+pub struct TestExecutor;
+
+impl TestExecutor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Runs the tests, reporting when the build finishes and coverage starts
+    pub async fn execute(
+        &self,
+        request: TestRequest,
+        progress: &Progress,
+    ) -> Result<TestResult, ApiError> {
+        let start = Instant::now();
+        progress.phase(Phase::Compiling);
+        let timeout_duration = cli_timeout(request.timeout_seconds);
+
+        let temp_dir = create_temp_package(request.move_toml.as_deref(), &request.files)?;
+        let temp_path = temp_dir.path().to_path_buf();
+
+        let package_args = self.package_args(&request);
+        let mut test_args = package_args.clone();
+        if let Some(filter) = &request.filter {
+            test_args.push("--filter".to_string());
+            test_args.push(filter.clone());
+        }
+        if let Some(limit) = request.instruction_limit {
+            test_args.push("--instructions".to_string());
+            test_args.push(limit.to_string());
+        }
+        if request.coverage {
+            test_args.push("--coverage".to_string());
+        }
+
+        let result = timeout(timeout_duration, async {
+            let mut testing = false;
+            let (stdout, stderr, exit_code) =
+                run_aptos_move_streaming("test", &temp_path, &test_args, &[], |line| {
+                    if !testing && line.contains("Running Move unit tests") {
+                        testing = true;
+                        progress.phase(Phase::Testing);
+                    }
+                })
+                .await?;
+            let output = format!("{}\n{}", stdout, stderr);
+
+            let tests = parse_test_output(&output);
+            let coverage = if request.coverage && !tests.is_empty() {
+                progress.phase(Phase::Coverage);
+                self.collect_coverage(&temp_path, &package_args).await
+            } else {
+                None
+            };
+
+            Ok::<_, ApiError>((output, exit_code, tests, coverage))
+        })
+        .await;
+
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        let (output, exit_code, tests, coverage) = match result {
+            Ok(outputs) => outputs?,
+            Err(_) => {
+                return Ok(TestResult {
+                    status: TestRunStatus::Timeout,
+                    duration_ms,
+                    tests: vec![],
+                    passed: 0,
+                    failed: 0,
+                    diagnostics: vec![],
+                    coverage: None,
+                    summary: format!("Tests timed out after {} seconds", timeout_duration.as_secs()),
+                    raw_output: None,
+                });
+            }
+        };
+
+        let passed = tests
+            .iter()
+            .filter(|t| t.status == TestCaseStatus::Passed)
+            .count();
+        let failed = tests.len() - passed;
+
+        let status = if exit_code == 0 {
+            TestRunStatus::Passed
+        } else if failed > 0 {
+            TestRunStatus::Failed
+        } else {
+            TestRunStatus::Error
+        };
+
+        // Without any results the package most likely failed to build
+        let diagnostics = if status == TestRunStatus::Error {
+            parse_diagnostics(&output, DiagnosticOrigin::Compiler)
+        } else {
+            vec![]
+        };
+
+        let summary = match status {
+            TestRunStatus::Passed if tests.is_empty() => "No tests found".to_string(),
+            TestRunStatus::Passed => format!("All {} test(s) passed", passed),
+            TestRunStatus::Failed => {
+                format!("{} of {} test(s) failed", failed, tests.len())
+            }
+            _ => format!(
+                "Test run failed with {} build error(s)",
+                diagnostics.len()
+            ),
+        };
+
+        Ok(TestResult {
+            status,
+            duration_ms,
+            tests,
+            passed,
+            failed,
+            diagnostics,
+            coverage,
+            summary,
+            raw_output: Some(output),
+        })
+    }
+
+    /// Arguments shared by `test` and `coverage`, which both rebuild the package
+    fn package_args(&self, request: &TestRequest) -> Vec<String> {
+        if request.named_addresses.is_empty() {
+            return vec![];
+        }
+
+        let addresses = request
+            .named_addresses
+            .iter()
+            .map(|(name, address)| format!("{}={}", name, address))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        vec!["--named-addresses".to_string(), addresses]
+    }
+
+    /// Reads function and line coverage left behind by `aptos move test --coverage`
+    ///
+    /// Coverage is best effort: a failing report is logged and omitted rather
+    /// than failing the test run.
+    async fn collect_coverage(
+        &self,
+        project_path: &Path,
+        package_args: &[String],
+    ) -> Option<TestCoverage> {
+        let mut summary_args = package_args.to_vec();
+        summary_args.push("--summarize-functions".to_string());
+
+        let (stdout, stderr, _) =
+            match run_aptos_move("coverage summary", project_path, &summary_args).await {
+                Ok(output) => output,
+                Err(e) => {
+                    tracing::warn!("Failed to read coverage summary: {}", e);
+                    return None;
+                }
+            };
+        let mut coverage = parse_coverage_summary(&format!("{}\n{}", stdout, stderr))?;
+
+        for module in coverage.modules.iter_mut() {
+            let Some((_, name)) = module.name.rsplit_once("::") else {
+                continue;
+            };
+
+            let mut source_args = package_args.to_vec();
+            source_args.push("--module".to_string());
+            source_args.push(name.to_string());
+
+            // The source view is only colored when forced, since stdout is a pipe
+            match run_aptos_move_with_env(
+                "coverage source",
+                project_path,
+                &source_args,
+                &[("CLICOLOR_FORCE", "1")],
+            )
+            .await
+            {
+                Ok((stdout, _, 0)) => module.lines = Some(parse_source_coverage(&stdout)),
+                Ok((_, stderr, _)) => {
+                    tracing::warn!("Failed to read line coverage for {}: {}", module.name, stderr)
+                }
+                Err(e) => tracing::warn!("Failed to read line coverage for {}: {}", module.name, e),
+            }
+        }
+
+        Some(coverage)
+    }
+}
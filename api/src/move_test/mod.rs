@@ -0,0 +1,7 @@
+pub mod coverage;
+pub mod executor;
+pub mod parser;
+pub mod types;
+
+pub use executor::TestExecutor;
+pub use types::*;
@@ -0,0 +1,196 @@
+//! Parsing of `aptos move test` output
+//!
+//! Results are printed one per line as `[ PASS    ] 0x1::module::test`,
+//! followed by a failure report per module:
+//!
+//! ```text
+//! Failures in 0x1::counter_tests:
+//!
+//! ┌── test_decrement ──────
+//! │ error[E11001]: test failure
+//! │ ... aborted with code 1 originating in the module 0000...0001::counter rooted here
+//! └──────────────────
+//! ```
+
+use regex::Regex;
+use std::collections::HashMap;
+
+use super::types::{TestAbort, TestCase, TestCaseStatus};
+
+/// Builds a test case for every result line, attaching failure reports
+pub fn parse_test_output(output: &str) -> Vec<TestCase> {
+    let result_re =
+        Regex::new(r"(?m)^\[\s*(PASS|FAIL|TIMEOUT)\s*\]\s+(\S+?::\w+)::(\w+)\s*$").unwrap();
+
+    let failures = parse_failures(output);
+    let gas = parse_statistics(output);
+
+    result_re
+        .captures_iter(output)
+        .map(|cap| {
+            let module = shorten_address(&cap[2]);
+            let function = cap[3].to_string();
+            let name = format!("{}::{}", module, function);
+
+            let status = match &cap[1] {
+                "PASS" => TestCaseStatus::Passed,
+                "FAIL" => TestCaseStatus::Failed,
+                _ => TestCaseStatus::Timeout,
+            };
+
+            let failure = failures.get(&name).cloned();
+            let abort = failure.as_deref().and_then(parse_abort);
+
+            TestCase {
+                gas_used: gas.get(&name).copied(),
+                name,
+                module,
+                function,
+                status,
+                abort,
+                failure,
+            }
+        })
+        .collect()
+}
+
+/// Collects failure reports keyed by fully qualified test name
+fn parse_failures(output: &str) -> HashMap<String, String> {
+    let module_re = Regex::new(r"^Failures in (\S+?::\w+):").unwrap();
+    let open_re = Regex::new(r"^┌──\s*(\w+)\s*─*").unwrap();
+
+    let mut failures = HashMap::new();
+    let mut module = String::new();
+    let mut current: Option<(String, Vec<&str>)> = None;
+
+    for line in output.lines() {
+        if let Some(cap) = module_re.captures(line) {
+            module = shorten_address(&cap[1]);
+        } else if let Some(cap) = open_re.captures(line) {
+            current = Some((format!("{}::{}", module, &cap[1]), vec![]));
+        } else if line.starts_with('└') {
+            if let Some((name, body)) = current.take() {
+                failures.insert(name, body.join("\n").trim().to_string());
+            }
+        } else if let Some((_, body)) = current.as_mut() {
+            let content = line.strip_prefix('│').unwrap_or(line);
+            body.push(content.strip_prefix(' ').unwrap_or(content));
+        }
+    }
+
+    failures
+}
+
+/// Extracts the abort code and originating module from a failure report
+fn parse_abort(failure: &str) -> Option<TestAbort> {
+    let abort_re = Regex::new(
+        r"aborted with code (0x[0-9a-fA-F]+|\d+)(?: originating in the module (\S+?::\w+))?",
+    )
+    .unwrap();
+
+    let cap = abort_re.captures(failure)?;
+    let code = match cap[1].strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+        None => cap[1].parse().ok()?,
+    };
+
+    Some(TestAbort {
+        code,
+        module: cap.get(2).map(|m| shorten_address(m.as_str())),
+    })
+}
+
+/// Reads gas per test from a statistics table, when the CLI prints one
+fn parse_statistics(output: &str) -> HashMap<String, u64> {
+    let row_re =
+        Regex::new(r"(?m)^│\s*(\S+?::\w+)::(\w+)\s*│\s*[\d.]+s?\s*│\s*(\d+)\s*│").unwrap();
+
+    row_re
+        .captures_iter(output)
+        .filter_map(|cap| {
+            let name = format!("{}::{}", shorten_address(&cap[1]), &cap[2]);
+            Some((name, cap[3].parse().ok()?))
+        })
+        .collect()
+}
+
+/// Trims zero padding from `000...01::module` to `0x1::module`
+pub fn shorten_address(qualified: &str) -> String {
+    let (address, rest) = qualified.split_once("::").unwrap_or((qualified, ""));
+    let hex = match address.strip_prefix("0x") {
+        Some(hex) => hex,
+        None if address.len() == 64 => address,
+        None => return qualified.to_string(),
+    };
+
+    if hex.is_empty() || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        // Named address, leave as-is
+        return qualified.to_string();
+    }
+
+    let trimmed = hex.trim_start_matches('0');
+    let trimmed = if trimmed.is_empty() { "0" } else { trimmed };
+    if rest.is_empty() {
+        format!("0x{}", trimmed)
+    } else {
+        format!("0x{}::{}", trimmed, rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "INCLUDING DEPENDENCY MoveStdlib
+BUILDING sentinel_verify
+Running Move unit tests
+[ PASS    ] 0x1::counter_tests::test_increment
+[ FAIL    ] 0x1::counter_tests::test_decrement
+[ TIMEOUT ] 0x1::counter_tests::test_loop
+Test failures:
+
+Failures in 0x1::counter_tests:
+
+┌── test_decrement ──────
+│ error[E11001]: test failure
+│    ┌─ /tmp/.tmpAbC/sources/counter.move:27:9
+│    │
+│ 27 │         assert!(counter.value >= amount, E_UNDERFLOW);
+│    │         ^^^^^^ Test was not expected to error, but it aborted with code 2 originating in the module 0000000000000000000000000000000000000000000000000000000000000001::counter rooted here
+│
+│
+└──────────────────
+
+Test result: FAILED. Total tests: 3; passed: 1; failed: 2
+";
+
+    #[test]
+    fn test_parse_test_output() {
+        let tests = parse_test_output(OUTPUT);
+        assert_eq!(tests.len(), 3);
+
+        assert_eq!(tests[0].name, "0x1::counter_tests::test_increment");
+        assert_eq!(tests[0].status, TestCaseStatus::Passed);
+        assert!(tests[0].failure.is_none());
+
+        let failed = &tests[1];
+        assert_eq!(failed.module, "0x1::counter_tests");
+        assert_eq!(failed.function, "test_decrement");
+        assert_eq!(failed.status, TestCaseStatus::Failed);
+        assert!(failed.failure.as_ref().unwrap().starts_with("error[E11001]: test failure"));
+        assert_eq!(
+            failed.abort,
+            Some(TestAbort { code: 2, module: Some("0x1::counter".to_string()) })
+        );
+
+        assert_eq!(tests[2].status, TestCaseStatus::Timeout);
+    }
+
+    #[test]
+    fn test_shorten_address() {
+        assert_eq!(shorten_address("0000000000000000000000000000000000000000000000000000000000000001::counter"), "0x1::counter");
+        assert_eq!(shorten_address("0x00a1::m"), "0xa1::m");
+        assert_eq!(shorten_address("sentinel_demo::m"), "sentinel_demo::m");
+        assert_eq!(shorten_address("cafe::m"), "cafe::m");
+    }
+}
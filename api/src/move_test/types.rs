@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::compiler::Diagnostic;
+use crate::move_package::PackageFile;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TestRequest {
+    /// Package sources and tests, e.g. "sources/counter.move" and
+    /// "tests/counter_tests.move"
+    pub files: Vec<PackageFile>,
+    /// Package manifest; generated from the sources' imports when omitted.
+    /// Dependencies must be git dependencies on the framework repositories
+    #[serde(default)]
+    pub move_toml: Option<String>,
+    /// Values for named addresses left as "_" in the manifest
+    #[serde(default)]
+    pub named_addresses: BTreeMap<String, String>,
+    /// Only run tests whose name contains this string
+    #[serde(default)]
+    pub filter: Option<String>,
+    /// Collect line and function coverage
+    #[serde(default)]
+    pub coverage: bool,
+    /// Per-test instruction bound (`--instructions`)
+    #[serde(default)]
+    pub instruction_limit: Option<u64>,
+    #[serde(default = "default_timeout")]
+    pub timeout_seconds: u32,
+}
+
+fn default_timeout() -> u32 {
+    120
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TestRunStatus {
+    Passed,
+    Failed,
+    Error,
+    Timeout,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TestResult {
+    pub status: TestRunStatus,
+    pub duration_ms: u64,
+    pub tests: Vec<TestCase>,
+    pub passed: usize,
+    pub failed: usize,
+    /// Build errors when the package did not compile
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<Diagnostic>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<TestCoverage>,
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_output: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TestCaseStatus {
+    Passed,
+    Failed,
+    Timeout,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TestCase {
+    /// Fully qualified name, e.g. "0x1::counter_tests::test_increment"
+    pub name: String,
+    pub module: String,
+    pub function: String,
+    pub status: TestCaseStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abort: Option<TestAbort>,
+    /// Only reported when the CLI prints test statistics
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<u64>,
+    /// Failure report printed by the test runner
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TestAbort {
+    pub code: u64,
+    /// Module the abort originated in, e.g. "0x1::counter"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TestCoverage {
+    /// Overall percentage reported by the CLI
+    pub percent: f64,
+    pub modules: Vec<ModuleTestCoverage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleTestCoverage {
+    /// Fully qualified module name, e.g. "0x1::counter"
+    pub name: String,
+    pub percent: f64,
+    pub functions: Vec<FunctionTestCoverage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lines: Option<LineCoverage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionTestCoverage {
+    pub name: String,
+    /// Executable bytecode instructions in the function
+    pub total: u64,
+    pub covered: u64,
+    pub percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LineCoverage {
+    pub covered_lines: Vec<u32>,
+    pub uncovered_lines: Vec<u32>,
+    pub percent: f64,
+}
@@ -0,0 +1,253 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+
+use super::client::MovementClient;
+use super::types::{AccountInfo, FunctionCall, MoveModuleAbi, SimulationTransaction, TableItemRequest};
+use crate::cache::{self, CacheBackend};
+use crate::error::ApiError;
+use crate::simulation::expectations::normalize_address;
+
+/// Read-through cache for module ABIs and account sequence numbers
+///
+/// Both change rarely compared to how often dashboards ask for them. A
+/// stale entry is recovered from rather than served: a simulation rejected
+/// for its sequence number refetches the account and runs again, and a
+/// function missing from a cached ABI refetches the module. Everything else
+/// goes straight to the wrapped client.
+pub struct CachedMovementClient {
+    inner: Arc<dyn MovementClient>,
+    cache: Arc<dyn CacheBackend>,
+}
+
+impl CachedMovementClient {
+    pub fn new(inner: Arc<dyn MovementClient>, cache: Arc<dyn CacheBackend>) -> Self {
+        Self { inner, cache }
+    }
+
+    async fn fetch_account(
+        &self,
+        network: &str,
+        address: &str,
+    ) -> Result<Option<AccountInfo>, ApiError> {
+        let account = self.inner.get_account(network, address).await?;
+        if let Some(account) = &account {
+            let json = serde_json::to_string(account)?;
+            if let Err(e) = cache::cache_account(self.cache.as_ref(), network, address, &json).await {
+                tracing::warn!("Failed to cache account {}: {}", address, e);
+            }
+        }
+        Ok(account)
+    }
+
+    async fn fetch_module(
+        &self,
+        network: &str,
+        module_id: &str,
+        address: &str,
+        module_name: &str,
+    ) -> Result<Option<MoveModuleAbi>, ApiError> {
+        let module = self.inner.get_module(network, address, module_name).await?;
+        if let Some(module) = &module {
+            let json = serde_json::to_string(module)?;
+            if let Err(e) = cache::cache_module(self.cache.as_ref(), network, module_id, &json).await {
+                tracing::warn!("Failed to cache module {}: {}", module_id, e);
+            }
+        }
+        Ok(module)
+    }
+}
+
+/// True when the node rejected a transaction for its sequence number
+fn is_stale_sequence(result: &Result<Value, ApiError>) -> bool {
+    match result {
+        Ok(tx) => tx
+            .get("vm_status")
+            .and_then(|v| v.as_str())
+            .is_some_and(|status| status.contains("SEQUENCE_NUMBER_TOO")),
+        Err(ApiError::SimulationFailed(message)) => message.contains("SEQUENCE_NUMBER_TOO"),
+        Err(_) => false,
+    }
+}
+
+#[async_trait]
+impl MovementClient for CachedMovementClient {
+    async fn get_account(&self, network: &str, address: &str) -> Result<Option<AccountInfo>, ApiError> {
+        let address = normalize_address(address);
+
+        if let Some(json) = cache::get_cached_account(self.cache.as_ref(), network, &address).await {
+            if let Ok(account) = serde_json::from_str(&json) {
+                return Ok(Some(account));
+            }
+        }
+
+        self.fetch_account(network, &address).await
+    }
+
+    async fn get_resource(
+        &self,
+        network: &str,
+        address: &str,
+        resource_type: &str,
+    ) -> Result<Option<Value>, ApiError> {
+        self.inner.get_resource(network, address, resource_type).await
+    }
+
+    async fn get_module(
+        &self,
+        network: &str,
+        address: &str,
+        module_name: &str,
+    ) -> Result<Option<MoveModuleAbi>, ApiError> {
+        let address = normalize_address(address);
+        let module_id = format!("{}::{}", address, module_name);
+
+        if let Some(json) = cache::get_cached_module(self.cache.as_ref(), network, &module_id).await {
+            if let Ok(module) = serde_json::from_str(&json) {
+                return Ok(Some(module));
+            }
+        }
+
+        self.fetch_module(network, &module_id, &address, module_name).await
+    }
+
+    async fn get_table_item(
+        &self,
+        network: &str,
+        handle: &str,
+        item: &TableItemRequest,
+    ) -> Result<Option<Value>, ApiError> {
+        self.inner.get_table_item(network, handle, item).await
+    }
+
+    async fn view(&self, network: &str, call: &FunctionCall) -> Result<Vec<Value>, ApiError> {
+        self.inner.view(network, call).await
+    }
+
+    async fn simulate(&self, network: &str, transaction: &SimulationTransaction) -> Result<Value, ApiError> {
+        let result = self.inner.simulate(network, transaction).await;
+        if !is_stale_sequence(&result) {
+            return result;
+        }
+
+        // The cached sequence number is behind the chain
+        let address = normalize_address(&transaction.sender);
+        if let Err(e) = cache::invalidate_account(self.cache.as_ref(), network, &address).await {
+            tracing::warn!("Failed to invalidate account {}: {}", address, e);
+        }
+        let sequence_number = self
+            .fetch_account(network, &address)
+            .await?
+            .map(|account| account.sequence_number)
+            .unwrap_or(0);
+        if sequence_number == transaction.sequence_number {
+            return result;
+        }
+
+        tracing::info!(
+            "Retrying simulation for {} with sequence number {}",
+            address,
+            sequence_number
+        );
+        let retry = SimulationTransaction {
+            sequence_number,
+            ..transaction.clone()
+        };
+        self.inner.simulate(network, &retry).await
+    }
+
+    async fn get_transaction(&self, network: &str, hash: &str) -> Result<Option<Value>, ApiError> {
+        self.inner.get_transaction(network, hash).await
+    }
+
+    /// A function missing from a cached ABI means the module was
+    /// republished since it was cached
+    async fn is_view_function(
+        &self,
+        network: &str,
+        address: &str,
+        module_name: &str,
+        function_name: &str,
+    ) -> bool {
+        let Ok(Some(module)) = self.get_module(network, address, module_name).await else {
+            return false;
+        };
+        if let Some(view_only) = module.is_view_only(function_name) {
+            return view_only;
+        }
+
+        let address = normalize_address(address);
+        let module_id = format!("{}::{}", address, module_name);
+        if let Err(e) = cache::invalidate_module(self.cache.as_ref(), network, &module_id).await {
+            tracing::warn!("Failed to invalidate module {}: {}", module_id, e);
+        }
+
+        match self.fetch_module(network, &module_id, &address, module_name).await {
+            Ok(Some(module)) => module.is_view_only(function_name).unwrap_or(false),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::MemoryCache;
+    use crate::movement::MockMovementClient;
+
+    #[test]
+    fn test_stale_sequence_detection() {
+        assert!(is_stale_sequence(&Ok(MockMovementClient::transaction(
+            false,
+            "SEQUENCE_NUMBER_TOO_OLD",
+            0
+        ))));
+        assert!(is_stale_sequence(&Err(ApiError::SimulationFailed(
+            r#"{"message":"Invalid transaction: Type: Validation Code: SEQUENCE_NUMBER_TOO_NEW"}"#.to_string()
+        ))));
+        assert!(!is_stale_sequence(&Ok(MockMovementClient::transaction(true, "Executed successfully", 10))));
+        assert!(!is_stale_sequence(&Err(ApiError::RpcError("SEQUENCE_NUMBER_TOO_OLD".to_string()))));
+    }
+
+    #[tokio::test]
+    async fn test_stale_cached_sequence_is_refreshed() {
+        let mock = Arc::new(
+            MockMovementClient::new()
+                .with_account("0xa11ce", 7)
+                .with_simulation(
+                    "0x1::coin::transfer",
+                    Ok(MockMovementClient::transaction(false, "SEQUENCE_NUMBER_TOO_OLD", 0)),
+                )
+                .with_simulation(
+                    "0x1::coin::transfer",
+                    Ok(MockMovementClient::transaction(true, "Executed successfully", 10)),
+                ),
+        );
+        let cache = Arc::new(MemoryCache::new(16));
+        let stale = serde_json::to_string(&AccountInfo {
+            sequence_number: 3,
+            authentication_key: "0xa11ce".to_string(),
+        })
+        .unwrap();
+        cache::cache_account(cache.as_ref(), "testnet", "0xa11ce", &stale).await.unwrap();
+
+        let client = CachedMovementClient::new(mock.clone(), cache);
+        let account = client.get_account("testnet", "0xA11CE").await.unwrap().unwrap();
+        assert_eq!(account.sequence_number, 3);
+
+        let transaction = SimulationTransaction {
+            sender: "0xa11ce".to_string(),
+            sequence_number: account.sequence_number,
+            max_gas_amount: 100_000,
+            gas_unit_price: 100,
+            call: FunctionCall::new("0x1", "coin", "transfer", &[], &[]),
+            public_key: None,
+        };
+        let result = client.simulate("testnet", &transaction).await.unwrap();
+        assert_eq!(result["success"], true);
+
+        let sequences: Vec<u64> = mock.simulate_calls().iter().map(|t| t.sequence_number).collect();
+        assert_eq!(sequences, [3, 7]);
+        assert_eq!(client.get_account("testnet", "0xa11ce").await.unwrap().unwrap().sequence_number, 7);
+    }
+}
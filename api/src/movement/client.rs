@@ -0,0 +1,61 @@
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::types::{AccountInfo, FunctionCall, MoveModuleAbi, SimulationTransaction, TableItemRequest};
+use crate::error::ApiError;
+
+/// Typed access to a Movement full node
+///
+/// Lookups of things that may not exist return `Ok(None)` for a 404;
+/// `view` and `simulate` return `ApiError::SimulationFailed` with the node's
+/// message when it rejects the request.
+#[async_trait]
+pub trait MovementClient: Send + Sync {
+    async fn get_account(&self, network: &str, address: &str) -> Result<Option<AccountInfo>, ApiError>;
+
+    /// The `data` of a resource
+    async fn get_resource(
+        &self,
+        network: &str,
+        address: &str,
+        resource_type: &str,
+    ) -> Result<Option<Value>, ApiError>;
+
+    async fn get_module(
+        &self,
+        network: &str,
+        address: &str,
+        module_name: &str,
+    ) -> Result<Option<MoveModuleAbi>, ApiError>;
+
+    async fn get_table_item(
+        &self,
+        network: &str,
+        handle: &str,
+        item: &TableItemRequest,
+    ) -> Result<Option<Value>, ApiError>;
+
+    /// Return values of a view function
+    async fn view(&self, network: &str, call: &FunctionCall) -> Result<Vec<Value>, ApiError>;
+
+    /// The simulated transaction as returned by the node
+    async fn simulate(&self, network: &str, transaction: &SimulationTransaction) -> Result<Value, ApiError>;
+
+    async fn get_transaction(&self, network: &str, hash: &str) -> Result<Option<Value>, ApiError>;
+
+    /// True for view functions and non-entry functions, which can only be
+    /// called through `view`. Lookup failures count as "not a view".
+    async fn is_view_function(
+        &self,
+        network: &str,
+        address: &str,
+        module_name: &str,
+        function_name: &str,
+    ) -> bool {
+        let Ok(Some(module)) = self.get_module(network, address, module_name).await else {
+            return false;
+        };
+
+        module.is_view_only(function_name).unwrap_or(false)
+    }
+}
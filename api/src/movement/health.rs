@@ -0,0 +1,280 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::network::{NetworkRegistry, RpcEndpoint};
+
+/// Consecutive failures that open an endpoint's circuit
+const FAILURE_THRESHOLD: u32 = 5;
+
+/// How long an open circuit rejects requests before letting one through
+const OPEN_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Weight of the newest sample in the latency and error-rate averages
+const EWMA_ALPHA: f64 = 0.2;
+
+/// Endpoints whose recent error rate exceeds this are tried last
+const DEGRADED_ERROR_RATE: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+#[derive(Debug, Default)]
+struct EndpointStats {
+    requests: u64,
+    failures: u64,
+    consecutive_failures: u32,
+    latency_ms: Option<f64>,
+    error_rate: f64,
+    last_error: Option<String>,
+    opened_at: Option<Instant>,
+    /// Why the endpoint is never used, e.g. it serves the wrong chain
+    disabled: Option<String>,
+}
+
+impl EndpointStats {
+    fn circuit(&self, now: Instant) -> CircuitState {
+        match self.opened_at {
+            Some(at) if now.duration_since(at) < OPEN_COOLDOWN => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+            None => CircuitState::Closed,
+        }
+    }
+
+    fn is_degraded(&self, now: Instant) -> bool {
+        self.disabled.is_some()
+            || self.consecutive_failures > 0
+            || self.error_rate > DEGRADED_ERROR_RATE
+            || self.circuit(now) != CircuitState::Closed
+    }
+}
+
+/// Health of one endpoint as reported by `/health`
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointStatus {
+    pub network: String,
+    pub url: String,
+    pub circuit: CircuitState,
+    pub healthy: bool,
+    pub requests: u64,
+    pub failures: u64,
+    pub error_rate: f64,
+    pub latency_ms: Option<f64>,
+    pub last_error: Option<String>,
+    pub disabled: Option<String>,
+}
+
+/// Per-endpoint latency and error tracking with circuit breaking
+///
+/// Endpoints keep their configured order while healthy; ones that have been
+/// failing are tried after the rest, and after `FAILURE_THRESHOLD`
+/// consecutive failures an endpoint is skipped entirely until the cooldown
+/// passes and a probe request succeeds.
+pub struct RpcHealth {
+    endpoints: HashMap<(String, String), Mutex<EndpointStats>>,
+    order: Vec<(String, String)>,
+}
+
+impl RpcHealth {
+    pub fn new(networks: &NetworkRegistry) -> Self {
+        let order: Vec<(String, String)> = networks
+            .iter()
+            .flat_map(|network| {
+                network
+                    .endpoints
+                    .iter()
+                    .map(|e| (network.name.clone(), e.url.clone()))
+            })
+            .collect();
+        Self::with_endpoints(order)
+    }
+
+    fn with_endpoints(order: Vec<(String, String)>) -> Self {
+        Self {
+            endpoints: order
+                .iter()
+                .map(|key| (key.clone(), Mutex::new(EndpointStats::default())))
+                .collect(),
+            order,
+        }
+    }
+
+    /// Endpoints to try, healthy ones first; open circuits are left out
+    pub fn ordered<'a>(&self, network: &str, endpoints: &'a [RpcEndpoint]) -> Vec<&'a RpcEndpoint> {
+        self.ordered_at(network, endpoints, Instant::now())
+    }
+
+    fn ordered_at<'a>(
+        &self,
+        network: &str,
+        endpoints: &'a [RpcEndpoint],
+        now: Instant,
+    ) -> Vec<&'a RpcEndpoint> {
+        let mut available: Vec<(bool, &RpcEndpoint)> = endpoints
+            .iter()
+            .filter_map(|endpoint| match self.stats(network, &endpoint.url) {
+                Some(stats) => {
+                    let stats = stats.lock().unwrap();
+                    (stats.disabled.is_none() && stats.circuit(now) != CircuitState::Open)
+                        .then(|| (stats.is_degraded(now), endpoint))
+                }
+                None => Some((false, endpoint)),
+            })
+            .collect();
+
+        // Stable, so configured order is kept within each group
+        available.sort_by_key(|(degraded, _)| *degraded);
+        available.into_iter().map(|(_, endpoint)| endpoint).collect()
+    }
+
+    pub fn record_success(&self, network: &str, url: &str, latency: Duration) {
+        let Some(stats) = self.stats(network, url) else {
+            return;
+        };
+        let mut stats = stats.lock().unwrap();
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+
+        stats.requests += 1;
+        stats.consecutive_failures = 0;
+        stats.error_rate *= 1.0 - EWMA_ALPHA;
+        stats.latency_ms = Some(match stats.latency_ms {
+            Some(avg) => avg + EWMA_ALPHA * (latency_ms - avg),
+            None => latency_ms,
+        });
+        stats.opened_at = None;
+    }
+
+    pub fn record_failure(&self, network: &str, url: &str, error: String) {
+        self.record_failure_at(network, url, error, Instant::now());
+    }
+
+    fn record_failure_at(&self, network: &str, url: &str, error: String, now: Instant) {
+        let Some(stats) = self.stats(network, url) else {
+            return;
+        };
+        let mut stats = stats.lock().unwrap();
+
+        stats.requests += 1;
+        stats.failures += 1;
+        stats.consecutive_failures += 1;
+        stats.error_rate += EWMA_ALPHA * (1.0 - stats.error_rate);
+        stats.last_error = Some(error);
+
+        // A failed probe re-opens the circuit straight away
+        if stats.circuit(now) == CircuitState::HalfOpen
+            || stats.consecutive_failures >= FAILURE_THRESHOLD
+        {
+            if stats.opened_at.is_none() {
+                tracing::warn!("Opening circuit for {} RPC endpoint {}", network, url);
+            }
+            stats.opened_at = Some(now);
+        }
+    }
+
+    /// Takes an endpoint out of rotation for good
+    pub fn disable(&self, network: &str, url: &str, reason: String) {
+        if let Some(stats) = self.stats(network, url) {
+            stats.lock().unwrap().disabled = Some(reason);
+        }
+    }
+
+    /// Status of every endpoint, in configured order
+    pub fn snapshot(&self) -> Vec<EndpointStatus> {
+        let now = Instant::now();
+        self.order
+            .iter()
+            .map(|(network, url)| {
+                let stats = self.endpoints[&(network.clone(), url.clone())].lock().unwrap();
+                EndpointStatus {
+                    network: network.clone(),
+                    url: url.clone(),
+                    circuit: stats.circuit(now),
+                    healthy: !stats.is_degraded(now),
+                    requests: stats.requests,
+                    failures: stats.failures,
+                    error_rate: stats.error_rate,
+                    latency_ms: stats.latency_ms,
+                    last_error: stats.last_error.clone(),
+                    disabled: stats.disabled.clone(),
+                }
+            })
+            .collect()
+    }
+
+    fn stats(&self, network: &str, url: &str) -> Option<&Mutex<EndpointStats>> {
+        self.endpoints.get(&(network.to_string(), url.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(url: &str) -> RpcEndpoint {
+        RpcEndpoint {
+            url: url.to_string(),
+            headers: Vec::new(),
+        }
+    }
+
+    fn health(urls: &[&str]) -> RpcHealth {
+        RpcHealth::with_endpoints(
+            urls.iter()
+                .map(|url| ("testnet".to_string(), url.to_string()))
+                .collect(),
+        )
+    }
+
+    fn urls(endpoints: Vec<&RpcEndpoint>) -> Vec<&str> {
+        endpoints.iter().map(|e| e.url.as_str()).collect()
+    }
+
+    #[test]
+    fn test_failing_endpoint_is_demoted() {
+        let health = health(&["a", "b"]);
+        let endpoints = [endpoint("a"), endpoint("b")];
+        let now = Instant::now();
+
+        assert_eq!(urls(health.ordered_at("testnet", &endpoints, now)), ["a", "b"]);
+
+        health.record_failure_at("testnet", "a", "HTTP 503".to_string(), now);
+        assert_eq!(urls(health.ordered_at("testnet", &endpoints, now)), ["b", "a"]);
+
+        health.record_success("testnet", "a", Duration::from_millis(40));
+        assert_eq!(urls(health.ordered_at("testnet", &endpoints, now)), ["a", "b"]);
+
+        health.disable("testnet", "b", "chain id 4, expected 250".to_string());
+        assert_eq!(urls(health.ordered_at("testnet", &endpoints, now)), ["a"]);
+        assert!(!health.snapshot()[1].healthy);
+    }
+
+    #[test]
+    fn test_circuit_opens_and_recovers() {
+        let health = health(&["a", "b"]);
+        let endpoints = [endpoint("a"), endpoint("b")];
+        let now = Instant::now();
+
+        for _ in 0..FAILURE_THRESHOLD {
+            health.record_failure_at("testnet", "a", "timeout".to_string(), now);
+        }
+        assert_eq!(urls(health.ordered_at("testnet", &endpoints, now)), ["b"]);
+        assert_eq!(health.snapshot()[0].circuit, CircuitState::Open);
+
+        // After the cooldown one probe is let through; failing it re-opens
+        let later = now + OPEN_COOLDOWN;
+        assert_eq!(urls(health.ordered_at("testnet", &endpoints, later)), ["b", "a"]);
+        health.record_failure_at("testnet", "a", "timeout".to_string(), later);
+        assert_eq!(urls(health.ordered_at("testnet", &endpoints, later)), ["b"]);
+
+        let after_probe = later + OPEN_COOLDOWN;
+        health.record_success("testnet", "a", Duration::from_millis(40));
+        assert_eq!(health.snapshot()[0].circuit, CircuitState::Closed);
+        assert_eq!(health.ordered_at("testnet", &endpoints, after_probe).len(), 2);
+    }
+}
@@ -0,0 +1,335 @@
+use async_trait::async_trait;
+use rand::Rng;
+use reqwest::StatusCode;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::client::MovementClient;
+use super::health::RpcHealth;
+use super::types::{
+    prefixed_address, AccountInfo, FunctionCall, MoveModuleAbi, SimulationTransaction,
+    TableItemRequest,
+};
+use crate::config::Config;
+use crate::error::ApiError;
+
+/// Ed25519 base point, a valid curve point whose key doesn't matter since
+/// simulations skip signature and auth key checks
+const SIMULATION_PUBLIC_KEY: &str =
+    "0x3b6a27bcceb6a42d62a3a8d02a6f0d73653215771de243a63ac048a18b59da29";
+
+const ZERO_SIGNATURE: &str = "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000";
+
+/// Transactions expire this long after simulation
+const EXPIRATION_SECS: i64 = 600;
+
+/// First retry waits up to this long; each later one doubles the bound
+const BACKOFF_BASE_MS: u64 = 100;
+
+const BACKOFF_MAX_MS: u64 = 2_000;
+
+/// Talks to the node over HTTP, failing over between a network's endpoints
+///
+/// Every call is a read or a simulation, so all of them are safe to retry.
+/// Server errors, rate limiting, timeouts and connection failures move on to
+/// the next endpoint after a jittered backoff; other 4xx responses are the
+/// node's answer and are returned as is.
+pub struct HttpMovementClient {
+    http_client: reqwest::Client,
+    config: Config,
+    health: Arc<RpcHealth>,
+}
+
+impl HttpMovementClient {
+    pub fn new(config: Config, health: Arc<RpcHealth>) -> Self {
+        Self {
+            http_client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(config.rpc_timeout_secs))
+                .build()
+                .unwrap_or_default(),
+            config,
+            health,
+        }
+    }
+
+    /// Sends a request built for each endpoint in turn until one answers
+    async fn send(
+        &self,
+        network: &str,
+        path: &str,
+        build: impl Fn(&reqwest::Client, String) -> reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, ApiError> {
+        let endpoints = self
+            .health
+            .ordered(network, &self.config.networks.get(network)?.endpoints);
+        if endpoints.is_empty() {
+            return Err(ApiError::RpcError(format!(
+                "All {} RPC endpoints are unavailable",
+                network
+            )));
+        }
+
+        let attempts = (self.config.rpc_max_attempts as usize).max(endpoints.len());
+        let mut last_error = String::new();
+
+        for attempt in 0..attempts {
+            if attempt > 0 {
+                tokio::time::sleep(backoff_delay(attempt as u32)).await;
+            }
+
+            let endpoint = endpoints[attempt % endpoints.len()];
+            let mut request = build(&self.http_client, format!("{}{}", endpoint.url, path));
+            for (name, value) in &endpoint.headers {
+                request = request.header(name, value);
+            }
+
+            let started = Instant::now();
+            let error = match request.send().await {
+                Ok(response) if is_retryable(response.status()) => {
+                    format!("{} returned {}", endpoint.url, response.status())
+                }
+                Ok(response) => {
+                    self.health
+                        .record_success(network, &endpoint.url, started.elapsed());
+                    return Ok(response);
+                }
+                Err(e) => format!("{}: {}", endpoint.url, e),
+            };
+
+            tracing::warn!("RPC {} attempt {} failed: {}", path, attempt + 1, error);
+            self.health.record_failure(network, &endpoint.url, error.clone());
+            last_error = error;
+        }
+
+        Err(ApiError::RpcError(last_error))
+    }
+
+    /// Checks every endpoint of networks with a configured chain id and takes
+    /// nodes serving a different chain out of rotation
+    pub async fn verify_chain_ids(&self) {
+        for network in self.config.networks.iter() {
+            let Some(expected) = network.chain_id else {
+                continue;
+            };
+
+            for endpoint in &network.endpoints {
+                let mut request = self.http_client.get(&endpoint.url);
+                for (name, value) in &endpoint.headers {
+                    request = request.header(name, value);
+                }
+
+                // The base URL serves the ledger info
+                let chain_id = match request.send().await {
+                    Ok(response) => response
+                        .json::<Value>()
+                        .await
+                        .ok()
+                        .and_then(|info| info.get("chain_id").and_then(|v| v.as_u64())),
+                    Err(e) => {
+                        tracing::warn!("Could not check chain id of {}: {}", endpoint.url, e);
+                        continue;
+                    }
+                };
+
+                match chain_id {
+                    Some(id) if id == expected as u64 => {}
+                    Some(id) => {
+                        let reason = format!("node reports chain id {}, expected {}", id, expected);
+                        tracing::error!("Disabling {} endpoint {}: {}", network.name, endpoint.url, reason);
+                        self.health.disable(&network.name, &endpoint.url, reason);
+                    }
+                    None => tracing::warn!("{} did not report a chain id", endpoint.url),
+                }
+            }
+        }
+    }
+
+    /// GETs a path under the network's base URL; 404 becomes `None`
+    async fn get_json(&self, network: &str, path: &str) -> Result<Option<Value>, ApiError> {
+        let response = self.send(network, path, |client, url| client.get(url)).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(ApiError::RpcError(format!("GET {} returned {}: {}", path, status, error_text)));
+        }
+
+        Ok(Some(response.json().await?))
+    }
+
+    /// POSTs JSON; a rejected request becomes `SimulationFailed` with the node's message
+    async fn post_json(&self, network: &str, path: &str, body: &Value) -> Result<Value, ApiError> {
+        let response = self
+            .send(network, path, |client, url| {
+                client
+                    .post(url)
+                    .header("Content-Type", "application/json")
+                    .json(body)
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(ApiError::SimulationFailed(error_text));
+        }
+
+        Ok(response.json().await?)
+    }
+}
+
+/// Errors another node might not return
+fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Full jitter: a random wait up to an exponentially growing bound
+fn backoff_delay(attempt: u32) -> Duration {
+    let bound = BACKOFF_BASE_MS
+        .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+        .min(BACKOFF_MAX_MS);
+    Duration::from_millis(rand::thread_rng().gen_range(0..=bound))
+}
+
+#[async_trait]
+impl MovementClient for HttpMovementClient {
+    async fn get_account(&self, network: &str, address: &str) -> Result<Option<AccountInfo>, ApiError> {
+        let path = format!("/accounts/{}", prefixed_address(address));
+        let Some(account) = self.get_json(network, &path).await? else {
+            return Ok(None);
+        };
+
+        Ok(Some(AccountInfo {
+            sequence_number: account
+                .get("sequence_number")
+                .and_then(|v| v.as_str())
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            authentication_key: account
+                .get("authentication_key")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+        }))
+    }
+
+    async fn get_resource(
+        &self,
+        network: &str,
+        address: &str,
+        resource_type: &str,
+    ) -> Result<Option<Value>, ApiError> {
+        let path = format!("/accounts/{}/resource/{}", prefixed_address(address), resource_type);
+        Ok(self
+            .get_json(network, &path)
+            .await?
+            .and_then(|r| r.get("data").cloned()))
+    }
+
+    async fn get_module(
+        &self,
+        network: &str,
+        address: &str,
+        module_name: &str,
+    ) -> Result<Option<MoveModuleAbi>, ApiError> {
+        let path = format!("/accounts/{}/module/{}", prefixed_address(address), module_name);
+        let Some(module) = self.get_json(network, &path).await? else {
+            return Ok(None);
+        };
+
+        match module.get("abi") {
+            Some(abi) => Ok(Some(serde_json::from_value(abi.clone())?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_table_item(
+        &self,
+        network: &str,
+        handle: &str,
+        item: &TableItemRequest,
+    ) -> Result<Option<Value>, ApiError> {
+        let path = format!("/tables/{}/item", handle);
+        match self.post_json(network, &path, &serde_json::to_value(item)?).await {
+            Ok(value) => Ok(Some(value)),
+            // The node answers a missing key with a 404 error body
+            Err(ApiError::SimulationFailed(message)) if message.contains("table_item_not_found") => Ok(None),
+            Err(ApiError::SimulationFailed(message)) => Err(ApiError::RpcError(message)),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn view(&self, network: &str, call: &FunctionCall) -> Result<Vec<Value>, ApiError> {
+        let path = match call.ledger_version {
+            Some(version) => format!("/view?ledger_version={}", version),
+            None => "/view".to_string(),
+        };
+        let result = self.post_json(network, &path, &serde_json::to_value(call)?).await?;
+        Ok(match result {
+            Value::Array(values) => values,
+            other => vec![other],
+        })
+    }
+
+    async fn simulate(&self, network: &str, transaction: &SimulationTransaction) -> Result<Value, ApiError> {
+        let body = serde_json::json!({
+            "sender": prefixed_address(&transaction.sender),
+            "sequence_number": transaction.sequence_number.to_string(),
+            "max_gas_amount": transaction.max_gas_amount.to_string(),
+            "gas_unit_price": transaction.gas_unit_price.to_string(),
+            "expiration_timestamp_secs": (chrono::Utc::now().timestamp() + EXPIRATION_SECS).to_string(),
+            "payload": {
+                "type": "entry_function_payload",
+                "function": transaction.call.function,
+                "type_arguments": transaction.call.type_arguments,
+                "arguments": transaction.call.arguments,
+            },
+            "signature": {
+                "type": "ed25519_signature",
+                "public_key": transaction.public_key.as_deref().unwrap_or(SIMULATION_PUBLIC_KEY),
+                "signature": ZERO_SIGNATURE,
+            },
+        });
+
+        // Movement returns gas estimates below the minimum, so max gas is not
+        // estimated; the auth key check would reject the placeholder key
+        let features = self.config.networks.get(network)?.features;
+        let path = format!(
+            "/transactions/simulate?estimate_gas_unit_price={}&estimate_prioritized_gas_unit_price=false&skip_auth_key_validation={}",
+            features.estimate_gas_price, features.skip_auth_key_validation
+        );
+        tracing::debug!("Simulation body: {}", body);
+
+        let result = self.post_json(network, &path, &body).await?;
+        result
+            .as_array()
+            .and_then(|txs| txs.first())
+            .cloned()
+            .ok_or_else(|| ApiError::SimulationFailed("Empty response from simulation".to_string()))
+    }
+
+    async fn get_transaction(&self, network: &str, hash: &str) -> Result<Option<Value>, ApiError> {
+        self.get_json(network, &format!("/transactions/by_hash/{}", hash)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_policy() {
+        assert!(is_retryable(StatusCode::BAD_GATEWAY));
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_retryable(StatusCode::BAD_REQUEST));
+        assert!(!is_retryable(StatusCode::NOT_FOUND));
+
+        for attempt in 1..10 {
+            let bound = (BACKOFF_BASE_MS << (attempt - 1)).min(BACKOFF_MAX_MS);
+            assert!(backoff_delay(attempt) <= Duration::from_millis(bound));
+        }
+    }
+}
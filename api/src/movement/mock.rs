@@ -0,0 +1,150 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use super::client::MovementClient;
+use super::types::{AccountInfo, FunctionCall, MoveModuleAbi, SimulationTransaction, TableItemRequest};
+use crate::error::ApiError;
+
+/// In-memory node for offline executor tests
+///
+/// Simulation responses are queued per function and replayed in order, the
+/// last one repeating; an `Err` is returned as `ApiError::RpcError`.
+#[derive(Default)]
+pub struct MockMovementClient {
+    accounts: HashMap<String, AccountInfo>,
+    resources: HashMap<(String, String), Value>,
+    modules: HashMap<(String, String), MoveModuleAbi>,
+    views: HashMap<String, Vec<Value>>,
+    simulations: Mutex<HashMap<String, VecDeque<Result<Value, String>>>>,
+    simulate_calls: Mutex<Vec<SimulationTransaction>>,
+}
+
+impl MockMovementClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_account(mut self, address: &str, sequence_number: u64) -> Self {
+        self.accounts.insert(
+            address.to_string(),
+            AccountInfo {
+                sequence_number,
+                authentication_key: address.to_string(),
+            },
+        );
+        self
+    }
+
+    pub fn with_resource(mut self, address: &str, resource_type: &str, data: Value) -> Self {
+        self.resources
+            .insert((address.to_string(), resource_type.to_string()), data);
+        self
+    }
+
+    pub fn with_module(mut self, address: &str, module: MoveModuleAbi) -> Self {
+        self.modules
+            .insert((address.to_string(), module.name.clone()), module);
+        self
+    }
+
+    pub fn with_view(mut self, function: &str, values: Vec<Value>) -> Self {
+        self.views.insert(function.to_string(), values);
+        self
+    }
+
+    pub fn with_simulation(self, function: &str, response: Result<Value, String>) -> Self {
+        self.simulations
+            .lock()
+            .unwrap()
+            .entry(function.to_string())
+            .or_default()
+            .push_back(response);
+        self
+    }
+
+    /// Transactions passed to `simulate`, in call order
+    pub fn simulate_calls(&self) -> Vec<SimulationTransaction> {
+        self.simulate_calls.lock().unwrap().clone()
+    }
+
+    /// A node transaction body with the given outcome
+    pub fn transaction(success: bool, vm_status: &str, gas_used: u64) -> Value {
+        serde_json::json!({
+            "success": success,
+            "vm_status": vm_status,
+            "gas_used": gas_used.to_string(),
+            "gas_unit_price": "100",
+            "changes": [],
+            "events": [],
+        })
+    }
+}
+
+#[async_trait]
+impl MovementClient for MockMovementClient {
+    async fn get_account(&self, _network: &str, address: &str) -> Result<Option<AccountInfo>, ApiError> {
+        Ok(self.accounts.get(address).cloned())
+    }
+
+    async fn get_resource(
+        &self,
+        _network: &str,
+        address: &str,
+        resource_type: &str,
+    ) -> Result<Option<Value>, ApiError> {
+        Ok(self
+            .resources
+            .get(&(address.to_string(), resource_type.to_string()))
+            .cloned())
+    }
+
+    async fn get_module(
+        &self,
+        _network: &str,
+        address: &str,
+        module_name: &str,
+    ) -> Result<Option<MoveModuleAbi>, ApiError> {
+        Ok(self
+            .modules
+            .get(&(address.to_string(), module_name.to_string()))
+            .cloned())
+    }
+
+    async fn get_table_item(
+        &self,
+        _network: &str,
+        _handle: &str,
+        _item: &TableItemRequest,
+    ) -> Result<Option<Value>, ApiError> {
+        Ok(None)
+    }
+
+    async fn view(&self, _network: &str, call: &FunctionCall) -> Result<Vec<Value>, ApiError> {
+        self.views
+            .get(&call.function)
+            .cloned()
+            .ok_or_else(|| ApiError::SimulationFailed(format!("function {} not found", call.function)))
+    }
+
+    async fn simulate(&self, _network: &str, transaction: &SimulationTransaction) -> Result<Value, ApiError> {
+        self.simulate_calls.lock().unwrap().push(transaction.clone());
+
+        let mut simulations = self.simulations.lock().unwrap();
+        let queue = simulations
+            .get_mut(&transaction.call.function)
+            .ok_or_else(|| ApiError::SimulationFailed(format!("function {} not found", transaction.call.function)))?;
+        let response = if queue.len() > 1 {
+            queue.pop_front().unwrap()
+        } else {
+            queue.front().cloned().unwrap()
+        };
+
+        response.map_err(ApiError::RpcError)
+    }
+
+    async fn get_transaction(&self, _network: &str, _hash: &str) -> Result<Option<Value>, ApiError> {
+        Ok(None)
+    }
+}
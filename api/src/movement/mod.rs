@@ -0,0 +1,22 @@
+//! Client for the Movement full node REST API
+//!
+//! Every executor talks to the node through `MovementClient`, so request
+//! building, argument encoding and the simulation signature live in one
+//! place. `HttpMovementClient` is the real implementation, usually wrapped
+//! in `CachedMovementClient`; tests use `MockMovementClient`.
+
+mod cached;
+mod client;
+mod health;
+mod http;
+#[cfg(test)]
+mod mock;
+pub mod types;
+
+pub use cached::CachedMovementClient;
+pub use client::MovementClient;
+pub use health::{CircuitState, EndpointStatus, RpcHealth};
+pub use http::HttpMovementClient;
+#[cfg(test)]
+pub use mock::MockMovementClient;
+pub use types::*;
@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Account state relevant to building transactions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountInfo {
+    pub sequence_number: u64,
+    pub authentication_key: String,
+}
+
+/// An entry or view function call, with arguments encoded for the node
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionCall {
+    /// Fully qualified, e.g. "0x1::coin::transfer"
+    pub function: String,
+    pub type_arguments: Vec<String>,
+    pub arguments: Vec<Value>,
+    /// Evaluate a view against this ledger version instead of the latest
+    #[serde(skip)]
+    pub ledger_version: Option<u64>,
+}
+
+impl FunctionCall {
+    pub fn new(
+        module_address: &str,
+        module_name: &str,
+        function_name: &str,
+        type_args: &[String],
+        args: &[Value],
+    ) -> Self {
+        Self {
+            function: format!("{}::{}::{}", module_address, module_name, function_name),
+            type_arguments: type_args.to_vec(),
+            // The REST API expects u64, u128 and u256 values as strings
+            arguments: args
+                .iter()
+                .map(|arg| match arg {
+                    Value::Number(n) => Value::String(n.to_string()),
+                    other => other.clone(),
+                })
+                .collect(),
+            ledger_version: None,
+        }
+    }
+}
+
+/// An unsigned transaction to simulate
+#[derive(Debug, Clone)]
+pub struct SimulationTransaction {
+    pub sender: String,
+    pub sequence_number: u64,
+    pub max_gas_amount: u64,
+    pub gas_unit_price: u64,
+    pub call: FunctionCall,
+    /// Signs with a fixed placeholder key when omitted
+    pub public_key: Option<String>,
+}
+
+/// ABI of a published module, as returned by `/accounts/{address}/module/{name}`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MoveModuleAbi {
+    #[serde(default)]
+    pub address: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub exposed_functions: Vec<MoveFunctionAbi>,
+    #[serde(default)]
+    pub structs: Vec<Value>,
+}
+
+impl MoveModuleAbi {
+    /// Whether a function can only be called through `view`: true for view
+    /// functions and non-entry functions, `None` if the module has no such function
+    pub fn is_view_only(&self, function_name: &str) -> Option<bool> {
+        self.exposed_functions
+            .iter()
+            .find(|f| f.name == function_name)
+            .map(|f| f.is_view || !f.is_entry)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MoveFunctionAbi {
+    pub name: String,
+    #[serde(default)]
+    pub visibility: String,
+    #[serde(default)]
+    pub is_entry: bool,
+    #[serde(default)]
+    pub is_view: bool,
+    #[serde(default)]
+    pub params: Vec<String>,
+    #[serde(default)]
+    pub r#return: Vec<String>,
+}
+
+/// Key and value types of a table lookup
+#[derive(Debug, Clone, Serialize)]
+pub struct TableItemRequest {
+    pub key_type: String,
+    pub value_type: String,
+    pub key: Value,
+}
+
+/// Adds the `0x` prefix the REST API requires
+pub fn prefixed_address(address: &str) -> String {
+    if address.starts_with("0x") {
+        address.to_string()
+    } else {
+        format!("0x{}", address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_function_call_stringifies_numbers() {
+        let call = FunctionCall::new(
+            "0x1",
+            "coin",
+            "transfer",
+            &["0x1::aptos_coin::AptosCoin".to_string()],
+            &[serde_json::json!("0xb0b"), serde_json::json!(100), serde_json::json!(true)],
+        );
+        assert_eq!(call.function, "0x1::coin::transfer");
+        assert_eq!(call.arguments, vec![serde_json::json!("0xb0b"), serde_json::json!("100"), serde_json::json!(true)]);
+        assert_eq!(prefixed_address("a11ce"), "0xa11ce");
+    }
+}
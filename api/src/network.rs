@@ -0,0 +1,288 @@
+//! Registry of the networks requests can target
+//!
+//! Mainnet and testnet are built in. `devnet` and `local` are registered when
+//! `MOVEMENT_RPC_DEVNET` / `MOVEMENT_RPC_LOCAL` are set, and any network,
+//! including custom forks, can be defined or replaced in the TOML file named
+//! by `NETWORKS_FILE`:
+//!
+//! ```toml
+//! [my-fork]
+//! rpc_urls = ["https://fork.example.com/v1"]
+//! chain_id = 250
+//! faucet_url = "https://faucet.fork.example.com"
+//! headers = { Authorization = "Bearer ..." }
+//! features = { estimate_gas_price = false }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+
+use crate::error::ApiError;
+
+/// Shinami Node Service base URLs for Movement
+const SHINAMI_MOVEMENT_MAINNET: &str = "https://api.shinami.com/aptos/node/v1/movement_mainnet";
+const SHINAMI_MOVEMENT_TESTNET: &str = "https://api.shinami.com/aptos/node/v1/movement_testnet";
+
+/// Public Movement RPC fallbacks
+const PUBLIC_MOVEMENT_MAINNET: &str = "https://mainnet.movementnetwork.xyz/v1";
+const PUBLIC_MOVEMENT_TESTNET: &str = "https://testnet.movementnetwork.xyz/v1";
+
+const MAINNET_CHAIN_ID: u8 = 126;
+const TESTNET_CHAIN_ID: u8 = 250;
+/// Chain id of a localnet started with default settings
+const LOCAL_CHAIN_ID: u8 = 4;
+
+/// A node URL with the headers to send it
+#[derive(Clone, Debug, PartialEq)]
+pub struct RpcEndpoint {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+}
+
+/// Node capabilities that differ between networks
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkFeatures {
+    /// Ask the node to estimate the gas unit price when simulating
+    pub estimate_gas_price: bool,
+    /// Node accepts `skip_auth_key_validation` on simulations
+    pub skip_auth_key_validation: bool,
+}
+
+impl Default for NetworkFeatures {
+    fn default() -> Self {
+        Self {
+            estimate_gas_price: true,
+            skip_auth_key_validation: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NetworkConfig {
+    pub name: String,
+    /// Endpoints in failover order
+    pub endpoints: Vec<RpcEndpoint>,
+    /// When set, nodes reporting a different chain id are not used
+    pub chain_id: Option<u8>,
+    pub faucet_url: Option<String>,
+    pub features: NetworkFeatures,
+}
+
+/// A network entry in `NETWORKS_FILE`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NetworkFileEntry {
+    rpc_urls: Vec<String>,
+    #[serde(default)]
+    chain_id: Option<u8>,
+    #[serde(default)]
+    faucet_url: Option<String>,
+    /// Sent to every endpoint of the network
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default)]
+    features: NetworkFeatures,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct NetworkRegistry {
+    networks: Vec<NetworkConfig>,
+}
+
+impl NetworkRegistry {
+    /// Built-in networks, then environment overrides, then `NETWORKS_FILE`
+    pub fn from_env(shinami_api_key: Option<&str>) -> Result<Self, String> {
+        let mut registry = Self::builtin(shinami_api_key);
+
+        let env_networks = [
+            ("mainnet", None),
+            ("testnet", None),
+            ("devnet", None),
+            ("local", Some(LOCAL_CHAIN_ID)),
+        ];
+        for (name, chain_id) in env_networks {
+            let var = format!("MOVEMENT_RPC_{}", name.to_uppercase());
+            let Ok(value) = env::var(&var) else {
+                continue;
+            };
+            let endpoints = value
+                .split(',')
+                .map(str::trim)
+                .filter(|url| !url.is_empty())
+                .map(|url| endpoint(url, shinami_api_key))
+                .collect();
+
+            match registry.networks.iter_mut().find(|n| n.name == name) {
+                Some(network) => network.endpoints = endpoints,
+                None => registry.insert(NetworkConfig {
+                    name: name.to_string(),
+                    endpoints,
+                    chain_id,
+                    faucet_url: None,
+                    features: NetworkFeatures::default(),
+                }),
+            }
+        }
+
+        if let Ok(path) = env::var("NETWORKS_FILE") {
+            let content = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read NETWORKS_FILE {}: {}", path, e))?;
+            registry.merge_file(&content)?;
+        }
+
+        Ok(registry)
+    }
+
+    /// Mainnet and testnet, preferring Shinami when an API key is present and
+    /// failing over to the public RPC
+    pub fn builtin(shinami_api_key: Option<&str>) -> Self {
+        let urls = |shinami: &str, public: &str| match shinami_api_key {
+            Some(_) => vec![shinami.to_string(), public.to_string()],
+            None => vec![public.to_string()],
+        };
+        let network = |name: &str, urls: Vec<String>, chain_id: u8| NetworkConfig {
+            name: name.to_string(),
+            endpoints: urls.iter().map(|url| endpoint(url, shinami_api_key)).collect(),
+            chain_id: Some(chain_id),
+            faucet_url: None,
+            features: NetworkFeatures::default(),
+        };
+
+        Self {
+            networks: vec![
+                network(
+                    "mainnet",
+                    urls(SHINAMI_MOVEMENT_MAINNET, PUBLIC_MOVEMENT_MAINNET),
+                    MAINNET_CHAIN_ID,
+                ),
+                network(
+                    "testnet",
+                    urls(SHINAMI_MOVEMENT_TESTNET, PUBLIC_MOVEMENT_TESTNET),
+                    TESTNET_CHAIN_ID,
+                ),
+            ],
+        }
+    }
+
+    /// Adds or replaces networks from a `NETWORKS_FILE` document
+    fn merge_file(&mut self, content: &str) -> Result<(), String> {
+        let entries: BTreeMap<String, NetworkFileEntry> =
+            toml::from_str(content).map_err(|e| format!("Invalid NETWORKS_FILE: {}", e))?;
+
+        for (name, entry) in entries {
+            if entry.rpc_urls.is_empty() {
+                return Err(format!("Network '{}' has no rpc_urls", name));
+            }
+            let headers: Vec<(String, String)> = entry.headers.into_iter().collect();
+            self.insert(NetworkConfig {
+                endpoints: entry
+                    .rpc_urls
+                    .into_iter()
+                    .map(|url| RpcEndpoint {
+                        url,
+                        headers: headers.clone(),
+                    })
+                    .collect(),
+                name,
+                chain_id: entry.chain_id,
+                faucet_url: entry.faucet_url,
+                features: entry.features,
+            });
+        }
+        Ok(())
+    }
+
+    fn insert(&mut self, network: NetworkConfig) {
+        match self.networks.iter_mut().find(|n| n.name == network.name) {
+            Some(existing) => *existing = network,
+            None => self.networks.push(network),
+        }
+    }
+
+    /// Looks up a network by name, rejecting unknown names
+    pub fn get(&self, name: &str) -> Result<&NetworkConfig, ApiError> {
+        self.networks.iter().find(|n| n.name == name).ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "Unknown network '{}'. Available networks: {}",
+                name,
+                self.names().join(", ")
+            ))
+        })
+    }
+
+    /// Rejects the first unknown name
+    pub fn check<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> Result<(), ApiError> {
+        names.into_iter().try_for_each(|name| self.get(name).map(|_| ()))
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.networks.iter().map(|n| n.name.as_str()).collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &NetworkConfig> {
+        self.networks.iter()
+    }
+}
+
+/// The Shinami key is only sent to Shinami endpoints
+fn endpoint(url: &str, shinami_api_key: Option<&str>) -> RpcEndpoint {
+    RpcEndpoint {
+        url: url.to_string(),
+        headers: shinami_api_key
+            .filter(|_| url.contains("shinami.com"))
+            .map(|key| vec![("X-Api-Key".to_string(), key.to_string())])
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_networks() {
+        let registry = NetworkRegistry::builtin(Some("key"));
+        assert_eq!(registry.names(), ["mainnet", "testnet"]);
+
+        let mainnet = registry.get("mainnet").unwrap();
+        assert_eq!(mainnet.chain_id, Some(MAINNET_CHAIN_ID));
+        assert_eq!(mainnet.endpoints.len(), 2);
+        assert_eq!(mainnet.endpoints[0].headers, [("X-Api-Key".to_string(), "key".to_string())]);
+        assert!(mainnet.endpoints[1].headers.is_empty());
+
+        let err = registry.get("mainet").unwrap_err();
+        assert!(matches!(err, ApiError::BadRequest(ref m) if m.contains("mainnet, testnet")));
+    }
+
+    #[test]
+    fn test_networks_file() {
+        let mut registry = NetworkRegistry::builtin(None);
+        registry
+            .merge_file(
+                r#"
+[fork]
+rpc_urls = ["http://fork-a/v1", "http://fork-b/v1"]
+chain_id = 250
+faucet_url = "http://faucet"
+headers = { Authorization = "Bearer t" }
+features = { estimate_gas_price = false }
+
+[testnet]
+rpc_urls = ["http://mirror/v1"]
+"#,
+            )
+            .unwrap();
+
+        assert_eq!(registry.names(), ["mainnet", "testnet", "fork"]);
+        let fork = registry.get("fork").unwrap();
+        assert_eq!(fork.endpoints[1].headers, [("Authorization".to_string(), "Bearer t".to_string())]);
+        assert!(!fork.features.estimate_gas_price);
+        assert!(fork.features.skip_auth_key_validation);
+        assert_eq!(registry.get("testnet").unwrap().chain_id, None);
+
+        assert!(registry.merge_file("[empty]\nrpc_urls = []").is_err());
+        assert!(registry.merge_file("[typo]\nrpc_url = [\"x\"]").is_err());
+    }
+}
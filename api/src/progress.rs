@@ -0,0 +1,137 @@
+//! Progress reporting for long-running requests
+//!
+//! Executors emit events through a `Progress` handle while they work; the
+//! streaming routes forward them to the client as server-sent events. The
+//! regular endpoints pass `Progress::none()` and nothing is sent.
+
+use std::convert::Infallible;
+
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::StreamExt;
+use serde::Serialize;
+
+use crate::error::ApiError;
+use crate::simulation::ScenarioResult;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    Preparing,
+    Compiling,
+    GeneratingVcs,
+    Solving,
+    Testing,
+    Coverage,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum ProgressEvent {
+    /// A long-running step moved to a new phase
+    Phase { phase: Phase },
+    /// A batch scenario finished; `index` is its position in the request
+    Scenario { index: usize, result: ScenarioResult },
+    /// The run finished; same body as the non-streaming endpoint
+    Summary(serde_json::Value),
+    Error { error: String, code: u16 },
+}
+
+impl ProgressEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            ProgressEvent::Phase { .. } => "phase",
+            ProgressEvent::Scenario { .. } => "scenario",
+            ProgressEvent::Summary(_) => "summary",
+            ProgressEvent::Error { .. } => "error",
+        }
+    }
+
+    fn to_sse(&self) -> Event {
+        Event::default()
+            .event(self.name())
+            .json_data(self)
+            .unwrap_or_else(|_| Event::default().event("error").data("Failed to encode event"))
+    }
+}
+
+/// Sending half handed to executors
+#[derive(Clone, Default)]
+pub struct Progress(Option<UnboundedSender<ProgressEvent>>);
+
+impl Progress {
+    /// A handle that discards every event
+    pub fn none() -> Self {
+        Self(None)
+    }
+
+    pub fn channel() -> (Self, UnboundedReceiver<ProgressEvent>) {
+        let (tx, rx) = unbounded();
+        (Self(Some(tx)), rx)
+    }
+
+    pub fn emit(&self, event: ProgressEvent) {
+        if let Some(tx) = &self.0 {
+            // The client may have gone away; the run still completes
+            let _ = tx.unbounded_send(event);
+        }
+    }
+
+    pub fn phase(&self, phase: Phase) {
+        self.emit(ProgressEvent::Phase { phase });
+    }
+
+    /// True once a streaming client has disconnected
+    pub fn is_cancelled(&self) -> bool {
+        self.0.as_ref().is_some_and(|tx| tx.is_closed())
+    }
+
+    /// Emits the final result or error and closes the stream
+    pub fn finish<T: Serialize>(self, result: Result<T, ApiError>) {
+        let event = match result.map(|r| serde_json::to_value(r)) {
+            Ok(Ok(value)) => ProgressEvent::Summary(value),
+            Ok(Err(e)) => ProgressEvent::Error {
+                error: format!("Failed to encode result: {}", e),
+                code: 500,
+            },
+            Err(e) => ProgressEvent::Error {
+                code: e.status_code().as_u16(),
+                error: e.message(),
+            },
+        };
+        self.emit(event);
+    }
+}
+
+/// Streams events to the client until the sender side finishes
+pub fn sse_response(events: UnboundedReceiver<ProgressEvent>) -> Response {
+    let stream = events.map(|event| Ok::<_, Infallible>(event.to_sse()));
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finish_and_cancel() {
+        let (progress, mut events) = Progress::channel();
+        progress.phase(Phase::Solving);
+        assert!(!progress.is_cancelled());
+        progress.clone().finish(Err::<(), _>(ApiError::ProverTimeout));
+
+        let phase = events.try_recv().unwrap();
+        assert_eq!(phase.name(), "phase");
+        assert_eq!(serde_json::to_value(&phase).unwrap(), serde_json::json!({"phase": "solving"}));
+
+        let error = events.try_recv().unwrap();
+        assert_eq!(serde_json::to_value(&error).unwrap()["code"], 408);
+
+        drop(events);
+        assert!(progress.is_cancelled());
+        assert!(!Progress::none().is_cancelled());
+    }
+}
@@ -0,0 +1,487 @@
+//! Spec coverage analysis for Move modules
+//!
+//! A lightweight source scan (not a full Move parser) that finds public and
+//! entry functions, the spec blocks attached to them and module/struct
+//! invariants. When a prover result is available, failures are attributed to
+//! functions by source line to produce a verified/total score per module.
+
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+
+use super::specs::prepare_source;
+use super::types::{
+    FunctionCoverage, ModuleCoverage, ProverRequest, ProverResult, ProverStatus,
+    SpecCoverageReport, SpecLevel, StructInvariants,
+};
+use crate::error::ApiError;
+use crate::move_package::{line_at, matching_brace, strip_comments};
+
+/// Condition counts gathered from one or more spec blocks
+#[derive(Debug, Clone, Default)]
+struct SpecCounts {
+    requires: u32,
+    ensures: u32,
+    aborts_if: u32,
+    invariants: u32,
+    /// Explicit `pragma aborts_if_is_strict` value, if set
+    strict: Option<bool>,
+    /// Explicit `pragma verify` value, if set
+    verify: Option<bool>,
+    includes: Vec<String>,
+}
+
+impl SpecCounts {
+    fn merge(&mut self, other: &SpecCounts) {
+        self.requires += other.requires;
+        self.ensures += other.ensures;
+        self.aborts_if += other.aborts_if;
+        self.invariants += other.invariants;
+        self.strict = other.strict.or(self.strict);
+        self.verify = other.verify.or(self.verify);
+    }
+}
+
+/// A `spec <target> { ... }` block with its line range
+#[derive(Debug)]
+struct SpecBlockInfo {
+    target: String,
+    is_schema: bool,
+    start_line: u32,
+    end_line: u32,
+    counts: SpecCounts,
+}
+
+/// A function declaration with its line range
+#[derive(Debug)]
+struct FunctionInfo {
+    name: String,
+    is_public: bool,
+    is_entry: bool,
+    start_line: u32,
+    end_line: u32,
+}
+
+/// Builds a coverage report for the request's source (with specs injected)
+pub fn analyze_spec_coverage(
+    request: &ProverRequest,
+    prover_result: Option<&ProverResult>,
+) -> Result<SpecCoverageReport, ApiError> {
+    let source = prepare_source(request)?;
+    let code = strip_comments(&source.code);
+
+    let module_re = Regex::new(r"\bmodule\s+([\w:]+)\s*\{").expect("valid regex");
+
+    let mut modules = Vec::new();
+    let mut offset = 0;
+    while let Some(cap) = module_re.captures(&code[offset..]) {
+        let whole = cap.get(0).expect("match");
+        let open = offset + whole.end() - 1;
+        let close = matching_brace(&code, open).unwrap_or(code.len());
+        let name = cap[1].rsplit("::").next().unwrap_or(&cap[1]).to_string();
+
+        modules.push(analyze_module(&code, &name, open, close, prover_result));
+        offset = close.min(code.len());
+        if offset >= code.len() {
+            break;
+        }
+    }
+
+    if modules.is_empty() {
+        return Err(ApiError::BadRequest(
+            "No module declarations found in move_code".to_string(),
+        ));
+    }
+
+    let total: u32 = modules.iter().map(|m| m.total_functions).sum();
+    let specified: u32 = modules.iter().map(|m| m.specified_functions).sum();
+    let verified: Option<u32> = modules.iter().map(|m| m.verified_functions).sum();
+
+    let summary = match verified {
+        Some(verified) => format!(
+            "{}/{} functions verified, {}/{} specified",
+            verified, total, specified, total
+        ),
+        None => format!(
+            "{}/{} functions specified (no prover result available)",
+            specified, total
+        ),
+    };
+
+    Ok(SpecCoverageReport {
+        modules,
+        prover_status: prover_result.map(|r| r.status.clone()),
+        summary,
+    })
+}
+
+fn analyze_module(
+    code: &str,
+    name: &str,
+    open: usize,
+    close: usize,
+    prover_result: Option<&ProverResult>,
+) -> ModuleCoverage {
+    let body = &code[open..close];
+    let line_of = |pos: usize| line_at(code, open + pos);
+
+    // Spec blocks first, so `fun` declarations inside them can be skipped
+    let spec_re = Regex::new(r"\bspec\s+(?:(schema)\s+)?(\w+)").expect("valid regex");
+    let mut spec_blocks = Vec::new();
+    let mut spec_ranges = Vec::new();
+    for cap in spec_re.captures_iter(body) {
+        let target = cap[2].to_string();
+        if target == "fun" || target == "native" {
+            continue;
+        }
+        let start = cap.get(0).expect("match").start();
+        let Some(block_open) = body[start..].find('{').map(|i| start + i) else {
+            continue;
+        };
+        let block_close = matching_brace(body, block_open).unwrap_or(body.len());
+
+        spec_ranges.push((start, block_close));
+        spec_blocks.push(SpecBlockInfo {
+            target,
+            is_schema: cap.get(1).is_some(),
+            start_line: line_of(start),
+            end_line: line_of(block_close),
+            counts: count_conditions(&body[block_open..block_close]),
+        });
+    }
+
+    let struct_re = Regex::new(r"\bstruct\s+(\w+)").expect("valid regex");
+    let struct_names: HashSet<String> = struct_re
+        .captures_iter(body)
+        .map(|cap| cap[1].to_string())
+        .collect();
+
+    let fun_re = Regex::new(
+        r"((?:(?:public\s*(?:\([^)]*\))?|entry|native|inline)\s+)*)\bfun\s+(\w+)",
+    )
+    .expect("valid regex");
+    let mut functions = Vec::new();
+    for cap in fun_re.captures_iter(body) {
+        let start = cap.get(0).expect("match").start();
+        if spec_ranges.iter().any(|&(s, e)| start >= s && start < e) {
+            continue;
+        }
+
+        let modifiers: String = cap[1].split_whitespace().collect();
+        let is_public = modifiers.contains("public") && !modifiers.contains("public(");
+        let is_entry = modifiers.contains("entry");
+
+        let after = cap.get(0).expect("match").end();
+        let end = match body[after..].find(['{', ';']).map(|i| after + i) {
+            Some(pos) if body.as_bytes()[pos] == b'{' => {
+                matching_brace(body, pos).unwrap_or(body.len())
+            }
+            Some(pos) => pos,
+            None => body.len(),
+        };
+
+        functions.push(FunctionInfo {
+            name: cap[2].to_string(),
+            is_public,
+            is_entry,
+            start_line: line_of(start),
+            end_line: line_of(end),
+        });
+    }
+
+    // Merge spec blocks by target, resolving schema includes
+    let schemas: HashMap<&str, &SpecCounts> = spec_blocks
+        .iter()
+        .filter(|b| b.is_schema)
+        .map(|b| (b.target.as_str(), &b.counts))
+        .collect();
+
+    let mut by_target: HashMap<&str, SpecCounts> = HashMap::new();
+    for block in spec_blocks.iter().filter(|b| !b.is_schema) {
+        let entry = by_target.entry(block.target.as_str()).or_default();
+        entry.merge(&block.counts);
+        for include in &block.counts.includes {
+            entry.merge(&resolve_schema(include, &schemas, 0));
+        }
+    }
+
+    let module_counts = by_target.get("module").cloned().unwrap_or_default();
+    let module_strict = module_counts.strict.unwrap_or(false);
+
+    let mut struct_invariants: Vec<StructInvariants> = by_target
+        .iter()
+        .filter(|(target, counts)| struct_names.contains(**target) && counts.invariants > 0)
+        .map(|(target, counts)| StructInvariants {
+            name: target.to_string(),
+            invariants: counts.invariants,
+        })
+        .collect();
+    struct_invariants.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let failed_lines = prover_result.map(failed_spec_lines);
+
+    let mut coverage = Vec::new();
+    for function in functions.iter().filter(|f| f.is_public || f.is_entry) {
+        let counts = by_target
+            .get(function.name.as_str())
+            .cloned()
+            .unwrap_or_default();
+        let strict = counts.strict.unwrap_or(module_strict);
+        let verify_disabled = !counts.verify.or(module_counts.verify).unwrap_or(true);
+
+        let condition_count = counts.requires + counts.ensures + counts.aborts_if;
+        let level = if condition_count == 0 {
+            SpecLevel::None
+        } else if strict {
+            SpecLevel::Strict
+        } else if counts.aborts_if > 0 {
+            SpecLevel::AbortsIf
+        } else {
+            SpecLevel::Conditions
+        };
+
+        let verified = prover_result.map(|result| {
+            if result.status != ProverStatus::Passed && result.status != ProverStatus::Failed {
+                return false;
+            }
+            if level == SpecLevel::None || verify_disabled {
+                return false;
+            }
+
+            let (lines, names) = failed_lines.as_ref().expect("computed with prover_result");
+            let in_function = |line: &u32| *line >= function.start_line && *line <= function.end_line;
+            let in_spec = |line: &u32| {
+                spec_blocks.iter().any(|b| {
+                    b.target == function.name && *line >= b.start_line && *line <= b.end_line
+                })
+            };
+
+            !lines.iter().any(|l| in_function(l) || in_spec(l)) && !names.contains(&function.name)
+        });
+
+        coverage.push(FunctionCoverage {
+            name: function.name.clone(),
+            is_public: function.is_public,
+            is_entry: function.is_entry,
+            line: function.start_line,
+            level,
+            requires: counts.requires,
+            ensures: counts.ensures,
+            aborts_if: counts.aborts_if,
+            aborts_if_is_strict: strict,
+            verify_disabled,
+            verified,
+        });
+    }
+
+    let total_functions = coverage.len() as u32;
+    let specified_functions = coverage.iter().filter(|f| f.level != SpecLevel::None).count() as u32;
+    let verified_functions = prover_result
+        .map(|_| coverage.iter().filter(|f| f.verified == Some(true)).count() as u32);
+    let score = verified_functions.map(|verified| {
+        if total_functions == 0 {
+            0.0
+        } else {
+            verified as f64 / total_functions as f64
+        }
+    });
+
+    ModuleCoverage {
+        name: name.to_string(),
+        total_functions,
+        specified_functions,
+        verified_functions,
+        score,
+        aborts_if_is_strict: module_strict,
+        module_invariants: module_counts.invariants,
+        struct_invariants,
+        functions: coverage,
+    }
+}
+
+/// Counts spec conditions in a spec block body
+fn count_conditions(body: &str) -> SpecCounts {
+    let count = |pattern: &str| {
+        Regex::new(pattern)
+            .map(|re| re.find_iter(body).count() as u32)
+            .unwrap_or(0)
+    };
+
+    // `pragma name;` means true, `pragma name = false;` turns it off
+    let pragma = |name: &str| {
+        Regex::new(&format!(r"pragma\s+{}(?:\s*=\s*(true|false))?", name))
+            .ok()
+            .and_then(|re| re.captures(body))
+            .map(|cap| cap.get(1).map(|v| v.as_str() == "true").unwrap_or(true))
+    };
+
+    let include_re = Regex::new(r"\binclude\s+(\w+)").expect("valid regex");
+
+    SpecCounts {
+        requires: count(r"\brequires\b"),
+        ensures: count(r"\bensures\b"),
+        aborts_if: count(r"\baborts_if\b") + count(r"\baborts_with\b"),
+        invariants: count(r"\binvariant\b"),
+        strict: pragma("aborts_if_is_strict"),
+        verify: pragma("verify"),
+        includes: include_re
+            .captures_iter(body)
+            .map(|cap| cap[1].to_string())
+            .collect(),
+    }
+}
+
+/// Flattens a schema and the schemas it includes
+fn resolve_schema(name: &str, schemas: &HashMap<&str, &SpecCounts>, depth: u32) -> SpecCounts {
+    let mut counts = SpecCounts::default();
+    if depth > 4 {
+        return counts;
+    }
+    if let Some(schema) = schemas.get(name) {
+        counts.merge(schema);
+        for include in &schema.includes {
+            counts.merge(&resolve_schema(include, schemas, depth + 1));
+        }
+    }
+    counts
+}
+
+/// Lines and function names of failed specs in a prover result
+fn failed_spec_lines(result: &ProverResult) -> (Vec<u32>, Vec<String>) {
+    let mut lines = Vec::new();
+    let mut names = Vec::new();
+
+    for spec in result.modules.iter().flat_map(|m| &m.specs) {
+        if spec.status != ProverStatus::Failed {
+            continue;
+        }
+        match spec.location.as_ref().and_then(|l| l.line) {
+            Some(line) => lines.push(line),
+            None => names.push(spec.function.clone()),
+        }
+    }
+
+    (lines, names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prover::{ModuleResult, SourceLocation, SpecResult};
+
+    const CODE: &str = r#"module 0x1::vault {
+    struct Vault has key { balance: u64 }
+
+    public entry fun deposit(account: &signer, amount: u64) acquires Vault {
+        // fun commented_out() {}
+        let v = borrow_global_mut<Vault>(@0x1);
+        v.balance = v.balance + amount;
+    }
+
+    public fun balance(): u64 acquires Vault {
+        borrow_global<Vault>(@0x1).balance
+    }
+
+    public(friend) fun internal() {}
+
+    fun helper() {}
+
+    spec module {
+        pragma aborts_if_is_strict;
+        invariant true;
+    }
+
+    spec Vault {
+        invariant balance <= 1000;
+    }
+
+    spec schema DepositAbortsIf {
+        amount: u64;
+        aborts_if !exists<Vault>(@0x1);
+    }
+
+    spec deposit {
+        include DepositAbortsIf;
+        ensures global<Vault>(@0x1).balance == old(global<Vault>(@0x1).balance) + amount;
+    }
+}
+"#;
+
+    fn request() -> ProverRequest {
+        serde_json::from_value(serde_json::json!({
+            "move_code": CODE,
+            "module_name": "vault",
+        }))
+        .unwrap()
+    }
+
+    fn prover_result(status: ProverStatus, failed_line: Option<u32>) -> ProverResult {
+        ProverResult {
+            status,
+            duration_ms: 10,
+            modules: vec![ModuleResult {
+                name: "vault".to_string(),
+                status: ProverStatus::Failed,
+                specs: failed_line
+                    .map(|line| SpecResult {
+                        name: "spec_1".to_string(),
+                        function: "deposit".to_string(),
+                        status: ProverStatus::Failed,
+                        location: Some(SourceLocation {
+                            module: "vault".to_string(),
+                            function: "".to_string(),
+                            line: Some(line),
+                        }),
+                        counterexample: None,
+                        message: None,
+                        spec_index: None,
+                    })
+                    .into_iter()
+                    .collect(),
+                invariants: vec![],
+            }],
+            summary: String::new(),
+            raw_output: None,
+            cached: false,
+            run_id: None,
+        }
+    }
+
+    #[test]
+    fn test_coverage_without_prover_result() {
+        let report = analyze_spec_coverage(&request(), None).unwrap();
+        let module = &report.modules[0];
+
+        assert_eq!(module.name, "vault");
+        assert_eq!(module.total_functions, 2);
+        assert_eq!(module.specified_functions, 1);
+        assert!(module.verified_functions.is_none());
+        assert!(module.aborts_if_is_strict);
+        assert_eq!(module.module_invariants, 1);
+        assert_eq!(module.struct_invariants.len(), 1);
+        assert_eq!(module.struct_invariants[0].name, "Vault");
+
+        let deposit = &module.functions[0];
+        assert_eq!(deposit.name, "deposit");
+        assert!(deposit.is_entry);
+        assert_eq!(deposit.level, SpecLevel::Strict);
+        assert_eq!(deposit.ensures, 1);
+        assert_eq!(deposit.aborts_if, 1);
+
+        let balance = &module.functions[1];
+        assert_eq!(balance.level, SpecLevel::None);
+    }
+
+    #[test]
+    fn test_coverage_scores_against_prover_result() {
+        let passed = prover_result(ProverStatus::Passed, None);
+        let report = analyze_spec_coverage(&request(), Some(&passed)).unwrap();
+        assert_eq!(report.modules[0].verified_functions, Some(1));
+        assert_eq!(report.modules[0].score, Some(0.5));
+
+        // Failure on the ensures line inside `spec deposit`
+        let failed = prover_result(ProverStatus::Failed, Some(34));
+        let report = analyze_spec_coverage(&request(), Some(&failed)).unwrap();
+        assert_eq!(report.modules[0].verified_functions, Some(0));
+        assert_eq!(report.modules[0].functions[0].verified, Some(false));
+    }
+}
@@ -1,37 +1,16 @@
 use std::collections::HashMap;
 use std::time::Instant;
 use regex::Regex;
-use tempfile::TempDir;
-use tokio::process::Command;
-use tokio::time::{timeout, Duration};
+use tokio::time::timeout;
 
+use super::specs::{prepare_source, InjectedSource};
 use super::types::{
     Counterexample, ModuleResult, ProverRequest, ProverResult, ProverStatus,
     SourceLocation, SpecResult,
 };
 use crate::error::ApiError;
-
-// Use aptos-framework v1.0.0 which is compatible with Move Prover
-// The mainnet branch uses Move 2.2 features not yet supported by the prover
-const MOVE_TOML_TEMPLATE: &str = r#"[package]
-name = "sentinel_verify"
-version = "1.0.0"
-
-[dependencies]
-AptosFramework = { git = "https://github.com/aptos-labs/aptos-core.git", subdir = "aptos-move/framework/aptos-framework", rev = "aptos-node-v1.8.0" }
-
-[addresses]
-sentinel_verify = "0x1"
-"#;
-
-// Minimal Move.toml for simple modules that don't need framework dependencies
-const MOVE_TOML_MINIMAL: &str = r#"[package]
-name = "sentinel_verify"
-version = "1.0.0"
-
-[addresses]
-sentinel_verify = "0x1"
-"#;
+use crate::move_package::{cli_timeout, create_temp_project, run_aptos_move_streaming};
+use crate::progress::{Phase, Progress};
 
 pub struct ProverExecutor;
 
@@ -40,18 +19,33 @@ impl ProverExecutor {
         Self
     }
 
-    pub async fn execute(&self, request: ProverRequest) -> Result<ProverResult, ApiError> {
+    /// Runs the prover, reporting phase changes as the CLI output reveals them
+    pub async fn execute(
+        &self,
+        request: ProverRequest,
+        progress: &Progress,
+    ) -> Result<ProverResult, ApiError> {
         let start = Instant::now();
-        let timeout_duration = Duration::from_secs(request.timeout_seconds as u64);
+        progress.phase(Phase::Preparing);
+        let timeout_duration = cli_timeout(request.timeout_seconds);
+
+        // Splice submitted specs and option pragmas into the module
+        let source = prepare_source(&request)?;
 
         // Create temp directory with Move project structure
-        let temp_dir = self.create_temp_project(&request)?;
+        let temp_dir = create_temp_project(&request.module_name, &source.code)?;
         let temp_path = temp_dir.path().to_path_buf();
 
         // Run the prover with timeout
+        let mut phase = Phase::Preparing;
         let result = timeout(
             timeout_duration,
-            self.run_prover(&temp_path),
+            run_aptos_move_streaming("prove", &temp_path, &request.options.cli_args(), &[], |line| {
+                if let Some(next) = prover_phase(line).filter(|&next| next != phase) {
+                    phase = next;
+                    progress.phase(next);
+                }
+            }),
         )
         .await;
 
@@ -65,6 +59,8 @@ impl ProverExecutor {
                     exit_code,
                     duration_ms,
                     &request.module_name,
+                    &source,
+                    request.options.warnings_as_errors,
                 )
             }
             Ok(Err(e)) => {
@@ -75,6 +71,8 @@ impl ProverExecutor {
                     modules: vec![],
                     summary: format!("Prover execution failed: {}", e),
                     raw_output: Some(e.to_string()),
+                    cached: false,
+                    run_id: None,
                 })
             }
             Err(_) => {
@@ -83,82 +81,39 @@ impl ProverExecutor {
                     status: ProverStatus::Timeout,
                     duration_ms,
                     modules: vec![],
-                    summary: format!("Prover timed out after {} seconds", request.timeout_seconds),
+                    summary: format!("Prover timed out after {} seconds", timeout_duration.as_secs()),
                     raw_output: None,
+                    cached: false,
+                    run_id: None,
                 })
             }
         }
     }
 
-    fn create_temp_project(&self, request: &ProverRequest) -> Result<TempDir, ApiError> {
-        let temp_dir = TempDir::new()
-            .map_err(|e| ApiError::ProverError(format!("Failed to create temp directory: {}", e)))?;
-
-        let base_path = temp_dir.path();
-
-        // Create sources directory
-        let sources_dir = base_path.join("sources");
-        std::fs::create_dir_all(&sources_dir)
-            .map_err(|e| ApiError::ProverError(format!("Failed to create sources dir: {}", e)))?;
-
-        // Check if code uses framework imports - if not, use minimal Move.toml
-        let needs_framework = request.move_code.contains("use aptos_framework::")
-            || request.move_code.contains("use std::")
-            || request.move_code.contains("use aptos_std::");
-
-        let move_toml = if needs_framework {
-            MOVE_TOML_TEMPLATE
-        } else {
-            MOVE_TOML_MINIMAL
-        };
-
-        // Write Move.toml
-        std::fs::write(base_path.join("Move.toml"), move_toml)
-            .map_err(|e| ApiError::ProverError(format!("Failed to write Move.toml: {}", e)))?;
-
-        // Write the Move source file
-        let source_file = sources_dir.join(format!("{}.move", request.module_name));
-        std::fs::write(&source_file, &request.move_code)
-            .map_err(|e| ApiError::ProverError(format!("Failed to write source file: {}", e)))?;
-
-        Ok(temp_dir)
-    }
-
-    async fn run_prover(&self, project_path: &std::path::Path) -> Result<(String, String, i32), ApiError> {
-        let output = Command::new("aptos")
-            .args(["move", "prove", "--package-dir"])
-            .arg(project_path)
-            .output()
-            .await
-            .map_err(|e| {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    ApiError::ProverError("aptos CLI not found. Please install the Aptos CLI.".to_string())
-                } else {
-                    ApiError::ProverError(format!("Failed to execute prover: {}", e))
-                }
-            })?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        let exit_code = output.status.code().unwrap_or(-1);
-
-        Ok((stdout, stderr, exit_code))
-    }
-
     fn parse_prover_output(
         &self,
         output: &str,
         exit_code: i32,
         duration_ms: u64,
         module_name: &str,
+        source: &InjectedSource,
+        warnings_as_errors: bool,
     ) -> Result<ProverResult, ApiError> {
         // Check for SUCCESS - can be "SUCCESS" or "Result": "Success"
         let is_success = (output.contains("SUCCESS") || output.contains("\"Result\": \"Success\"")) && exit_code == 0;
 
         // Parse any errors or warnings
-        let specs = self.parse_spec_results(output, module_name);
+        let specs = self.parse_spec_results(output, module_name, source);
+
+        let warning_count = output
+            .lines()
+            .filter(|line| line.trim_start().starts_with("warning:"))
+            .count();
+        let failed_on_warnings = is_success && warnings_as_errors && warning_count > 0;
 
-        let module_status = if is_success {
+        let module_status = if failed_on_warnings {
+            ProverStatus::Failed
+        } else if is_success {
             ProverStatus::Passed
         } else if specs.iter().any(|s| s.status == ProverStatus::Failed) {
             ProverStatus::Failed
@@ -168,7 +123,12 @@ impl ProverExecutor {
 
         let overall_status = module_status.clone();
 
-        let summary = if is_success {
+        let summary = if failed_on_warnings {
+            format!(
+                "Module {} verified with {} warning(s), treated as errors",
+                module_name, warning_count
+            )
+        } else if is_success {
             format!("Module {} verified successfully", module_name)
         } else {
             let failed_count = specs.iter().filter(|s| s.status == ProverStatus::Failed).count();
@@ -190,10 +150,17 @@ impl ProverExecutor {
             }],
             summary,
             raw_output: Some(output.to_string()),
+            cached: false,
+            run_id: None,
         })
     }
 
-    fn parse_spec_results(&self, output: &str, module_name: &str) -> Vec<SpecResult> {
+    fn parse_spec_results(
+        &self,
+        output: &str,
+        module_name: &str,
+        source: &InjectedSource,
+    ) -> Vec<SpecResult> {
         let mut specs = Vec::new();
 
         // Pattern for errors like:
@@ -217,8 +184,14 @@ impl ProverExecutor {
                 let line_num = cap.get(2).and_then(|m| m.as_str().parse().ok());
                 let failed_code = cap.get(3).map(|m| m.as_str().trim()).unwrap_or("");
 
+                // Map failures inside injected spec blocks back to the submitted entry
+                let injected = line_num.and_then(|line| source.spec_at_line(line));
+
                 // Try to extract function name from ensures/requires
-                let function_name = self.extract_function_name(output, line_num.unwrap_or(0));
+                let function_name = match injected {
+                    Some(spec) => spec.target.clone(),
+                    None => self.extract_function_name(output, line_num.unwrap_or(0)),
+                };
 
                 // Look for counterexample after this error
                 let counterexample = counterexample_pattern.as_ref().and_then(|ce_pattern| {
@@ -243,8 +216,12 @@ impl ProverExecutor {
                     })
                 });
 
+                let name = injected
+                    .and_then(|spec| spec.name.clone())
+                    .unwrap_or_else(|| format!("spec_{}", specs.len() + 1));
+
                 specs.push(SpecResult {
-                    name: format!("spec_{}", specs.len() + 1),
+                    name,
                     function: function_name,
                     status: ProverStatus::Failed,
                     location: Some(SourceLocation {
@@ -254,6 +231,7 @@ impl ProverExecutor {
                     }),
                     counterexample,
                     message: Some(error_message.to_string()),
+                    spec_index: injected.map(|spec| spec.index),
                 });
             }
         }
@@ -267,7 +245,28 @@ impl ProverExecutor {
                 location: None,
                 counterexample: None,
                 message: Some("All specifications verified".to_string()),
+                spec_index: None,
             });
+
+            // Report each injected spec entry individually
+            for spec in &source.specs {
+                specs.push(SpecResult {
+                    name: spec
+                        .name
+                        .clone()
+                        .unwrap_or_else(|| format!("injected_spec_{}", spec.index + 1)),
+                    function: spec.target.clone(),
+                    status: ProverStatus::Passed,
+                    location: Some(SourceLocation {
+                        module: module_name.to_string(),
+                        function: "".to_string(),
+                        line: Some(spec.start_line),
+                    }),
+                    counterexample: None,
+                    message: None,
+                    spec_index: Some(spec.index),
+                });
+            }
         }
 
         specs
@@ -293,3 +292,31 @@ impl Default for ProverExecutor {
         Self::new()
     }
 }
+
+/// Maps a line of `aptos move prove` output to the phase it announces
+fn prover_phase(line: &str) -> Option<Phase> {
+    let line = line.to_lowercase();
+    if line.contains("building") || line.contains("compiling") || line.contains("including dependency") {
+        Some(Phase::Compiling)
+    } else if line.contains("generating verification conditions") || line.contains("transforming bytecode") {
+        Some(Phase::GeneratingVcs)
+    } else if line.contains("running solver") || line.contains("running boogie") {
+        Some(Phase::Solving)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prover_phase() {
+        assert_eq!(prover_phase("BUILDING sentinel_verify"), Some(Phase::Compiling));
+        assert_eq!(prover_phase("[INFO] transforming bytecode"), Some(Phase::GeneratingVcs));
+        assert_eq!(prover_phase("[INFO] generating verification conditions"), Some(Phase::GeneratingVcs));
+        assert_eq!(prover_phase("[INFO] running solver"), Some(Phase::Solving));
+        assert_eq!(prover_phase("[INFO] 0.512s build, 0.103s trafo, 0.080s gen, 1.204s verify"), None);
+    }
+}
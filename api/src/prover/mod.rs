@@ -1,5 +1,9 @@
+pub mod coverage;
 pub mod executor;
+pub mod options;
+pub mod specs;
 pub mod types;
 
+pub use coverage::analyze_spec_coverage;
 pub use executor::ProverExecutor;
 pub use types::*;
@@ -0,0 +1,200 @@
+//! Translation of per-request `ProverOptions` into CLI flags and pragmas
+//!
+//! Solver settings map directly onto `aptos move prove` flags. Function and
+//! module selection and per-function timeouts have no CLI equivalent that
+//! accepts lists, so they are expressed as `pragma` spec blocks and injected
+//! alongside the submitted specs.
+
+use super::specs::declares_target;
+use super::types::{ProverBackend, ProverOptions, ProverVerbosity, SpecBlock};
+use crate::error::ApiError;
+use crate::move_package::is_language_version;
+
+impl ProverOptions {
+    /// Checks option values against the submitted module
+    pub fn validate(&self, move_code: &str) -> Result<(), ApiError> {
+        for function in self.functions.iter().chain(self.function_timeouts.keys()) {
+            if !declares_target(move_code, function) {
+                return Err(ApiError::BadRequest(format!(
+                    "options: no function named `{}` in module",
+                    function
+                )));
+            }
+        }
+
+        if self.function_timeouts.values().any(|&t| t == 0) {
+            return Err(ApiError::BadRequest(
+                "options: function timeouts must be positive".to_string(),
+            ));
+        }
+        if self.vc_timeout == Some(0) {
+            return Err(ApiError::BadRequest(
+                "options: vc_timeout must be positive".to_string(),
+            ));
+        }
+        if self.cores == Some(0) {
+            return Err(ApiError::BadRequest(
+                "options: cores must be positive".to_string(),
+            ));
+        }
+
+        if let Some(version) = &self.language_version {
+            if !is_language_version(version) {
+                return Err(ApiError::BadRequest(format!(
+                    "options: invalid language version `{}`",
+                    version
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Extra arguments appended to `aptos move prove`
+    pub fn cli_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if let Some(vc_timeout) = self.vc_timeout {
+            args.push("--vc-timeout".to_string());
+            args.push(vc_timeout.to_string());
+        }
+        if let Some(seed) = self.random_seed {
+            args.push("--random-seed".to_string());
+            args.push(seed.to_string());
+        }
+        if let Some(cores) = self.cores {
+            args.push("--proc-cores".to_string());
+            args.push(cores.to_string());
+        }
+        if self.backend == ProverBackend::Cvc5 {
+            args.push("--cvc5".to_string());
+        }
+        if let Some(version) = &self.language_version {
+            args.push("--language-version".to_string());
+            args.push(version.clone());
+        }
+        if let Some(verbosity) = self.verbosity {
+            let level = match verbosity {
+                ProverVerbosity::Error => "error",
+                ProverVerbosity::Warn => "warn",
+                ProverVerbosity::Info => "info",
+                ProverVerbosity::Debug => "debug",
+            };
+            args.push("--verbosity".to_string());
+            args.push(level.to_string());
+        }
+
+        args
+    }
+
+    /// Pragma spec blocks implementing function/module selection and timeouts
+    pub fn pragma_specs(&self, module_name: &str) -> Vec<SpecBlock> {
+        let mut specs = Vec::new();
+
+        let module_excluded =
+            !self.modules.is_empty() && !self.modules.iter().any(|m| m == module_name);
+
+        if module_excluded || !self.functions.is_empty() {
+            specs.push(pragma_block(None, "pragma verify = false"));
+        }
+
+        if !module_excluded {
+            for function in &self.functions {
+                specs.push(pragma_block(Some(function), "pragma verify = true"));
+            }
+        }
+
+        for (function, seconds) in &self.function_timeouts {
+            specs.push(pragma_block(
+                Some(function),
+                &format!("pragma timeout = {}", seconds),
+            ));
+        }
+
+        specs
+    }
+}
+
+fn pragma_block(target: Option<&str>, pragma: &str) -> SpecBlock {
+    SpecBlock {
+        name: None,
+        target: target.map(String::from),
+        schema: None,
+        conditions: vec![pragma.to_string()],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = "module 0x1::math {\n    public fun add(x: u64, y: u64): u64 { x + y }\n    public fun sub(x: u64, y: u64): u64 { x - y }\n}\n";
+
+    fn options(value: serde_json::Value) -> ProverOptions {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_default_options_add_nothing() {
+        let opts = ProverOptions::default();
+        assert!(opts.cli_args().is_empty());
+        assert!(opts.pragma_specs("math").is_empty());
+        assert!(opts.validate(CODE).is_ok());
+    }
+
+    #[test]
+    fn test_cli_args() {
+        let opts = options(serde_json::json!({
+            "vc_timeout": 40,
+            "random_seed": 7,
+            "cores": 4,
+            "backend": "cvc5",
+            "language_version": "2.0",
+            "verbosity": "debug",
+        }));
+
+        assert_eq!(
+            opts.cli_args(),
+            vec![
+                "--vc-timeout", "40", "--random-seed", "7", "--proc-cores", "4", "--cvc5",
+                "--language-version", "2.0", "--verbosity", "debug",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_function_selection_pragmas() {
+        let opts = options(serde_json::json!({
+            "functions": ["add"],
+            "function_timeouts": { "sub": 120 },
+        }));
+        let specs = opts.pragma_specs("math");
+
+        assert_eq!(specs.len(), 3);
+        assert_eq!(specs[0].target, None);
+        assert_eq!(specs[0].conditions, vec!["pragma verify = false"]);
+        assert_eq!(specs[1].target.as_deref(), Some("add"));
+        assert_eq!(specs[1].conditions, vec!["pragma verify = true"]);
+        assert_eq!(specs[2].target.as_deref(), Some("sub"));
+        assert_eq!(specs[2].conditions, vec!["pragma timeout = 120"]);
+    }
+
+    #[test]
+    fn test_module_selection_pragmas() {
+        let listed = options(serde_json::json!({ "modules": ["math"] }));
+        assert!(listed.pragma_specs("math").is_empty());
+
+        let excluded = options(serde_json::json!({ "modules": ["other"], "functions": ["add"] }));
+        let specs = excluded.pragma_specs("math");
+        assert_eq!(specs.len(), 1);
+        assert_eq!(specs[0].conditions, vec!["pragma verify = false"]);
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_values() {
+        assert!(options(serde_json::json!({ "functions": ["mul"] })).validate(CODE).is_err());
+        assert!(options(serde_json::json!({ "function_timeouts": { "add": 0 } })).validate(CODE).is_err());
+        assert!(options(serde_json::json!({ "language_version": "2.x" })).validate(CODE).is_err());
+        assert!(options(serde_json::json!({ "cores": 0 })).validate(CODE).is_err());
+    }
+}
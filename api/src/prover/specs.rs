@@ -0,0 +1,280 @@
+//! Spec block injection for prover requests
+//!
+//! Specs submitted separately from the code are rendered as Move `spec`
+//! blocks and spliced in just before the target module's closing brace.
+//! Nothing is inserted above that point, so line numbers in the submitted
+//! source stay valid up to the end of the target module and prover errors
+//! inside the injected region can be mapped back to the spec entry that
+//! produced them.
+
+use regex::Regex;
+
+use super::types::{ProverRequest, SpecBlock};
+use crate::error::ApiError;
+use crate::move_package::{matching_brace, strip_comments};
+
+/// Line range occupied by an injected spec block (1-based, inclusive)
+#[derive(Debug, Clone)]
+pub struct InjectedSpec {
+    /// Index into `ProverRequest.specs`
+    pub index: usize,
+    /// Label supplied with the spec entry
+    pub name: Option<String>,
+    /// Function or struct name, `schema <name>`, or `module`
+    pub target: String,
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
+/// Move source with spec blocks spliced in
+#[derive(Debug, Clone)]
+pub struct InjectedSource {
+    pub code: String,
+    pub specs: Vec<InjectedSpec>,
+}
+
+impl InjectedSource {
+    /// Returns the injected spec entry covering `line`, if any
+    pub fn spec_at_line(&self, line: u32) -> Option<&InjectedSpec> {
+        self.specs
+            .iter()
+            .find(|s| line >= s.start_line && line <= s.end_line)
+    }
+}
+
+/// Builds the source that is actually proved for a request
+///
+/// Submitted specs are injected first, followed by the pragmas derived from
+/// the prover options. Only the submitted entries are kept in the line map,
+/// so failures are reported against `ProverRequest.specs` indices.
+pub fn prepare_source(request: &ProverRequest) -> Result<InjectedSource, ApiError> {
+    request.options.validate(&request.move_code)?;
+
+    let mut specs = request.specs.clone();
+    specs.extend(request.options.pragma_specs(&request.module_name));
+
+    let mut source = inject_specs(&request.move_code, &request.module_name, &specs)?;
+    source.specs.truncate(request.specs.len());
+    Ok(source)
+}
+
+/// Splices the given spec blocks into the named module
+pub fn inject_specs(
+    move_code: &str,
+    module_name: &str,
+    specs: &[SpecBlock],
+) -> Result<InjectedSource, ApiError> {
+    if specs.is_empty() {
+        return Ok(InjectedSource {
+            code: move_code.to_string(),
+            specs: vec![],
+        });
+    }
+
+    let (open, close) = find_module(move_code, module_name).ok_or_else(|| {
+        ApiError::BadRequest(format!(
+            "Cannot inject specs: no complete module named `{}`",
+            module_name
+        ))
+    })?;
+    let body = &move_code[open + 1..close];
+    let (head, tail) = move_code.split_at(close);
+
+    let mut code = head.trim_end().to_string();
+    code.push_str("\n\n    // Specs injected by Sentinel\n");
+
+    let mut injected = Vec::with_capacity(specs.len());
+    for (index, spec) in specs.iter().enumerate() {
+        let block = render_spec_block(body, spec)
+            .map_err(|e| ApiError::BadRequest(format!("specs[{}]: {}", index, e)))?;
+
+        let start_line = code.matches('\n').count() as u32 + 1;
+        code.push_str(&block);
+        let end_line = code.matches('\n').count() as u32;
+
+        let target = match (&spec.target, &spec.schema) {
+            (Some(target), _) => target.clone(),
+            (None, Some(schema)) => format!("schema {}", schema),
+            (None, None) => "module".to_string(),
+        };
+
+        injected.push(InjectedSpec {
+            index,
+            name: spec.name.clone(),
+            target,
+            start_line,
+            end_line,
+        });
+    }
+
+    code.push_str(tail);
+
+    Ok(InjectedSource {
+        code,
+        specs: injected,
+    })
+}
+
+/// Byte offsets of the named module's opening and closing braces
+///
+/// Comments are blanked before scanning, so braces inside them and modules
+/// declared after the target do not affect where the specs go.
+fn find_module(move_code: &str, module_name: &str) -> Option<(usize, usize)> {
+    let code = strip_comments(move_code);
+    let module_re = Regex::new(&format!(
+        r"\bmodule\s+(?:\w+\s*::\s*)?{}\s*\{{",
+        regex::escape(module_name)
+    ))
+    .ok()?;
+
+    let open = module_re.find(&code)?.end() - 1;
+    let close = matching_brace(&code, open)?;
+    Some((open, close))
+}
+
+/// Renders a single spec entry as a Move `spec` block (newline-terminated)
+fn render_spec_block(module_body: &str, spec: &SpecBlock) -> Result<String, String> {
+    if spec.conditions.is_empty() {
+        return Err("at least one condition is required".to_string());
+    }
+
+    let header = match (&spec.target, &spec.schema) {
+        (Some(_), Some(_)) => {
+            return Err("`target` and `schema` are mutually exclusive".to_string());
+        }
+        (Some(target), None) => {
+            validate_identifier(target)?;
+            if !declares_target(module_body, target) {
+                return Err(format!("no function or struct named `{}` in module", target));
+            }
+            format!("spec {} {{", target)
+        }
+        (None, Some(schema)) => {
+            validate_identifier(schema)?;
+            format!("spec schema {} {{", schema)
+        }
+        (None, None) => "spec module {".to_string(),
+    };
+
+    let mut block = format!("    {}\n", header);
+    for condition in &spec.conditions {
+        let condition = condition.trim();
+        block.push_str("        ");
+        block.push_str(condition);
+        if !condition.ends_with(';') && !condition.ends_with('}') {
+            block.push(';');
+        }
+        block.push('\n');
+    }
+    block.push_str("    }\n");
+
+    Ok(block)
+}
+
+fn validate_identifier(name: &str) -> Result<(), String> {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("`{}` is not a valid Move identifier", name))
+    }
+}
+
+/// Returns true if the module declares a function or struct with this name
+pub fn declares_target(move_code: &str, name: &str) -> bool {
+    Regex::new(&format!(r"\b(?:fun|struct)\s+{}\b", regex::escape(name)))
+        .map(|re| re.is_match(move_code))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = "module 0x1::counter {\n    struct Counter has key { value: u64 }\n\n    public fun add(x: u64, y: u64): u64 {\n        x + y\n    }\n}\n";
+
+    fn block(target: Option<&str>, schema: Option<&str>, conditions: &[&str]) -> SpecBlock {
+        SpecBlock {
+            name: None,
+            target: target.map(String::from),
+            schema: schema.map(String::from),
+            conditions: conditions.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_no_specs_leaves_code_untouched() {
+        let injected = inject_specs(CODE, "counter", &[]).unwrap();
+        assert_eq!(injected.code, CODE);
+        assert!(injected.specs.is_empty());
+    }
+
+    #[test]
+    fn test_inject_function_and_module_specs() {
+        let specs = vec![
+            block(Some("add"), None, &["ensures result == x + y", "aborts_if x + y > MAX_U64;"]),
+            block(None, None, &["invariant true"]),
+        ];
+        let injected = inject_specs(CODE, "counter", &specs).unwrap();
+
+        assert!(injected.code.contains("    spec add {\n        ensures result == x + y;\n        aborts_if x + y > MAX_U64;\n    }\n"));
+        assert!(injected.code.contains("    spec module {\n        invariant true;\n    }\n"));
+        assert!(injected.code.trim_end().ends_with('}'));
+
+        // Original lines keep their numbers
+        let original: Vec<&str> = CODE.lines().collect();
+        let spliced: Vec<&str> = injected.code.lines().collect();
+        assert_eq!(&spliced[..6], &original[..6]);
+
+        let lines: Vec<&str> = injected.code.lines().collect();
+        let first = &injected.specs[0];
+        assert_eq!(lines[first.start_line as usize - 1].trim(), "spec add {");
+        assert_eq!(lines[first.end_line as usize - 1].trim(), "}");
+        assert_eq!(injected.spec_at_line(first.start_line + 1).unwrap().index, 0);
+        assert_eq!(injected.spec_at_line(injected.specs[1].start_line + 1).unwrap().index, 1);
+        assert!(injected.spec_at_line(2).is_none());
+    }
+
+    #[test]
+    fn test_inject_schema() {
+        let specs = vec![block(None, Some("AddAbortsIf"), &["aborts_if false"])];
+        let injected = inject_specs(CODE, "counter", &specs).unwrap();
+        assert!(injected.code.contains("spec schema AddAbortsIf {"));
+    }
+
+    #[test]
+    fn test_rejects_unknown_target() {
+        let specs = vec![block(Some("missing"), None, &["ensures true"])];
+        assert!(inject_specs(CODE, "counter", &specs).is_err());
+    }
+
+    #[test]
+    fn test_inject_into_named_module() {
+        let code = "module 0x1::counter {\n    public fun add(x: u64, y: u64): u64 { x + y }\n}\n\nmodule 0x1::other {\n    public fun sub(x: u64, y: u64): u64 { x - y }\n}\n// }\n/* } */\n";
+        let specs = vec![block(Some("add"), None, &["ensures result == x + y"])];
+        let injected = inject_specs(code, "counter", &specs).unwrap();
+
+        let (counter, rest) = injected.code.split_once("module 0x1::other").unwrap();
+        assert!(counter.contains("    spec add {\n        ensures result == x + y;\n    }\n}"));
+        assert!(rest.ends_with("{ x - y }\n}\n// }\n/* } */\n"));
+        assert!(!rest.contains("spec"));
+
+        // Targets are looked up in the named module only
+        let specs = vec![block(Some("sub"), None, &["ensures true"])];
+        assert!(inject_specs(code, "counter", &specs).is_err());
+        assert!(inject_specs(code, "other", &specs).is_ok());
+        assert!(inject_specs(code, "missing", &specs).is_err());
+    }
+
+    #[test]
+    fn test_rejects_invalid_entries() {
+        assert!(inject_specs(CODE, "counter", &[block(Some("add"), None, &[])]).is_err());
+        assert!(inject_specs(CODE, "counter", &[block(Some("add"), Some("S"), &["ensures true"])]).is_err());
+        assert!(inject_specs(CODE, "counter", &[block(Some("add {"), None, &["ensures true"])]).is_err());
+    }
+}
@@ -1,20 +1,94 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct ProverRequest {
     pub move_code: String,
     pub module_name: String,
+    /// Spec blocks to splice into the module before proving
     #[serde(default)]
-    pub specs: Vec<String>,
+    pub specs: Vec<SpecBlock>,
     #[serde(default = "default_timeout")]
     pub timeout_seconds: u32,
+    /// Prover configuration passed through to `aptos move prove`
+    #[serde(default)]
+    pub options: ProverOptions,
 }
 
 fn default_timeout() -> u32 {
     60
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// Per-request prover configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProverOptions {
+    /// Verify only these functions (all others get `pragma verify = false`)
+    #[serde(default)]
+    pub functions: Vec<String>,
+    /// Verify only these modules; the submitted module is skipped if not listed
+    #[serde(default)]
+    pub modules: Vec<String>,
+    /// Per-function solver timeouts in seconds (`pragma timeout = N`)
+    #[serde(default)]
+    pub function_timeouts: BTreeMap<String, u32>,
+    /// Timeout in seconds for each verification condition (`--vc-timeout`)
+    #[serde(default)]
+    pub vc_timeout: Option<u32>,
+    /// Solver random seed (`--random-seed`)
+    #[serde(default)]
+    pub random_seed: Option<u64>,
+    /// Number of cores used by the solver (`--proc-cores`)
+    #[serde(default)]
+    pub cores: Option<u16>,
+    /// Boogie solver backend
+    #[serde(default)]
+    pub backend: ProverBackend,
+    /// Move language version, e.g. "2.0" (`--language-version`)
+    #[serde(default)]
+    pub language_version: Option<String>,
+    /// Prover log verbosity (`--verbosity`)
+    #[serde(default)]
+    pub verbosity: Option<ProverVerbosity>,
+    /// Report a failure when the prover emits any warnings
+    #[serde(default)]
+    pub warnings_as_errors: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProverBackend {
+    #[default]
+    Z3,
+    Cvc5,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProverVerbosity {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+/// A spec block submitted separately from the module source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpecBlock {
+    /// Optional label reported back in `SpecResult.name`
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Function or struct the spec attaches to; omit for `spec module`
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Declares `spec schema <name>` instead of attaching to a target
+    #[serde(default)]
+    pub schema: Option<String>,
+    /// Spec conditions, e.g. `ensures result == x + 1` or `aborts_if x == 0`
+    pub conditions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProverResult {
     pub status: ProverStatus,
     pub duration_ms: u64,
@@ -22,9 +96,15 @@ pub struct ProverResult {
     pub summary: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub raw_output: Option<String>,
+    /// True when the result was served from the prover cache
+    #[serde(default)]
+    pub cached: bool,
+    /// Id to pass as `prover_run_id` to `/reports/markdown`; set once stored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<Uuid>,
 }
 
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum ProverStatus {
     Passed,
@@ -33,7 +113,7 @@ pub enum ProverStatus {
     Error,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModuleResult {
     pub name: String,
     pub status: ProverStatus,
@@ -41,7 +121,7 @@ pub struct ModuleResult {
     pub invariants: Vec<InvariantResult>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpecResult {
     pub name: String,
     pub function: String,
@@ -52,9 +132,12 @@ pub struct SpecResult {
     pub counterexample: Option<Counterexample>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
+    /// Index into `ProverRequest.specs` when the spec was injected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spec_index: Option<usize>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InvariantResult {
     pub name: String,
     pub status: ProverStatus,
@@ -62,7 +145,7 @@ pub struct InvariantResult {
     pub violated_at: Option<SourceLocation>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SourceLocation {
     pub module: String,
     pub function: String,
@@ -70,9 +153,83 @@ pub struct SourceLocation {
     pub line: Option<u32>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Counterexample {
     pub inputs: std::collections::HashMap<String, serde_json::Value>,
     pub trace: Vec<String>,
     pub failed_assertion: String,
 }
+
+// Spec coverage types for audit reporting
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpecCoverageRequest {
+    #[serde(flatten)]
+    pub prover: ProverRequest,
+    /// Prover result to score against; defaults to the cached result for
+    /// the same prover request
+    #[serde(default)]
+    pub prover_result: Option<ProverResult>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpecCoverageReport {
+    pub modules: Vec<ModuleCoverage>,
+    /// Status of the prover result the scores are based on, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prover_status: Option<ProverStatus>,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleCoverage {
+    pub name: String,
+    /// Public and entry functions
+    pub total_functions: u32,
+    pub specified_functions: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified_functions: Option<u32>,
+    /// verified_functions / total_functions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    pub aborts_if_is_strict: bool,
+    pub module_invariants: u32,
+    pub struct_invariants: Vec<StructInvariants>,
+    pub functions: Vec<FunctionCoverage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StructInvariants {
+    pub name: String,
+    pub invariants: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionCoverage {
+    pub name: String,
+    pub is_public: bool,
+    pub is_entry: bool,
+    pub line: u32,
+    pub level: SpecLevel,
+    pub requires: u32,
+    pub ensures: u32,
+    pub aborts_if: u32,
+    pub aborts_if_is_strict: bool,
+    pub verify_disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified: Option<bool>,
+}
+
+/// How thoroughly a function is specified
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SpecLevel {
+    /// No conditions at all
+    None,
+    /// Pre/post conditions but no abort conditions
+    Conditions,
+    /// Has `aborts_if`, but aborts are not required to be exhaustive
+    AbortsIf,
+    /// `pragma aborts_if_is_strict` makes the abort conditions complete
+    Strict,
+}
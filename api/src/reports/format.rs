@@ -0,0 +1,103 @@
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::{header, request::Parts},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{JunitReport, SarifReport};
+use crate::error::ApiError;
+
+const SARIF_CONTENT_TYPE: &str = "application/sarif+json";
+const JUNIT_CONTENT_TYPE: &str = "application/xml";
+
+/// Response format requested by the client
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Sarif,
+    Junit,
+}
+
+#[derive(Deserialize)]
+struct FormatQuery {
+    format: Option<ReportFormat>,
+}
+
+impl ReportFormat {
+    /// Picks a format from an `Accept` header value, ignoring unknown types
+    fn from_accept(accept: &str) -> Self {
+        for media_type in accept.split(',') {
+            let media_type = media_type.split(';').next().unwrap_or("").trim();
+            match media_type {
+                SARIF_CONTENT_TYPE => return ReportFormat::Sarif,
+                "application/junit+xml" | "application/xml" | "text/xml" => {
+                    return ReportFormat::Junit
+                }
+                "application/json" => return ReportFormat::Json,
+                _ => {}
+            }
+        }
+        ReportFormat::Json
+    }
+
+    /// Renders a result in this format
+    pub fn respond<T>(self, result: &T) -> Response
+    where
+        T: Serialize + SarifReport + JunitReport,
+    {
+        match self {
+            ReportFormat::Json => Json(result).into_response(),
+            ReportFormat::Sarif => (
+                [(header::CONTENT_TYPE, SARIF_CONTENT_TYPE)],
+                Json(result.to_sarif()),
+            )
+                .into_response(),
+            ReportFormat::Junit => (
+                [(header::CONTENT_TYPE, JUNIT_CONTENT_TYPE)],
+                result.to_junit(),
+            )
+                .into_response(),
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ReportFormat {
+    type Rejection = ApiError;
+
+    /// The `format` query parameter takes precedence over `Accept`
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let query = Query::<FormatQuery>::try_from_uri(&parts.uri).map_err(|_| {
+            ApiError::BadRequest("format must be one of: json, sarif, junit".to_string())
+        })?;
+        if let Some(format) = query.0.format {
+            return Ok(format);
+        }
+
+        Ok(parts
+            .headers
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .map(ReportFormat::from_accept)
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_accept() {
+        assert_eq!(ReportFormat::from_accept("application/sarif+json"), ReportFormat::Sarif);
+        assert_eq!(ReportFormat::from_accept("text/xml; charset=utf-8"), ReportFormat::Junit);
+        assert_eq!(ReportFormat::from_accept("*/*"), ReportFormat::Json);
+        assert_eq!(
+            ReportFormat::from_accept("application/json, application/xml"),
+            ReportFormat::Json
+        );
+    }
+}
@@ -0,0 +1,397 @@
+//! JUnit XML serialization
+//!
+//! Uses the common Ant/Surefire dialect understood by CI test tabs: one
+//! `<testsuite>` per module (or per run for simulations and gas) and one
+//! `<testcase>` per spec, test or scenario.
+
+use std::fmt::Write;
+
+use crate::gas::GasProfile;
+use crate::move_test::{TestCaseStatus, TestResult, TestRunStatus};
+use crate::prover::{ProverResult, ProverStatus};
+use crate::simulation::BatchSimulationResult;
+
+/// Types that can be rendered as a JUnit XML document
+pub trait JunitReport {
+    fn to_junit(&self) -> String;
+}
+
+enum Outcome {
+    Passed,
+    Failure { message: String, details: String },
+    Error { message: String, details: String },
+    Skipped { message: String },
+}
+
+struct Case {
+    name: String,
+    classname: String,
+    outcome: Outcome,
+    system_out: Option<String>,
+}
+
+struct Suite {
+    name: String,
+    time_ms: u64,
+    cases: Vec<Case>,
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than tab/newline are not valid XML 1.0
+            c if c.is_control() && c != '\n' && c != '\t' && c != '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn render(suites: &[Suite]) -> String {
+    let count = |suite: &Suite, failure: bool| {
+        suite
+            .cases
+            .iter()
+            .filter(|c| match c.outcome {
+                Outcome::Failure { .. } => failure,
+                Outcome::Error { .. } => !failure,
+                Outcome::Passed | Outcome::Skipped { .. } => false,
+            })
+            .count()
+    };
+    let skipped = |suite: &Suite| {
+        suite
+            .cases
+            .iter()
+            .filter(|c| matches!(c.outcome, Outcome::Skipped { .. }))
+            .count()
+    };
+
+    let tests: usize = suites.iter().map(|s| s.cases.len()).sum();
+    let failures: usize = suites.iter().map(|s| count(s, true)).sum();
+    let errors: usize = suites.iter().map(|s| count(s, false)).sum();
+    let time_ms: u64 = suites.iter().map(|s| s.time_ms).sum();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"Sentinel\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+        tests,
+        failures,
+        errors,
+        time_ms as f64 / 1000.0
+    );
+
+    for suite in suites {
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.3}\">",
+            escape(&suite.name),
+            suite.cases.len(),
+            count(suite, true),
+            count(suite, false),
+            skipped(suite),
+            suite.time_ms as f64 / 1000.0
+        );
+
+        for case in &suite.cases {
+            let _ = write!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"{}\"",
+                escape(&case.name),
+                escape(&case.classname)
+            );
+
+            if matches!(case.outcome, Outcome::Passed) && case.system_out.is_none() {
+                xml.push_str("/>\n");
+                continue;
+            }
+            xml.push_str(">\n");
+
+            match &case.outcome {
+                Outcome::Passed => {}
+                Outcome::Failure { message, details } => {
+                    let _ = writeln!(
+                        xml,
+                        "      <failure message=\"{}\">{}</failure>",
+                        escape(message),
+                        escape(details)
+                    );
+                }
+                Outcome::Error { message, details } => {
+                    let _ = writeln!(
+                        xml,
+                        "      <error message=\"{}\">{}</error>",
+                        escape(message),
+                        escape(details)
+                    );
+                }
+                Outcome::Skipped { message } => {
+                    let _ = writeln!(xml, "      <skipped message=\"{}\"/>", escape(message));
+                }
+            }
+            if let Some(out) = &case.system_out {
+                let _ = writeln!(xml, "      <system-out>{}</system-out>", escape(out));
+            }
+            xml.push_str("    </testcase>\n");
+        }
+
+        xml.push_str("  </testsuite>\n");
+    }
+
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+impl JunitReport for ProverResult {
+    fn to_junit(&self) -> String {
+        let mut suites: Vec<Suite> = self
+            .modules
+            .iter()
+            .map(|module| Suite {
+                name: format!("prover: {}", module.name),
+                time_ms: self.duration_ms,
+                cases: module
+                    .specs
+                    .iter()
+                    .map(|spec| {
+                        let message = spec.message.clone().unwrap_or_else(|| spec.name.clone());
+                        let details = spec
+                            .counterexample
+                            .as_ref()
+                            .map(|ce| ce.trace.join("\n"))
+                            .unwrap_or_default();
+                        let outcome = match spec.status {
+                            ProverStatus::Passed => Outcome::Passed,
+                            ProverStatus::Failed => Outcome::Failure { message, details },
+                            _ => Outcome::Error { message, details },
+                        };
+                        Case {
+                            name: spec.name.clone(),
+                            classname: format!("{}::{}", module.name, spec.function),
+                            outcome,
+                            system_out: None,
+                        }
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        // Surface run-level failures that produced no spec results
+        let has_cases = suites.iter().any(|s| !s.cases.is_empty());
+        if !has_cases && self.status != ProverStatus::Passed {
+            suites.push(Suite {
+                name: "prover".to_string(),
+                time_ms: self.duration_ms,
+                cases: vec![Case {
+                    name: "prove".to_string(),
+                    classname: "prover".to_string(),
+                    outcome: Outcome::Error {
+                        message: self.summary.clone(),
+                        details: self.raw_output.clone().unwrap_or_default(),
+                    },
+                    system_out: None,
+                }],
+            });
+        }
+
+        render(&suites)
+    }
+}
+
+impl JunitReport for TestResult {
+    fn to_junit(&self) -> String {
+        let mut suites: Vec<Suite> = Vec::new();
+
+        for test in &self.tests {
+            let outcome = match test.status {
+                TestCaseStatus::Passed => Outcome::Passed,
+                TestCaseStatus::Failed => Outcome::Failure {
+                    message: match &test.abort {
+                        Some(abort) => format!("aborted with code {}", abort.code),
+                        None => "test failure".to_string(),
+                    },
+                    details: test.failure.clone().unwrap_or_default(),
+                },
+                TestCaseStatus::Timeout => Outcome::Error {
+                    message: "test timed out".to_string(),
+                    details: String::new(),
+                },
+            };
+            let case = Case {
+                name: test.function.clone(),
+                classname: test.module.clone(),
+                outcome,
+                system_out: test.gas_used.map(|gas| format!("gas_used: {}", gas)),
+            };
+
+            match suites.iter_mut().find(|s| s.name == test.module) {
+                Some(suite) => suite.cases.push(case),
+                None => suites.push(Suite {
+                    name: test.module.clone(),
+                    time_ms: 0,
+                    cases: vec![case],
+                }),
+            }
+        }
+
+        // Build failures and run timeouts produce no per-test results
+        if matches!(self.status, TestRunStatus::Error | TestRunStatus::Timeout) {
+            let details = self
+                .diagnostics
+                .iter()
+                .map(|d| match (&d.file, &d.span) {
+                    (Some(file), Some(span)) => format!("{}:{}: {}", file, span.start_line, d.message),
+                    _ => d.message.clone(),
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            suites.push(Suite {
+                name: "build".to_string(),
+                time_ms: 0,
+                cases: vec![Case {
+                    name: "build".to_string(),
+                    classname: "package".to_string(),
+                    outcome: Outcome::Error {
+                        message: self.summary.clone(),
+                        details,
+                    },
+                    system_out: None,
+                }],
+            });
+        }
+
+        if let Some(first) = suites.first_mut() {
+            first.time_ms = self.duration_ms;
+        }
+
+        render(&suites)
+    }
+}
+
+impl JunitReport for BatchSimulationResult {
+    fn to_junit(&self) -> String {
+        let cases = self
+            .results
+            .iter()
+            .map(|r| Case {
+                name: r.name.clone(),
+                classname: "simulation".to_string(),
+                outcome: if r.passed {
+                    Outcome::Passed
+                } else if r.skipped {
+                    Outcome::Skipped {
+                        message: r.failure_reason.clone().unwrap_or_default(),
+                    }
+                } else {
+                    Outcome::Failure {
+                        message: r
+                            .failure_reason
+                            .clone()
+                            .unwrap_or_else(|| "scenario failed".to_string()),
+                        details: r.actual_error.clone().unwrap_or_default(),
+                    }
+                },
+                system_out: Some(format!("gas_used: {}", r.gas_used)),
+            })
+            .collect();
+
+        render(&[Suite {
+            name: "simulation".to_string(),
+            time_ms: 0,
+            cases,
+        }])
+    }
+}
+
+impl JunitReport for GasProfile {
+    fn to_junit(&self) -> String {
+        let mut cases = vec![Case {
+            name: "total_gas".to_string(),
+            classname: "gas".to_string(),
+            outcome: Outcome::Passed,
+            system_out: Some(format!("total_gas: {}", self.total_gas)),
+        }];
+
+        // Only critical suggestions fail the suite; others are informational
+        cases.extend(self.suggestions.iter().map(|s| Case {
+            name: s.location.clone().unwrap_or_else(|| "suggestion".to_string()),
+            classname: format!("gas.{}", s.severity),
+            outcome: if s.severity == "critical" {
+                Outcome::Failure {
+                    message: s.message.clone(),
+                    details: format!("estimated_savings: {}", s.estimated_savings),
+                }
+            } else {
+                Outcome::Passed
+            },
+            system_out: Some(s.message.clone()),
+        }));
+
+        render(&[Suite {
+            name: "gas".to_string(),
+            time_ms: 0,
+            cases,
+        }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::ScenarioResult;
+
+    #[test]
+    fn test_batch_result_to_junit() {
+        let result = BatchSimulationResult {
+            total: 2,
+            passed: 1,
+            failed: 1,
+            results: vec![
+                ScenarioResult {
+                    name: "deposit".to_string(),
+                    passed: true,
+                    gas_used: 120,
+                    expected_success: Some(true),
+                    actual_success: true,
+                    expected_error: None,
+                    actual_error: None,
+                    failure_reason: None,
+                    failures: vec![],
+                    skipped: false,
+                },
+                ScenarioResult {
+                    name: "withdraw <too much>".to_string(),
+                    passed: false,
+                    gas_used: 80,
+                    expected_success: Some(true),
+                    actual_success: false,
+                    expected_error: None,
+                    actual_error: Some("EINSUFFICIENT_BALANCE".to_string()),
+                    failure_reason: Some("Expected success but got \"abort\"".to_string()),
+                    failures: vec!["Expected success but got \"abort\"".to_string()],
+                    skipped: false,
+                },
+            ],
+            skipped: 0,
+            max_gas_used: 120,
+            gas_p50: 80,
+            gas_p95: 120,
+            summary: "1/2 passed".to_string(),
+            run_id: None,
+        };
+
+        let xml = result.to_junit();
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains("<testsuites name=\"Sentinel\" tests=\"2\" failures=\"1\" errors=\"0\""));
+        assert!(xml.contains("<testcase name=\"withdraw &lt;too much&gt;\" classname=\"simulation\">"));
+        assert!(xml.contains("<failure message=\"Expected success but got &quot;abort&quot;\">EINSUFFICIENT_BALANCE</failure>"));
+        assert!(xml.contains("<system-out>gas_used: 120</system-out>"));
+    }
+}
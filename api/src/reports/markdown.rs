@@ -0,0 +1,357 @@
+//! Markdown pull request comment rendering
+//!
+//! The comment opens with an overview table and puts each check in a
+//! `<details>` block, expanded when it failed. If the full rendering exceeds
+//! GitHub's comment limit it is re-rendered without counterexamples and
+//! passing rows, and as a last resort cut off with a note.
+
+use std::fmt::Write;
+
+use super::types::{GasBaseline, MarkdownReport, MarkdownReportRequest};
+use crate::gas::GasProfile;
+use crate::prover::{ProverResult, ProverStatus};
+use crate::simulation::BatchSimulationResult;
+
+/// GitHub rejects issue and pull request comments longer than this
+pub const GITHUB_COMMENT_LIMIT: usize = 65_536;
+
+const TRUNCATION_NOTE: &str = "\n\n> Report truncated to fit GitHub's comment size limit.\n";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Detail {
+    Full,
+    Compact,
+}
+
+/// Renders the combined results as a single comment body
+pub fn render_markdown(request: &MarkdownReportRequest) -> MarkdownReport {
+    render_within(request, GITHUB_COMMENT_LIMIT)
+}
+
+fn render_within(request: &MarkdownReportRequest, limit: usize) -> MarkdownReport {
+    let full = render(request, Detail::Full);
+    if full.chars().count() <= limit {
+        return report(full, false);
+    }
+
+    let compact = render(request, Detail::Compact);
+    if compact.chars().count() <= limit {
+        return report(compact, true);
+    }
+
+    let keep = limit.saturating_sub(TRUNCATION_NOTE.chars().count());
+    let mut cut: String = compact.chars().take(keep).collect();
+    // Don't leave an open code fence swallowing the note
+    if cut.matches("```").count() % 2 == 1 {
+        cut.truncate(cut.rfind("```").unwrap_or(cut.len()));
+    }
+    cut.push_str(TRUNCATION_NOTE);
+    report(cut, true)
+}
+
+fn report(markdown: String, truncated: bool) -> MarkdownReport {
+    MarkdownReport {
+        length: markdown.chars().count(),
+        markdown,
+        truncated,
+    }
+}
+
+fn render(request: &MarkdownReportRequest, detail: Detail) -> String {
+    let baseline = request.baseline.clone().unwrap_or_default();
+    let mut md = String::new();
+
+    let _ = writeln!(
+        md,
+        "## {}\n",
+        request.title.as_deref().unwrap_or("Sentinel Report")
+    );
+
+    md.push_str("| Check | Status | Details |\n|---|---|---|\n");
+    if let Some(prover) = &request.prover {
+        let status = match prover.status {
+            ProverStatus::Passed => "Passed",
+            ProverStatus::Failed => "Failed",
+            ProverStatus::Timeout => "Timeout",
+            ProverStatus::Error => "Error",
+        };
+        let _ = writeln!(md, "| Move Prover | {} | {} |", status, cell(&prover.summary));
+    }
+    if let Some(batch) = &request.batch {
+        let status = if batch.failed == 0 { "Passed" } else { "Failed" };
+        let _ = writeln!(
+            md,
+            "| Simulations | {} | {}/{} scenarios passed, max gas {} |",
+            status, batch.passed, batch.total, batch.max_gas_used
+        );
+    }
+    if let Some(gas) = &request.gas {
+        let _ = writeln!(
+            md,
+            "| Gas | {} | {} total{} |",
+            if gas.suggestions.iter().any(|s| s.severity == "critical") {
+                "Review"
+            } else {
+                "OK"
+            },
+            gas.total_gas,
+            delta(gas.total_gas, baseline.total_gas)
+                .map(|d| format!(" ({})", d))
+                .unwrap_or_default()
+        );
+    }
+
+    if let Some(prover) = &request.prover {
+        prover_section(&mut md, prover, detail);
+    }
+    if let Some(batch) = &request.batch {
+        batch_section(&mut md, batch, &baseline, detail);
+    }
+    if let Some(gas) = &request.gas {
+        gas_section(&mut md, gas, request.max_suggestions);
+    }
+
+    md
+}
+
+fn prover_section(md: &mut String, prover: &ProverResult, detail: Detail) {
+    let failing = prover.status != ProverStatus::Passed;
+    open_details(md, "Move Prover", &prover.summary, failing);
+
+    let rows: Vec<_> = prover
+        .modules
+        .iter()
+        .flat_map(|m| m.specs.iter().map(move |s| (m, s)))
+        .filter(|(_, s)| detail == Detail::Full || s.status != ProverStatus::Passed)
+        .collect();
+
+    if !rows.is_empty() {
+        md.push_str("| Module | Spec | Function | Status | Line |\n|---|---|---|---|---|\n");
+        for (module, spec) in &rows {
+            let line = spec
+                .location
+                .as_ref()
+                .and_then(|l| l.line)
+                .map(|l| l.to_string())
+                .unwrap_or_else(|| "-".to_string());
+            let _ = writeln!(
+                md,
+                "| {} | {} | {} | {:?} | {} |",
+                cell(&module.name),
+                cell(&spec.name),
+                cell(&spec.function),
+                spec.status,
+                line
+            );
+        }
+    }
+
+    if detail == Detail::Full {
+        let counterexamples: Vec<_> = rows
+            .iter()
+            .filter_map(|(_, s)| s.counterexample.as_ref().map(|ce| (s, ce)))
+            .collect();
+
+        if !counterexamples.is_empty() {
+            md.push_str("\n#### Counterexamples\n");
+            for (spec, ce) in counterexamples {
+                let _ = writeln!(
+                    md,
+                    "\n**{}**: {}\n",
+                    spec.name,
+                    spec.message.as_deref().unwrap_or(&ce.failed_assertion)
+                );
+                md.push_str("```\n");
+                let mut inputs: Vec<_> = ce.inputs.iter().collect();
+                inputs.sort_by(|a, b| a.0.cmp(b.0));
+                for (name, value) in inputs {
+                    let value = value.as_str().map(String::from).unwrap_or_else(|| value.to_string());
+                    let _ = writeln!(md, "{} = {}", name, value);
+                }
+                for step in &ce.trace {
+                    let _ = writeln!(md, "{}", step);
+                }
+                md.push_str("```\n");
+            }
+        }
+    }
+
+    close_details(md);
+}
+
+fn batch_section(
+    md: &mut String,
+    batch: &BatchSimulationResult,
+    baseline: &GasBaseline,
+    detail: Detail,
+) {
+    open_details(md, "Simulations", &batch.summary, batch.failed > 0);
+
+    let rows: Vec<_> = batch
+        .results
+        .iter()
+        .filter(|r| detail == Detail::Full || !r.passed)
+        .collect();
+
+    if !rows.is_empty() {
+        md.push_str("| Scenario | Status | Gas | Delta | Failure |\n|---|---|---|---|---|\n");
+        for result in rows {
+            let _ = writeln!(
+                md,
+                "| {} | {} | {} | {} | {} |",
+                cell(&result.name),
+                if result.passed {
+                    "Pass"
+                } else if result.skipped {
+                    "Skip"
+                } else {
+                    "Fail"
+                },
+                result.gas_used,
+                delta(result.gas_used, baseline.scenarios.get(&result.name).copied())
+                    .unwrap_or_default(),
+                cell(result.failure_reason.as_deref().unwrap_or("-"))
+            );
+        }
+    }
+
+    close_details(md);
+}
+
+fn gas_section(md: &mut String, gas: &GasProfile, max_suggestions: usize) {
+    let critical = gas.suggestions.iter().any(|s| s.severity == "critical");
+    open_details(md, "Gas", &format!("{} gas used", gas.total_gas), critical);
+
+    let mut suggestions: Vec<_> = gas.suggestions.iter().collect();
+    suggestions.sort_by_key(|s| std::cmp::Reverse(s.estimated_savings));
+
+    if suggestions.is_empty() {
+        md.push_str("No optimization suggestions.\n");
+    } else {
+        md.push_str("| Severity | Suggestion | Location | Est. savings |\n|---|---|---|---|\n");
+        for s in suggestions.into_iter().take(max_suggestions) {
+            let _ = writeln!(
+                md,
+                "| {} | {} | {} | {} |",
+                cell(&s.severity),
+                cell(&s.message),
+                cell(s.location.as_deref().unwrap_or("-")),
+                s.estimated_savings
+            );
+        }
+    }
+
+    close_details(md);
+}
+
+fn open_details(md: &mut String, title: &str, summary: &str, open: bool) {
+    let _ = write!(
+        md,
+        "\n<details{}>\n<summary><b>{}</b>: {}</summary>\n\n",
+        if open { " open" } else { "" },
+        title,
+        html_escape(summary)
+    );
+}
+
+fn close_details(md: &mut String) {
+    md.push_str("\n</details>\n");
+}
+
+/// Formats a change against the baseline, e.g. "+4.2%"
+fn delta(current: u64, baseline: Option<u64>) -> Option<String> {
+    let base = baseline.filter(|&b| b > 0)?;
+    let change = (current as f64 - base as f64) / base as f64 * 100.0;
+    Some(format!("{:+.1}%", change))
+}
+
+/// Keeps a value on one table row
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::ScenarioResult;
+
+    fn scenario(name: &str, passed: bool, gas_used: u64) -> ScenarioResult {
+        ScenarioResult {
+            name: name.to_string(),
+            passed,
+            gas_used,
+            expected_success: Some(true),
+            actual_success: passed,
+            expected_error: None,
+            actual_error: None,
+            failure_reason: (!passed).then(|| "Expected success | got abort".to_string()),
+            failures: vec![],
+            skipped: false,
+        }
+    }
+
+    fn request(results: Vec<ScenarioResult>) -> MarkdownReportRequest {
+        let failed = results.iter().filter(|r| !r.passed).count() as u32;
+        MarkdownReportRequest {
+            title: None,
+            prover: None,
+            prover_run_id: None,
+            batch: Some(BatchSimulationResult {
+                total: results.len() as u32,
+                passed: results.len() as u32 - failed,
+                failed,
+                skipped: 0,
+                max_gas_used: results.iter().map(|r| r.gas_used).max().unwrap_or(0),
+                gas_p50: 0,
+                gas_p95: 0,
+                results,
+                summary: "batch".to_string(),
+                run_id: None,
+            }),
+            batch_run_id: None,
+            gas: None,
+            gas_run_id: None,
+            baseline: Some(GasBaseline {
+                total_gas: None,
+                scenarios: [("deposit".to_string(), 100)].into_iter().collect(),
+            }),
+            max_suggestions: 5,
+        }
+    }
+
+    #[test]
+    fn test_render_batch_with_deltas() {
+        let report = render_markdown(&request(vec![
+            scenario("deposit", true, 110),
+            scenario("withdraw", false, 90),
+        ]));
+
+        assert!(!report.truncated);
+        assert!(report.markdown.starts_with("## Sentinel Report"));
+        assert!(report.markdown.contains("| Simulations | Failed | 1/2 scenarios passed, max gas 110 |"));
+        assert!(report.markdown.contains("<details open>"));
+        assert!(report.markdown.contains("| deposit | Pass | 110 | +10.0% | - |"));
+        assert!(report.markdown.contains("| withdraw | Fail | 90 |  | Expected success \\| got abort |"));
+    }
+
+    #[test]
+    fn test_render_fits_limit() {
+        let results = (0..200).map(|i| scenario(&format!("scenario_{}", i), i != 7, 1000)).collect();
+        let request = request(results);
+
+        let full = render(&request, Detail::Full).chars().count();
+        let report = render_within(&request, full - 1);
+        assert!(report.truncated);
+        assert!(report.length < full);
+        assert!(report.markdown.contains("scenario_7"));
+        assert!(!report.markdown.contains("scenario_8 "));
+
+        let tiny = render_within(&request, 300);
+        assert!(tiny.length <= 300);
+        assert!(tiny.markdown.ends_with(TRUNCATION_NOTE));
+    }
+}
@@ -0,0 +1,19 @@
+//! Standard report formats for CI consumers
+//!
+//! Prover, test, batch simulation and gas results can be rendered as SARIF
+//! 2.1.0 (code-scanning annotations) or JUnit XML (CI test tabs) in addition
+//! to the default JSON. Handlers take a `ReportFormat` extractor, which reads
+//! the `format` query parameter or the `Accept` header. Combined results can
+//! also be rendered as a Markdown pull request comment.
+
+mod format;
+mod junit;
+mod markdown;
+mod sarif;
+pub mod types;
+
+pub use format::ReportFormat;
+pub use junit::JunitReport;
+pub use markdown::render_markdown;
+pub use sarif::SarifReport;
+pub use types::*;
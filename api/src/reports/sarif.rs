@@ -0,0 +1,261 @@
+//! SARIF 2.1.0 serialization
+//!
+//! Each report is a single run of the `Sentinel` tool. Findings with a known
+//! source line get a physical location under `sources/`, everything else a
+//! logical location (module, function, scenario or test name).
+
+use serde_json::{json, Value};
+
+use crate::gas::GasProfile;
+use crate::move_test::{TestCaseStatus, TestResult};
+use crate::prover::{ProverResult, ProverStatus};
+use crate::simulation::BatchSimulationResult;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Types that can be rendered as a SARIF log
+pub trait SarifReport {
+    fn to_sarif(&self) -> Value;
+}
+
+/// (rule id, short description)
+type Rule = (&'static str, &'static str);
+
+const PROVER_RULES: &[Rule] = &[
+    ("prover/spec-failure", "Specification does not hold"),
+    ("prover/error", "Move Prover reported an error"),
+    ("prover/timeout", "Move Prover timed out"),
+];
+
+const TEST_RULES: &[Rule] = &[
+    ("test/failure", "Move unit test failed"),
+    ("test/timeout", "Move unit test timed out"),
+    ("test/build-error", "Package failed to build"),
+];
+
+const SIMULATION_RULES: &[Rule] = &[("simulation/scenario-failed", "Simulation scenario failed")];
+
+const GAS_RULES: &[Rule] = &[("gas/suggestion", "Gas optimization opportunity")];
+
+fn sarif_log(rules: &[Rule], results: Vec<Value>) -> Value {
+    let rules: Vec<Value> = rules
+        .iter()
+        .map(|(id, description)| {
+            json!({
+                "id": id,
+                "shortDescription": { "text": description },
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "Sentinel",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn sarif_result(rule_id: &str, level: &str, message: &str, location: Value) -> Value {
+    json!({
+        "ruleId": rule_id,
+        "level": level,
+        "message": { "text": message },
+        "locations": [location],
+    })
+}
+
+fn physical_location(uri: &str, line: Option<u32>, column: Option<u32>) -> Value {
+    let mut region = json!({ "startLine": line.unwrap_or(1).max(1) });
+    if let Some(column) = column.filter(|&c| c > 0) {
+        region["startColumn"] = json!(column);
+    }
+
+    json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": uri },
+            "region": region,
+        }
+    })
+}
+
+fn logical_location(name: &str, kind: &str) -> Value {
+    json!({
+        "logicalLocations": [{ "fullyQualifiedName": name, "kind": kind }]
+    })
+}
+
+impl SarifReport for ProverResult {
+    fn to_sarif(&self) -> Value {
+        let mut results = Vec::new();
+
+        for module in &self.modules {
+            let uri = format!("sources/{}.move", module.name);
+            for spec in module.specs.iter().filter(|s| s.status != ProverStatus::Passed) {
+                let (rule, level) = match spec.status {
+                    ProverStatus::Failed => ("prover/spec-failure", "error"),
+                    ProverStatus::Timeout => ("prover/timeout", "warning"),
+                    _ => ("prover/error", "error"),
+                };
+                let message = spec.message.clone().unwrap_or_else(|| spec.name.clone());
+                let line = spec.location.as_ref().and_then(|l| l.line);
+                results.push(sarif_result(rule, level, &message, physical_location(&uri, line, None)));
+            }
+        }
+
+        // Run-level failures without any attributed spec
+        if results.is_empty() && matches!(self.status, ProverStatus::Error | ProverStatus::Timeout) {
+            let rule = if self.status == ProverStatus::Timeout {
+                "prover/timeout"
+            } else {
+                "prover/error"
+            };
+            let module = self.modules.first().map(|m| m.name.as_str()).unwrap_or("package");
+            results.push(sarif_result(rule, "error", &self.summary, logical_location(module, "module")));
+        }
+
+        sarif_log(PROVER_RULES, results)
+    }
+}
+
+impl SarifReport for TestResult {
+    fn to_sarif(&self) -> Value {
+        let mut results: Vec<Value> = self
+            .diagnostics
+            .iter()
+            .map(|d| {
+                let location = match (&d.file, &d.span) {
+                    (Some(file), span) => physical_location(
+                        file,
+                        span.as_ref().map(|s| s.start_line),
+                        span.as_ref().map(|s| s.start_column),
+                    ),
+                    (None, _) => logical_location("package", "package"),
+                };
+                sarif_result("test/build-error", "error", &d.message, location)
+            })
+            .collect();
+
+        for test in self.tests.iter().filter(|t| t.status != TestCaseStatus::Passed) {
+            let rule = if test.status == TestCaseStatus::Timeout {
+                "test/timeout"
+            } else {
+                "test/failure"
+            };
+            let message = match &test.abort {
+                Some(abort) => format!("{} aborted with code {}", test.name, abort.code),
+                None => format!("{} failed", test.name),
+            };
+            results.push(sarif_result(rule, "error", &message, logical_location(&test.name, "function")));
+        }
+
+        sarif_log(TEST_RULES, results)
+    }
+}
+
+impl SarifReport for BatchSimulationResult {
+    fn to_sarif(&self) -> Value {
+        let results = self
+            .results
+            .iter()
+            .filter(|r| !r.passed && !r.skipped)
+            .flat_map(|r| {
+                // One result per failed assertion so each shows up on its own
+                let messages = if r.failures.is_empty() {
+                    vec![r
+                        .failure_reason
+                        .clone()
+                        .unwrap_or_else(|| format!("Scenario {} failed", r.name))]
+                } else {
+                    r.failures.clone()
+                };
+                messages.into_iter().map(|message| {
+                    sarif_result(
+                        "simulation/scenario-failed",
+                        "error",
+                        &message,
+                        logical_location(&r.name, "scenario"),
+                    )
+                })
+            })
+            .collect();
+
+        sarif_log(SIMULATION_RULES, results)
+    }
+}
+
+impl SarifReport for GasProfile {
+    fn to_sarif(&self) -> Value {
+        let results = self
+            .suggestions
+            .iter()
+            .map(|s| {
+                let level = match s.severity.as_str() {
+                    "critical" => "error",
+                    "warning" => "warning",
+                    _ => "note",
+                };
+                let location = logical_location(s.location.as_deref().unwrap_or("transaction"), "function");
+                sarif_result("gas/suggestion", level, &s.message, location)
+            })
+            .collect();
+
+        sarif_log(GAS_RULES, results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prover::{ModuleResult, SourceLocation, SpecResult};
+
+    #[test]
+    fn test_prover_result_to_sarif() {
+        let result = ProverResult {
+            status: ProverStatus::Failed,
+            duration_ms: 10,
+            modules: vec![ModuleResult {
+                name: "counter".to_string(),
+                status: ProverStatus::Failed,
+                specs: vec![SpecResult {
+                    name: "spec_1".to_string(),
+                    function: "increment".to_string(),
+                    status: ProverStatus::Failed,
+                    location: Some(SourceLocation {
+                        module: "counter".to_string(),
+                        function: "increment".to_string(),
+                        line: Some(15),
+                    }),
+                    counterexample: None,
+                    message: Some("post-condition does not hold".to_string()),
+                    spec_index: None,
+                }],
+                invariants: vec![],
+            }],
+            summary: "Module counter verification failed: 1 spec(s) failed".to_string(),
+            raw_output: None,
+            cached: false,
+            run_id: None,
+        };
+
+        let sarif = result.to_sarif();
+        assert_eq!(sarif["version"], "2.1.0");
+
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["ruleId"], "prover/spec-failure");
+        assert_eq!(results[0]["message"]["text"], "post-condition does not hold");
+
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "sources/counter.move");
+        assert_eq!(location["region"]["startLine"], 15);
+    }
+}
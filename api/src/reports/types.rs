@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::gas::GasProfile;
+use crate::prover::ProverResult;
+use crate::simulation::BatchSimulationResult;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarkdownReportRequest {
+    /// Heading of the comment; defaults to "Sentinel Report"
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub prover: Option<ProverResult>,
+    /// Stored prover run to load instead of passing `prover` inline
+    #[serde(default)]
+    pub prover_run_id: Option<Uuid>,
+    #[serde(default)]
+    pub batch: Option<BatchSimulationResult>,
+    #[serde(default)]
+    pub batch_run_id: Option<Uuid>,
+    #[serde(default)]
+    pub gas: Option<GasProfile>,
+    #[serde(default)]
+    pub gas_run_id: Option<Uuid>,
+    /// Gas figures from the base branch to compute deltas against
+    #[serde(default)]
+    pub baseline: Option<GasBaseline>,
+    /// Number of gas suggestions to include
+    #[serde(default = "default_max_suggestions")]
+    pub max_suggestions: usize,
+}
+
+fn default_max_suggestions() -> usize {
+    5
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GasBaseline {
+    /// Baseline for `GasProfile.total_gas`
+    #[serde(default)]
+    pub total_gas: Option<u64>,
+    /// Baseline gas per batch scenario name
+    #[serde(default)]
+    pub scenarios: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MarkdownReport {
+    pub markdown: String,
+    /// Length in characters, always within GitHub's comment limit
+    pub length: usize,
+    /// True when details were dropped to fit the limit
+    pub truncated: bool,
+}
@@ -1,21 +1,37 @@
 use axum::{
     extract::{Path, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     Json,
 };
 use chrono::{Duration, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::auth::generate_api_key;
+use crate::auth::{generate_api_key, ClientInfo, KeyScopes, SessionUser};
+use crate::db::{self, ApiKey, CreateApiKey, KeyEvent, KeyEventKind};
+use crate::error::ApiError;
 use crate::AppState;
 
+/// Keys a user can have working at once; a rotation may go one over
+/// while the replaced key is in its grace period
+const MAX_KEYS_PER_USER: i64 = 10;
+
+/// Longest lifetime a key can be given
+const MAX_KEY_LIFETIME_DAYS: i64 = 3650;
+
+/// How long a rotated key keeps working by default, and at most
+const DEFAULT_ROTATION_GRACE_HOURS: i64 = 24;
+const MAX_ROTATION_GRACE_HOURS: i64 = 30 * 24;
+
 #[derive(Debug, Deserialize)]
 pub struct CreateApiKeyRequest {
     pub name: String,
     #[serde(default)]
     pub expires_in_days: Option<i64>,
+    /// Omitted limits allow everything
+    #[serde(default)]
+    pub scopes: KeyScopes,
 }
 
 #[derive(Debug, Serialize)]
@@ -24,6 +40,7 @@ pub struct CreateApiKeyResponse {
     pub name: String,
     pub key: String, // Raw key - shown only once
     pub key_prefix: String,
+    pub scopes: KeyScopes,
     pub expires_at: Option<String>,
     pub created_at: String,
 }
@@ -33,8 +50,17 @@ pub struct ApiKeyInfo {
     pub id: String,
     pub name: String,
     pub key_prefix: String,
+    pub scopes: KeyScopes,
     pub last_used_at: Option<String>,
     pub expires_at: Option<String>,
+    pub revoked_at: Option<String>,
+    pub revoked_reason: Option<String>,
+    /// `active`, `expired` or `revoked`
+    pub status: &'static str,
+    /// The key that replaced this one, which stops working at `expires_at`
+    pub superseded_by: Option<String>,
+    /// The key this one replaced
+    pub replaces: Option<String>,
     pub created_at: String,
 }
 
@@ -43,16 +69,46 @@ pub struct ListApiKeysResponse {
     pub api_keys: Vec<ApiKeyInfo>,
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct RevokeApiKeyRequest {
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RotateApiKeyRequest {
+    /// How long the old key keeps working alongside the new one
+    #[serde(default)]
+    pub grace_period_hours: Option<i64>,
+    /// Defaults to the old key's lifetime, if it had one
+    #[serde(default)]
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RotateApiKeyResponse {
+    #[serde(flatten)]
+    pub api_key: CreateApiKeyResponse,
+    pub replaces: String,
+    /// When the replaced key stops working
+    pub replaced_key_expires_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListKeyEventsResponse {
+    pub events: Vec<KeyEvent>,
+}
+
 /// POST /api/v1/api-keys - Create a new API key
-/// Requires Clerk session authentication (user_id from header)
+/// Requires Clerk session authentication
 pub async fn create_api_key(
     State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+    client: ClientInfo,
     Json(request): Json<CreateApiKeyRequest>,
 ) -> impl IntoResponse {
     let pool = &state.db;
-    // In production, get user_id from Clerk JWT middleware
-    // For now, using a placeholder user_id header
-    let user_id = "user_placeholder"; // TODO: Extract from Clerk JWT
+    let user_id = user.id;
 
     // Validate name
     if request.name.trim().is_empty() {
@@ -63,16 +119,43 @@ pub async fn create_api_key(
             .into_response();
     }
 
-    // Check rate limit (max 10 keys per user)
-    let key_count: i64 = sqlx::query_scalar::<_, i64>(
-        r#"SELECT COUNT(*) FROM api_keys WHERE user_id = $1"#
-    )
-    .bind(user_id)
-    .fetch_one(pool)
-    .await
-    .unwrap_or(0);
+    if let Err(e) = request.scopes.validate(&state.networks) {
+        return e.into_response();
+    }
+    if let Some(project_id) = request.scopes.project_id {
+        match db::get_project_by_id(pool, project_id).await {
+            Ok(Some(project)) if project.user_id == user_id => {}
+            Ok(_) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({ "error": "Project not found" })),
+                )
+                    .into_response();
+            }
+            Err(e) => {
+                tracing::error!("Failed to load project {}: {}", project_id, e);
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({ "error": "Failed to create API key" })),
+                )
+                    .into_response();
+            }
+        }
+    }
 
-    if key_count >= 10 {
+    if let Some(response) = check_lifetime(request.expires_in_days) {
+        return response;
+    }
+
+    // Check rate limit (max 10 usable keys per user)
+    let key_count = match db::count_usable_api_keys(pool, user_id).await {
+        Ok(count) => count,
+        Err(e) => {
+            tracing::error!("Failed to count API keys: {}", e);
+            return ApiError::Internal("Failed to create API key".to_string()).into_response();
+        }
+    };
+    if key_count >= MAX_KEYS_PER_USER {
         return (
             StatusCode::TOO_MANY_REQUESTS,
             Json(serde_json::json!({ "error": "Maximum of 10 API keys allowed per user" })),
@@ -100,29 +183,38 @@ pub async fn create_api_key(
     let id = Uuid::new_v4();
     let now = Utc::now();
 
-    let result = sqlx::query(
-        r#"
-        INSERT INTO api_keys (id, user_id, name, key_hash, key_prefix, expires_at, created_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $7)
-        "#
+    let result = db::create_api_key(
+        pool,
+        &CreateApiKey {
+            id,
+            user_id,
+            name: request.name.trim(),
+            key_hash: &key_hash,
+            key_prefix: &key_prefix,
+            expires_at,
+            created_at: now,
+            allowed_endpoints: request.scopes.endpoint_names(),
+            allowed_networks: request.scopes.networks.as_deref(),
+            project_id: request.scopes.project_id,
+            access: request.scopes.access.as_str(),
+            replaces: None,
+        },
     )
-    .bind(id)
-    .bind(user_id)
-    .bind(request.name.trim())
-    .bind(&key_hash)
-    .bind(&key_prefix)
-    .bind(expires_at)
-    .bind(now)
-    .execute(pool)
     .await;
 
     match result {
         Ok(_) => {
+            let event = client.event(id, KeyEventKind::Created, None);
+            if let Err(e) = db::record_key_event(pool, event).await {
+                tracing::warn!("Failed to record created event for key {}: {}", id, e);
+            }
+
             let response = CreateApiKeyResponse {
                 id: id.to_string(),
                 name: request.name.trim().to_string(),
                 key: raw_key, // Show only once
                 key_prefix,
+                scopes: request.scopes,
                 expires_at: expires_at.map(|d| d.to_rfc3339()),
                 created_at: now.to_rfc3339(),
             };
@@ -139,34 +231,234 @@ pub async fn create_api_key(
     }
 }
 
-/// GET /api/v1/api-keys - List user's API keys
-pub async fn list_api_keys(State(state): State<AppState>) -> impl IntoResponse {
-    let pool = &state.db;
-    let user_id = "user_placeholder"; // TODO: Extract from Clerk JWT
-
-    let keys = sqlx::query_as::<_, ApiKeyRow>(
-        r#"
-        SELECT id, name, key_prefix, last_used_at, expires_at, created_at
-        FROM api_keys
-        WHERE user_id = $1
-        ORDER BY created_at DESC
-        "#
+/// A 400 response for an `expires_in_days` outside 1..=MAX_KEY_LIFETIME_DAYS
+fn check_lifetime(expires_in_days: Option<i64>) -> Option<Response> {
+    let days = expires_in_days?;
+    if (1..=MAX_KEY_LIFETIME_DAYS).contains(&days) {
+        return None;
+    }
+    Some(
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("expires_in_days must be between 1 and {}", MAX_KEY_LIFETIME_DAYS)
+            })),
+        )
+            .into_response(),
     )
-    .bind(user_id)
-    .fetch_all(pool)
+}
+
+/// POST /api/v1/api-keys/:id/rotate - Replace an API key without downtime
+///
+/// Issues a new key with the same name, scopes and project. The old key
+/// keeps working for `grace_period_hours` (default 24) and then expires;
+/// each key records the other. A rotation may take the user one key over
+/// the cap until the old key expires; further rotations wait for that.
+pub async fn rotate_api_key(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+    Path(id): Path<Uuid>,
+    client: ClientInfo,
+    request: Option<Json<RotateApiKeyRequest>>,
+) -> impl IntoResponse {
+    let pool = &state.db;
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+
+    let grace_hours = request.grace_period_hours.unwrap_or(DEFAULT_ROTATION_GRACE_HOURS);
+    if !(0..=MAX_ROTATION_GRACE_HOURS).contains(&grace_hours) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("grace_period_hours must be between 0 and {}", MAX_ROTATION_GRACE_HOURS)
+            })),
+        )
+            .into_response();
+    }
+    if let Some(response) = check_lifetime(request.expires_in_days) {
+        return response;
+    }
+
+    let old = match db::get_api_key_by_id(pool, id).await {
+        Ok(Some(key)) if key.user_id == Some(user.id) => key,
+        Ok(_) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": "API key not found" })),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to load API key {}: {}", id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": "Failed to rotate API key" })),
+            )
+                .into_response();
+        }
+    };
+    if old.superseded_by.is_some() {
+        return (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({ "error": "API key has already been rotated" })),
+        )
+            .into_response();
+    }
+    if old.revoked_at.is_some() || db::is_api_key_expired(&old) {
+        return (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({ "error": "Only active API keys can be rotated" })),
+        )
+            .into_response();
+    }
+
+    match db::count_usable_api_keys(pool, user.id).await {
+        Ok(count) if count > MAX_KEYS_PER_USER => {
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                Json(serde_json::json!({
+                    "error": "Too many API keys in use; revoke one or wait for a rotated key's grace period to end"
+                })),
+            )
+                .into_response();
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!("Failed to count API keys: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": "Failed to rotate API key" })),
+            )
+                .into_response();
+        }
+    }
+
+    let (raw_key, key_hash, key_prefix) = match generate_api_key() {
+        Ok(k) => k,
+        Err(e) => {
+            tracing::error!("Failed to generate API key: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": "Failed to generate API key" })),
+            )
+                .into_response();
+        }
+    };
+
+    let now = Utc::now();
+    let expires_at = match request.expires_in_days {
+        Some(days) => Some(now + Duration::days(days)),
+        // Same lifetime as the old key, within the usual bound
+        None => old.expires_at.map(|at| {
+            let lifetime = (at - old.created_at).min(Duration::days(MAX_KEY_LIFETIME_DAYS));
+            now + lifetime.max(Duration::days(1))
+        }),
+    };
+    // The old key never outlives its own expiry
+    let grace_ends = now + Duration::hours(grace_hours);
+    let old_expires_at = old.expires_at.map_or(grace_ends, |at| at.min(grace_ends));
+    let scopes = KeyScopes::from_key(&old);
+    let new_key = CreateApiKey {
+        id: Uuid::new_v4(),
+        user_id: user.id,
+        name: &old.name,
+        key_hash: &key_hash,
+        key_prefix: &key_prefix,
+        expires_at,
+        created_at: now,
+        allowed_endpoints: scopes.endpoint_names(),
+        allowed_networks: scopes.networks.as_deref(),
+        project_id: scopes.project_id,
+        access: scopes.access.as_str(),
+        replaces: Some(old.id),
+    };
+
+    let rotated = async {
+        let mut tx = pool.begin().await?;
+        db::create_api_key(&mut *tx, &new_key).await?;
+        // Concurrent rotations race here; the loser rolls back
+        if !db::supersede_api_key(&mut *tx, old.id, new_key.id, old_expires_at).await? {
+            return Ok(false);
+        }
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(true)
+    }
     .await;
 
+    match rotated {
+        Ok(false) => (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({ "error": "API key has already been rotated" })),
+        )
+            .into_response(),
+        Ok(true) => {
+            let events = [
+                client.event(
+                    old.id,
+                    KeyEventKind::Rotated,
+                    Some(format!("Superseded by {}; valid until {}", new_key.id, old_expires_at.to_rfc3339())),
+                ),
+                client.event(new_key.id, KeyEventKind::Created, Some(format!("Rotated from {}", old.id))),
+            ];
+            for event in events {
+                let key_id = event.api_key_id;
+                if let Err(e) = db::record_key_event(pool, event).await {
+                    tracing::warn!("Failed to record rotation event for key {}: {}", key_id, e);
+                }
+            }
+
+            let response = RotateApiKeyResponse {
+                api_key: CreateApiKeyResponse {
+                    id: new_key.id.to_string(),
+                    name: old.name.clone(),
+                    key: raw_key, // Show only once
+                    key_prefix,
+                    scopes,
+                    expires_at: expires_at.map(|d| d.to_rfc3339()),
+                    created_at: now.to_rfc3339(),
+                },
+                replaces: old.id.to_string(),
+                replaced_key_expires_at: old_expires_at.to_rfc3339(),
+            };
+            (StatusCode::CREATED, Json(serde_json::json!(response))).into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to rotate API key {}: {}", id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": "Failed to rotate API key" })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// GET /api/v1/api-keys - List user's API keys
+pub async fn list_api_keys(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> impl IntoResponse {
+    let pool = &state.db;
+    let user_id = user.id;
+
+    let keys = db::list_api_keys(pool, user_id).await;
+
     match keys {
-        Ok(rows) => {
-            let api_keys: Vec<ApiKeyInfo> = rows
+        Ok(keys) => {
+            let api_keys: Vec<ApiKeyInfo> = keys
                 .into_iter()
-                .map(|row| ApiKeyInfo {
-                    id: row.id.to_string(),
-                    name: row.name,
-                    key_prefix: row.key_prefix,
-                    last_used_at: row.last_used_at.map(|d| d.to_rfc3339()),
-                    expires_at: row.expires_at.map(|d| d.to_rfc3339()),
-                    created_at: row.created_at.to_rfc3339(),
+                .map(|key| ApiKeyInfo {
+                    id: key.id.to_string(),
+                    status: key_status(&key),
+                    scopes: KeyScopes::from_key(&key),
+                    name: key.name,
+                    key_prefix: key.key_prefix,
+                    last_used_at: key.last_used_at.map(|d| d.to_rfc3339()),
+                    expires_at: key.expires_at.map(|d| d.to_rfc3339()),
+                    revoked_at: key.revoked_at.map(|d| d.to_rfc3339()),
+                    revoked_reason: key.revoked_reason,
+                    superseded_by: key.superseded_by.map(|id| id.to_string()),
+                    replaces: key.replaces.map(|id| id.to_string()),
+                    created_at: key.created_at.to_rfc3339(),
                 })
                 .collect();
 
@@ -183,51 +475,497 @@ pub async fn list_api_keys(State(state): State<AppState>) -> impl IntoResponse {
     }
 }
 
-#[derive(sqlx::FromRow)]
-struct ApiKeyRow {
-    id: Uuid,
-    name: String,
-    key_prefix: String,
-    last_used_at: Option<chrono::DateTime<chrono::Utc>>,
-    expires_at: Option<chrono::DateTime<chrono::Utc>>,
-    created_at: chrono::DateTime<chrono::Utc>,
+fn key_status(key: &ApiKey) -> &'static str {
+    if key.revoked_at.is_some() {
+        "revoked"
+    } else if db::is_api_key_expired(key) {
+        "expired"
+    } else {
+        "active"
+    }
 }
 
 /// DELETE /api/v1/api-keys/:id - Revoke an API key
+///
+/// The key stops working immediately but is kept, with its history. An
+/// optional JSON body `{ "reason": "..." }` is stored with the revocation.
 pub async fn delete_api_key(
     State(state): State<AppState>,
+    SessionUser(user): SessionUser,
     Path(id): Path<Uuid>,
+    client: ClientInfo,
+    request: Option<Json<RevokeApiKeyRequest>>,
 ) -> impl IntoResponse {
     let pool = &state.db;
-    let user_id = "user_placeholder"; // TODO: Extract from Clerk JWT
+    let reason = request
+        .and_then(|Json(request)| request.reason)
+        .map(|reason| reason.trim().to_string())
+        .filter(|reason| !reason.is_empty());
 
-    let result = sqlx::query(
-        r#"DELETE FROM api_keys WHERE id = $1 AND user_id = $2"#
-    )
-    .bind(id)
-    .bind(user_id)
-    .execute(pool)
-    .await;
-
-    match result {
-        Ok(res) => {
-            if res.rows_affected() == 0 {
-                (
-                    StatusCode::NOT_FOUND,
-                    Json(serde_json::json!({ "error": "API key not found" })),
-                )
-                    .into_response()
-            } else {
-                Json(serde_json::json!({ "success": true })).into_response()
+    match db::revoke_api_key(pool, id, user.id, reason.as_deref()).await {
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": "API key not found" })),
+        )
+            .into_response(),
+        Ok(true) => {
+            let event = client.event(id, KeyEventKind::Revoked, reason);
+            if let Err(e) = db::record_key_event(pool, event).await {
+                tracing::warn!("Failed to record revoked event for key {}: {}", id, e);
             }
+            Json(serde_json::json!({ "success": true })).into_response()
         }
         Err(e) => {
-            tracing::error!("Failed to delete API key: {}", e);
+            tracing::error!("Failed to revoke API key: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "error": "Failed to delete API key" })),
+                Json(serde_json::json!({ "error": "Failed to revoke API key" })),
             )
                 .into_response()
         }
     }
 }
+
+/// GET /api/v1/api-keys/:id/events - A key's audit trail, newest first
+pub async fn list_api_key_events(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    let pool = &state.db;
+
+    let events = match db::get_api_key_by_id(pool, id).await {
+        Ok(Some(key)) if key.user_id == Some(user.id) => db::list_key_events(pool, id).await,
+        Ok(_) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": "API key not found" })),
+            )
+                .into_response()
+        }
+        Err(e) => Err(e),
+    };
+
+    match events {
+        Ok(events) => Json(ListKeyEventsResponse { events }).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to fetch API key events: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": "Failed to fetch API key events" })),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        extract::Request,
+        middleware::{self, Next},
+        routing::{delete, get, post},
+        Extension, Router,
+    };
+    use axum_test::TestServer;
+    use chrono::DateTime;
+
+    use crate::auth::{api_key_auth, client_info, require_scopes, AuthenticatedUser, TrustedProxies};
+    use crate::db::{self, CreateProject, CreateUsageEvent, CreateUser, DbPool, User};
+    use crate::routes::get_usage;
+
+    async fn whoami(Extension(user): Extension<AuthenticatedUser>) -> String {
+        user.user_id
+    }
+
+    #[derive(Deserialize)]
+    struct NetworkBody {
+        network: String,
+    }
+
+    async fn on_network(scopes: KeyScopes, Json(body): Json<NetworkBody>) -> Result<&'static str, ApiError> {
+        scopes.check_network(&body.network)?;
+        Ok("ok")
+    }
+
+    async fn test_user(pool: &DbPool) -> User {
+        db::upsert_user(
+            pool,
+            CreateUser {
+                clerk_id: format!("user_{}", Uuid::new_v4()),
+                email: "dev@sentinel.test".to_string(),
+                name: None,
+            },
+        )
+        .await
+        .unwrap()
+    }
+
+    /// The key management routes signed in as `user`, and a few routes
+    /// behind API key auth and scopes; one trusted proxy in front
+    fn test_app(pool: DbPool, user: User) -> Router {
+        let state = AppState::for_tests(pool);
+        // Stands in for session_auth, which is tested on its own
+        let session = SessionUser(user);
+        let sign_in = move |mut request: Request, next: Next| {
+            let session = session.clone();
+            async move {
+                request.extensions_mut().insert(session);
+                next.run(request).await
+            }
+        };
+        Router::new()
+            .route("/api-keys", post(create_api_key).get(list_api_keys))
+            .route("/api-keys/{id}", delete(delete_api_key))
+            .route("/api-keys/{id}/rotate", post(rotate_api_key))
+            .route("/api-keys/{id}/events", get(list_api_key_events))
+            .layer(middleware::from_fn(sign_in))
+            .merge(
+                Router::new()
+                    .route("/whoami", get(whoami))
+                    .route("/simulate", post(on_network))
+                    .route("/prove", post(|| async { "ok" }))
+                    .route("/usage", get(get_usage))
+                    .route("/cache/modules/{network}/{address}", delete(|| async { "ok" }))
+                    .route_layer(middleware::from_fn(require_scopes))
+                    .layer(middleware::from_fn_with_state(state.clone(), api_key_auth)),
+            )
+            .with_state(state)
+            .layer(middleware::from_fn_with_state(TrustedProxies(1), client_info))
+    }
+
+    #[tokio::test]
+    #[ignore = "needs Postgres; set TEST_DATABASE_URL and run with --ignored"]
+    async fn test_minted_key_authenticates() {
+        let pool = db::test_pool().await;
+        let user = test_user(&pool).await;
+        let server = TestServer::new(test_app(pool.clone(), user.clone())).unwrap();
+
+        let created = server
+            .post("/api-keys")
+            .json(&serde_json::json!({ "name": "ci" }))
+            .await;
+        created.assert_status(StatusCode::CREATED);
+        let created = created.json::<serde_json::Value>();
+        let key = created["key"].as_str().unwrap().to_string();
+        let id = created["id"].as_str().unwrap().to_string();
+
+        let response = server
+            .get("/whoami")
+            .authorization_bearer(&key)
+            // Only the hop appended by the trusted proxy counts
+            .add_header("X-Forwarded-For", "6.6.6.6, 203.0.113.7")
+            .await;
+        response.assert_status_ok();
+        assert_eq!(response.text(), user.id.to_string());
+
+        let last_used = || async {
+            server.get("/api-keys").await.json::<serde_json::Value>()["api_keys"][0]["last_used_at"].clone()
+        };
+        let first_used = last_used().await;
+        assert!(first_used.is_string());
+
+        // Calls within a minute leave last_used_at alone
+        let response = server.get("/whoami").add_header("X-API-Key", key.as_str()).await;
+        response.assert_status_ok();
+        assert_eq!(last_used().await, first_used);
+
+        // Same lookup prefix, different secret
+        let forged = format!("{}{}", &key[..17], "x".repeat(key.len() - 17));
+        server
+            .get("/whoami")
+            .authorization_bearer(forged)
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+
+        server
+            .delete(&format!("/api-keys/{}", id))
+            .json(&serde_json::json!({ "reason": "leaked in CI logs" }))
+            .await
+            .assert_status_ok();
+        let response = server.get("/whoami").authorization_bearer(&key).await;
+        response.assert_status(StatusCode::UNAUTHORIZED);
+        assert_eq!(response.json::<serde_json::Value>()["error"], "API key has been revoked");
+        // Already revoked
+        server
+            .delete(&format!("/api-keys/{}", id))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        let events = server
+            .get(&format!("/api-keys/{}/events", id))
+            .await
+            .json::<serde_json::Value>();
+        let events = events["events"].as_array().unwrap();
+        let kinds: Vec<_> = events.iter().map(|e| e["event"].as_str().unwrap()).collect();
+        assert_eq!(kinds, ["revoked", "used_from_new_ip", "created"]);
+        assert_eq!(events[0]["detail"], "leaked in CI logs");
+        assert_eq!(events[1]["ip_address"], "203.0.113.7");
+    }
+
+    #[tokio::test]
+    #[ignore = "needs Postgres; set TEST_DATABASE_URL and run with --ignored"]
+    async fn test_scoped_key_is_enforced() {
+        let pool = db::test_pool().await;
+        let user = test_user(&pool).await;
+        let server = TestServer::new(test_app(pool, user)).unwrap();
+
+        // Unknown networks are refused up front
+        server
+            .post("/api-keys")
+            .json(&serde_json::json!({ "name": "ci", "scopes": { "networks": ["devnet-42"] } }))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        let created = server
+            .post("/api-keys")
+            .json(&serde_json::json!({
+                "name": "ci",
+                "scopes": { "endpoints": ["simulate"], "networks": ["testnet"], "access": "read" }
+            }))
+            .await;
+        created.assert_status(StatusCode::CREATED);
+        let key = created.json::<serde_json::Value>()["key"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let simulate = |network: &str| {
+            server
+                .post("/simulate")
+                .authorization_bearer(&key)
+                .json(&serde_json::json!({ "network": network }))
+        };
+        simulate("testnet").await.assert_status_ok();
+        simulate("mainnet").await.assert_status(StatusCode::FORBIDDEN);
+
+        let response = server.post("/prove").authorization_bearer(&key).await;
+        response.assert_status(StatusCode::FORBIDDEN);
+        assert_eq!(
+            response.json::<serde_json::Value>()["error"],
+            "API key is not allowed to call prove endpoints"
+        );
+
+        server.get("/usage").authorization_bearer(&key).await.assert_status_ok();
+        server
+            .delete("/cache/modules/testnet/0x1")
+            .authorization_bearer(&key)
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+
+        let listed = server.get("/api-keys").await.json::<serde_json::Value>();
+        let scopes = &listed["api_keys"][0]["scopes"];
+        assert_eq!(scopes["endpoints"], serde_json::json!(["simulate"]));
+        assert_eq!(scopes["access"], "read");
+    }
+
+    #[tokio::test]
+    #[ignore = "needs Postgres; set TEST_DATABASE_URL and run with --ignored"]
+    async fn test_rotation_overlaps() {
+        let pool = db::test_pool().await;
+        let user = test_user(&pool).await;
+        let server = TestServer::new(test_app(pool, user)).unwrap();
+
+        let created = server
+            .post("/api-keys")
+            .json(&serde_json::json!({
+                "name": "deploy",
+                "expires_in_days": 90,
+                "scopes": { "endpoints": ["simulate"], "networks": ["testnet"] }
+            }))
+            .await
+            .json::<serde_json::Value>();
+        let old_id = created["id"].as_str().unwrap().to_string();
+        let old_key = created["key"].as_str().unwrap().to_string();
+
+        let rotated = server
+            .post(&format!("/api-keys/{}/rotate", old_id))
+            .json(&serde_json::json!({ "grace_period_hours": 1 }))
+            .await;
+        rotated.assert_status(StatusCode::CREATED);
+        let rotated = rotated.json::<serde_json::Value>();
+        let new_id = rotated["id"].as_str().unwrap().to_string();
+        let new_key = rotated["key"].as_str().unwrap().to_string();
+        assert_eq!(rotated["name"], "deploy");
+        assert_eq!(rotated["replaces"], old_id.as_str());
+        assert_eq!(rotated["scopes"], created["scopes"]);
+        // Same 90-day lifetime, counted from the rotation
+        let expires_at: DateTime<Utc> = rotated["expires_at"].as_str().unwrap().parse().unwrap();
+        assert!(expires_at - Utc::now() > Duration::days(89));
+
+        // Both keys work during the grace period
+        for key in [&old_key, &new_key] {
+            server.get("/whoami").authorization_bearer(key).await.assert_status_ok();
+        }
+        server
+            .post(&format!("/api-keys/{}/rotate", old_id))
+            .await
+            .assert_status(StatusCode::CONFLICT);
+
+        let listed = server.get("/api-keys").await.json::<serde_json::Value>();
+        let find = |id: &str| {
+            listed["api_keys"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|key| key["id"] == id)
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(find(&old_id)["superseded_by"], new_id.as_str());
+        assert_eq!(find(&new_id)["replaces"], old_id.as_str());
+
+        // No grace period: the replaced key stops working at once
+        let third = server
+            .post(&format!("/api-keys/{}/rotate", new_id))
+            .json(&serde_json::json!({ "grace_period_hours": 0 }))
+            .await
+            .json::<serde_json::Value>();
+        let response = server.get("/whoami").authorization_bearer(&new_key).await;
+        response.assert_status(StatusCode::UNAUTHORIZED);
+        assert_eq!(response.json::<serde_json::Value>()["error"], "API key has expired");
+        server
+            .get("/whoami")
+            .authorization_bearer(third["key"].as_str().unwrap())
+            .await
+            .assert_status_ok();
+
+        let events = server
+            .get(&format!("/api-keys/{}/events", new_id))
+            .await
+            .json::<serde_json::Value>();
+        let kinds: Vec<_> = events["events"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["event"].as_str().unwrap())
+            .collect();
+        assert_eq!(kinds, ["expired", "rotated", "created"]);
+
+        let third_id = third["id"].as_str().unwrap().to_string();
+        for days in [0, -1, 100_000_000] {
+            server
+                .post(&format!("/api-keys/{}/rotate", third_id))
+                .json(&serde_json::json!({ "expires_in_days": days }))
+                .await
+                .assert_status(StatusCode::BAD_REQUEST);
+        }
+
+        // The first key is still in its grace period and counts towards the
+        // cap, so 8 more keys reach it
+        for _ in 0..8 {
+            server
+                .post("/api-keys")
+                .json(&serde_json::json!({ "name": "filler" }))
+                .await
+                .assert_status(StatusCode::CREATED);
+        }
+        server
+            .post("/api-keys")
+            .json(&serde_json::json!({ "name": "one too many" }))
+            .await
+            .assert_status(StatusCode::TOO_MANY_REQUESTS);
+
+        // One rotation may overlap at the cap, but not a chain of them
+        let fourth = server
+            .post(&format!("/api-keys/{}/rotate", third_id))
+            .await;
+        fourth.assert_status(StatusCode::CREATED);
+        let fourth_id = fourth.json::<serde_json::Value>()["id"].as_str().unwrap().to_string();
+        server
+            .post(&format!("/api-keys/{}/rotate", fourth_id))
+            .await
+            .assert_status(StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs Postgres; set TEST_DATABASE_URL and run with --ignored"]
+    async fn test_project_key_is_bound_to_its_project() {
+        let pool = db::test_pool().await;
+        let user = test_user(&pool).await;
+        let project = db::create_project(
+            &pool,
+            user.id,
+            CreateProject {
+                name: "vault".to_string(),
+                description: None,
+                network: None,
+            },
+        )
+        .await
+        .unwrap();
+        let server = TestServer::new(test_app(pool.clone(), user.clone())).unwrap();
+
+        let mut keys = Vec::new();
+        for scopes in [serde_json::json!({ "project_id": project.id }), serde_json::json!({})] {
+            let created = server
+                .post("/api-keys")
+                .json(&serde_json::json!({ "name": "ci", "scopes": scopes }))
+                .await;
+            created.assert_status(StatusCode::CREATED);
+            let created = created.json::<serde_json::Value>();
+            let id: Uuid = created["id"].as_str().unwrap().parse().unwrap();
+            db::insert_usage_event(
+                &pool,
+                &CreateUsageEvent {
+                    user_id: user.id.to_string(),
+                    api_key_id: id,
+                    endpoint: "/api/v1/simulate".to_string(),
+                    network: Some("testnet".to_string()),
+                    units: 1,
+                    gas_used: None,
+                    prover_cpu_secs: None,
+                    status: 200,
+                    latency_ms: 10,
+                },
+            )
+            .await
+            .unwrap();
+            keys.push((id, created["key"].as_str().unwrap().to_string()));
+        }
+        let (project_key_id, project_key) = &keys[0];
+        let usage_keys = |report: serde_json::Value| -> Vec<String> {
+            report["keys"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|key| key["api_key_id"].as_str().unwrap().to_string())
+                .collect()
+        };
+
+        // The project's key only sees the project's usage
+        let report = server
+            .get("/usage")
+            .authorization_bearer(project_key)
+            .await
+            .json::<serde_json::Value>();
+        assert_eq!(usage_keys(report.clone()), [project_key_id.to_string()]);
+        assert_eq!(report["totals"]["calls"], 1);
+
+        let report = server
+            .get("/usage")
+            .authorization_bearer(&keys[1].1)
+            .await
+            .json::<serde_json::Value>();
+        assert_eq!(usage_keys(report).len(), 2);
+
+        // Deleting the project revokes its key rather than unbinding it
+        assert!(db::delete_project(&pool, project.id, user.id).await.unwrap());
+        let response = server.get("/usage").authorization_bearer(project_key).await;
+        response.assert_status(StatusCode::UNAUTHORIZED);
+        assert_eq!(response.json::<serde_json::Value>()["error"], "API key has been revoked");
+        server
+            .get("/usage")
+            .authorization_bearer(&keys[1].1)
+            .await
+            .assert_status_ok();
+
+        let events = server
+            .get(&format!("/api-keys/{}/events", project_key_id))
+            .await
+            .json::<serde_json::Value>();
+        assert_eq!(events["events"][0]["event"], "revoked");
+        assert_eq!(events["events"][0]["detail"], "project deleted");
+    }
+}
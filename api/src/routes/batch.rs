@@ -1,20 +1,37 @@
-use axum::{extract::State, Json};
+use axum::{extract::State, response::Response, Extension, Json};
 
+use super::reports::store_run;
+use crate::auth::{AuthenticatedUser, KeyScopes};
+use crate::db::RunKind;
 use crate::error::ApiError;
-use crate::simulation::{BatchSimulationRequest, BatchSimulationResult};
+use crate::progress::{sse_response, Progress};
+use crate::reports::ReportFormat;
+use crate::simulation::BatchSimulationRequest;
+use crate::usage::UsageMeter;
 use crate::AppState;
 
 pub async fn simulate_batch(
     State(state): State<AppState>,
+    meter: UsageMeter,
+    scopes: KeyScopes,
+    user: Option<Extension<AuthenticatedUser>>,
+    format: ReportFormat,
     Json(request): Json<BatchSimulationRequest>,
-) -> Result<Json<BatchSimulationResult>, ApiError> {
+) -> Result<Response, ApiError> {
     tracing::info!(
         "Running batch simulation: {} scenarios on {}",
         request.scenarios.len(),
         request.network
     );
 
-    let result = state.simulation.execute_batch(request).await?;
+    state.networks.check(request.networks())?;
+    scopes.check_networks(request.networks())?;
+    meter.set_network(&request.network);
+    let mut result = state.simulation.execute_batch(request, &Progress::none()).await?;
+
+    meter.add_gas(result.total_gas_used());
+    let user = user.map(|Extension(user)| user);
+    result.run_id = store_run(&state, user.as_ref(), RunKind::Batch, &result).await;
 
     tracing::info!(
         "Batch simulation completed: {}/{} passed, max_gas={}",
@@ -23,5 +40,38 @@ pub async fn simulate_batch(
         result.max_gas_used
     );
 
-    Ok(Json(result))
+    Ok(format.respond(&result))
+}
+
+/// Same as `simulate_batch`, streaming each scenario result as SSE
+pub async fn simulate_batch_stream(
+    State(state): State<AppState>,
+    meter: UsageMeter,
+    scopes: KeyScopes,
+    user: Option<Extension<AuthenticatedUser>>,
+    Json(request): Json<BatchSimulationRequest>,
+) -> Result<Response, ApiError> {
+    state.networks.check(request.networks())?;
+    scopes.check_networks(request.networks())?;
+    meter.set_network(&request.network);
+
+    tracing::info!(
+        "Streaming batch simulation: {} scenarios on {}",
+        request.scenarios.len(),
+        request.network
+    );
+
+    let user = user.map(|Extension(user)| user);
+    let (progress, events) = Progress::channel();
+
+    tokio::spawn(async move {
+        let mut result = state.simulation.execute_batch(request, &progress).await;
+        if let Ok(result) = &mut result {
+            meter.add_gas(result.total_gas_used());
+            result.run_id = store_run(&state, user.as_ref(), RunKind::Batch, &*result).await;
+        }
+        progress.finish(result);
+    });
+
+    Ok(sse_response(events))
 }
@@ -0,0 +1,30 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde_json::{json, Value};
+
+use crate::auth::KeyScopes;
+use crate::cache::invalidate_modules;
+use crate::error::ApiError;
+use crate::simulation::expectations::normalize_address;
+use crate::AppState;
+
+/// Drops cached module ABIs for an address; call after republishing a package
+pub async fn invalidate_module_cache(
+    State(state): State<AppState>,
+    scopes: KeyScopes,
+    Path((network, address)): Path<(String, String)>,
+) -> Result<Json<Value>, ApiError> {
+    state.networks.get(&network)?;
+    scopes.check_network(&network)?;
+    let address = normalize_address(&address);
+
+    let invalidated = invalidate_modules(state.cache.as_ref(), &network, &address)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to invalidate module cache: {}", e)))?;
+
+    tracing::info!("Invalidated {} cached modules for {} on {}", invalidated, address, network);
+
+    Ok(Json(json!({ "invalidated": invalidated })))
+}
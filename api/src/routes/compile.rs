@@ -0,0 +1,15 @@
+use axum::Json;
+
+use crate::compiler::{CompileExecutor, CompileRequest, CompileResult};
+use crate::error::ApiError;
+
+pub async fn compile_package(
+    Json(request): Json<CompileRequest>,
+) -> Result<Json<CompileResult>, ApiError> {
+    tracing::info!("Compiling module: {}", request.module_name);
+
+    let executor = CompileExecutor::new();
+    let result = executor.execute(request).await?;
+
+    Ok(Json(result))
+}
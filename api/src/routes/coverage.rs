@@ -0,0 +1,30 @@
+use axum::{extract::State, Json};
+
+use crate::cache::hash_prover_request;
+use crate::error::ApiError;
+use crate::prover::{analyze_spec_coverage, SpecCoverageReport, SpecCoverageRequest};
+use crate::AppState;
+
+pub async fn spec_coverage(
+    State(state): State<AppState>,
+    Json(request): Json<SpecCoverageRequest>,
+) -> Result<Json<SpecCoverageReport>, ApiError> {
+    tracing::info!("Analyzing spec coverage for module: {}", request.prover.module_name);
+
+    // Fall back to the last cached prover run for the same request
+    let prover_result = match request.prover_result {
+        Some(result) => Some(result),
+        None => {
+            state
+                .prover_cache
+                .get(&hash_prover_request(&request.prover))
+                .await
+        }
+    };
+
+    let report = analyze_spec_coverage(&request.prover, prover_result.as_ref())?;
+
+    tracing::info!("Spec coverage completed: {}", report.summary);
+
+    Ok(Json(report))
+}
@@ -1,13 +1,22 @@
-use axum::{extract::State, Json};
+use axum::{extract::State, response::Response, Extension, Json};
 
+use super::reports::store_run;
+use crate::auth::{AuthenticatedUser, KeyScopes};
+use crate::db::RunKind;
 use crate::error::ApiError;
-use crate::gas::{GasAnalysisRequest, GasProfile};
+use crate::gas::GasAnalysisRequest;
+use crate::reports::ReportFormat;
+use crate::usage::UsageMeter;
 use crate::AppState;
 
 pub async fn analyze_gas(
     State(state): State<AppState>,
+    meter: UsageMeter,
+    scopes: KeyScopes,
+    user: Option<Extension<AuthenticatedUser>>,
+    format: ReportFormat,
     Json(request): Json<GasAnalysisRequest>,
-) -> Result<Json<GasProfile>, ApiError> {
+) -> Result<Response, ApiError> {
     tracing::info!(
         "Analyzing gas for: {}::{}::{}",
         request.module_address,
@@ -15,7 +24,14 @@ pub async fn analyze_gas(
         request.function_name
     );
 
-    let profile = state.gas_analyzer.analyze(request).await?;
+    state.networks.get(&request.network)?;
+    scopes.check_network(&request.network)?;
+    meter.set_network(&request.network);
+    let mut profile = state.gas_analyzer.analyze(request).await?;
+
+    meter.add_gas(profile.total_gas);
+    let user = user.map(|Extension(user)| user);
+    profile.run_id = store_run(&state, user.as_ref(), RunKind::Gas, &profile).await;
 
     tracing::info!(
         "Gas analysis completed: total_gas={}, suggestions={}",
@@ -23,5 +39,5 @@ pub async fn analyze_gas(
         profile.suggestions.len()
     );
 
-    Ok(Json(profile))
+    Ok(format.respond(&profile))
 }
@@ -5,6 +5,8 @@ use serde_json::{json, Value};
 use sqlx::Row;
 use std::time::Instant;
 
+use crate::cache::CacheBackend;
+use crate::movement::CircuitState;
 use crate::AppState;
 
 static START_TIME: std::sync::OnceLock<Instant> = std::sync::OnceLock::new();
@@ -39,24 +41,32 @@ pub async fn health_check(State(state): State<AppState>) -> Json<Value> {
         Err(e) => format!("error: {}", e),
     };
 
-    // Check Redis connection
-    let redis_status = match &state.redis {
-        Some(pool) => {
-            let mut conn = pool.lock().await;
-            match redis::cmd("PING")
-                .query_async::<String>(&mut *conn)
-                .await
-            {
-                Ok(_) => "connected".to_string(),
-                Err(e) => format!("error: {}", e),
-            }
-        }
-        None => "disabled".to_string(),
+    // Check the cache; a failed ping switches it to the in-memory fallback
+    let cache_ping = state.cache.ping().await;
+    let redis_status = match (state.cache.status(), cache_ping) {
+        ("redis", Ok(())) => "connected".to_string(),
+        (status, Ok(())) => status.to_string(),
+        (_, Err(e)) => format!("error: {}", e),
     };
 
+    // A network with every endpoint's circuit open can't serve requests
+    let rpc = state.rpc_health.snapshot();
+    let rpc_down = rpc
+        .iter()
+        .map(|e| e.network.as_str())
+        .any(|network| {
+            rpc.iter()
+                .filter(|e| e.network == network)
+                .all(|e| e.circuit == CircuitState::Open)
+        });
+
     // Determine overall status
     let is_healthy = db_status == "connected";
-    let status = if is_healthy { "healthy" } else { "unhealthy" };
+    let status = match (is_healthy, rpc_down) {
+        (false, _) => "unhealthy",
+        (true, true) => "degraded",
+        (true, false) => "healthy",
+    };
 
     Json(json!({
         "status": status,
@@ -64,7 +74,8 @@ pub async fn health_check(State(state): State<AppState>) -> Json<Value> {
         "uptime_seconds": uptime,
         "dependencies": {
             "database": db_status,
-            "redis": redis_status
+            "redis": redis_status,
+            "rpc": rpc
         }
     }))
 }
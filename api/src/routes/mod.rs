@@ -1,15 +1,31 @@
 pub mod api_keys;
 pub mod batch;
+pub mod cache;
+pub mod compile;
+pub mod coverage;
 pub mod gas;
 pub mod health;
+pub mod networks;
 pub mod prover;
+pub mod reports;
+pub mod scenarios;
 pub mod simulate;
+pub mod test;
 pub mod trace;
+pub mod usage;
 
-pub use api_keys::{create_api_key, delete_api_key, list_api_keys};
-pub use batch::simulate_batch;
+pub use api_keys::{create_api_key, delete_api_key, list_api_key_events, list_api_keys, rotate_api_key};
+pub use batch::{simulate_batch, simulate_batch_stream};
+pub use cache::invalidate_module_cache;
+pub use compile::compile_package;
+pub use coverage::spec_coverage;
 pub use gas::analyze_gas;
 pub use health::{health_check, liveness, readiness};
-pub use prover::run_prover;
+pub use networks::list_networks;
+pub use prover::{run_prover, run_prover_stream};
+pub use reports::markdown_report;
+pub use scenarios::run_scenario_file;
 pub use simulate::simulate_transaction;
+pub use test::{run_tests, run_tests_stream};
 pub use trace::get_trace;
+pub use usage::get_usage;
@@ -0,0 +1,39 @@
+use axum::{extract::State, Json};
+use serde::Serialize;
+
+use crate::movement::EndpointStatus;
+use crate::network::NetworkFeatures;
+use crate::AppState;
+
+#[derive(Serialize)]
+pub struct NetworkInfo {
+    pub name: String,
+    pub chain_id: Option<u8>,
+    pub faucet_url: Option<String>,
+    pub features: NetworkFeatures,
+    /// Endpoint URLs and health; auth headers are never exposed
+    pub endpoints: Vec<EndpointStatus>,
+}
+
+/// Networks requests can target
+pub async fn list_networks(State(state): State<AppState>) -> Json<Vec<NetworkInfo>> {
+    let health = state.rpc_health.snapshot();
+
+    Json(
+        state
+            .networks
+            .iter()
+            .map(|network| NetworkInfo {
+                name: network.name.clone(),
+                chain_id: network.chain_id,
+                faucet_url: network.faucet_url.clone(),
+                features: network.features,
+                endpoints: health
+                    .iter()
+                    .filter(|e| e.network == network.name)
+                    .cloned()
+                    .collect(),
+            })
+            .collect(),
+    )
+}
@@ -1,15 +1,76 @@
-use axum::Json;
+use axum::{extract::State, response::Response, Extension, Json};
 
+use super::reports::store_run;
+use crate::auth::AuthenticatedUser;
+
+use crate::cache::hash_prover_request;
+use crate::db::RunKind;
 use crate::error::ApiError;
-use crate::prover::{ProverExecutor, ProverRequest, ProverResult};
+use crate::progress::{sse_response, Progress};
+use crate::prover::{ProverExecutor, ProverRequest, ProverResult, ProverStatus};
+use crate::reports::ReportFormat;
+use crate::usage::UsageMeter;
+use crate::AppState;
 
 pub async fn run_prover(
+    State(state): State<AppState>,
+    meter: UsageMeter,
+    user: Option<Extension<AuthenticatedUser>>,
+    format: ReportFormat,
     Json(request): Json<ProverRequest>,
-) -> Result<Json<ProverResult>, ApiError> {
+) -> Result<Response, ApiError> {
+    let user = user.map(|Extension(user)| user);
+    let result = prove(&state, request, &Progress::none(), &meter, user.as_ref()).await?;
+    Ok(format.respond(&result))
+}
+
+/// Same as `run_prover`, streaming phase changes and the result as SSE
+pub async fn run_prover_stream(
+    State(state): State<AppState>,
+    meter: UsageMeter,
+    user: Option<Extension<AuthenticatedUser>>,
+    Json(request): Json<ProverRequest>,
+) -> Response {
+    let user = user.map(|Extension(user)| user);
+    let (progress, events) = Progress::channel();
+
+    tokio::spawn(async move {
+        let result = prove(&state, request, &progress, &meter, user.as_ref()).await;
+        progress.finish(result);
+    });
+
+    sse_response(events)
+}
+
+async fn prove(
+    state: &AppState,
+    request: ProverRequest,
+    progress: &Progress,
+    meter: &UsageMeter,
+    user: Option<&AuthenticatedUser>,
+) -> Result<ProverResult, ApiError> {
     tracing::info!("Running prover for module: {}", request.module_name);
 
-    let executor = ProverExecutor::new();
-    let result = executor.execute(request).await?;
+    let request_hash = hash_prover_request(&request);
+    let mut result = match state.prover_cache.get(&request_hash).await {
+        Some(mut cached) => {
+            tracing::info!("Prover cache hit for module: {}", request.module_name);
+            cached.cached = true;
+            cached
+        }
+        None => {
+            let executor = ProverExecutor::new();
+            let result = executor.execute(request, progress).await?;
+            meter.add_prover_time(result.duration_ms);
+
+            // Only cache definitive verdicts; timeouts and errors may be transient
+            if matches!(result.status, ProverStatus::Passed | ProverStatus::Failed) {
+                state.prover_cache.put(&request_hash, &result).await;
+            }
+            result
+        }
+    };
 
-    Ok(Json(result))
+    result.run_id = store_run(state, user, RunKind::Prover, &result).await;
+    Ok(result)
 }
@@ -0,0 +1,224 @@
+use axum::{extract::State, Extension, Json};
+use serde::{de::DeserializeOwned, Serialize};
+use uuid::Uuid;
+
+use crate::auth::AuthenticatedUser;
+use crate::db::{self, CreateRun, RunKind};
+use crate::error::ApiError;
+use crate::reports::{render_markdown, MarkdownReport, MarkdownReportRequest};
+use crate::AppState;
+
+/// POST /api/v1/reports/markdown - Render results as a PR comment
+///
+/// Each of prover, batch and gas is passed inline as returned by its
+/// endpoint, or as the `run_id` that endpoint returned. Runs are only found
+/// for the user who made them, and a key bound to a project only finds that
+/// project's runs.
+pub async fn markdown_report(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(mut request): Json<MarkdownReportRequest>,
+) -> Result<Json<MarkdownReport>, ApiError> {
+    if let Some(id) = request.prover_run_id {
+        request.prover = Some(load_run(&state, &user, id, RunKind::Prover, request.prover.is_some()).await?);
+    }
+    if let Some(id) = request.batch_run_id {
+        request.batch = Some(load_run(&state, &user, id, RunKind::Batch, request.batch.is_some()).await?);
+    }
+    if let Some(id) = request.gas_run_id {
+        request.gas = Some(load_run(&state, &user, id, RunKind::Gas, request.gas.is_some()).await?);
+    }
+
+    if request.prover.is_none() && request.batch.is_none() && request.gas.is_none() {
+        return Err(ApiError::BadRequest(
+            "At least one of prover, batch or gas (or its run id) is required".to_string(),
+        ));
+    }
+
+    tracing::info!("Rendering markdown report");
+
+    let report = render_markdown(&request);
+    if report.truncated {
+        tracing::info!("Markdown report truncated to {} characters", report.length);
+    }
+
+    Ok(Json(report))
+}
+
+/// Loads a stored run referenced by `{kind}_run_id`
+async fn load_run<T: DeserializeOwned>(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    id: Uuid,
+    kind: RunKind,
+    inline: bool,
+) -> Result<T, ApiError> {
+    let name = kind.as_str();
+    if inline {
+        return Err(ApiError::BadRequest(format!(
+            "Provide either {} or {}_run_id, not both",
+            name, name
+        )));
+    }
+
+    // Another user's run is reported as missing
+    let result = db::get_run_result(&state.db, id, kind, &user.user_id, user.scopes.project_id)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to load {} run: {}", name, e)))?
+        .ok_or_else(|| ApiError::NotFound(format!("{} run {} not found", name, id)))?;
+
+    serde_json::from_value(result)
+        .map_err(|e| ApiError::Internal(format!("Stored {} run {} is unreadable: {}", name, id, e)))
+}
+
+/// Stores a result for `{kind}_run_id` and returns its id
+///
+/// Best effort: the call that produced the result still succeeds without a
+/// run id if the insert fails. Nothing is stored without an API key.
+pub(crate) async fn store_run<T: Serialize>(
+    state: &AppState,
+    user: Option<&AuthenticatedUser>,
+    kind: RunKind,
+    result: &T,
+) -> Option<Uuid> {
+    let user = user?;
+    let result = serde_json::to_value(result).ok()?;
+    let input = CreateRun {
+        user_id: &user.user_id,
+        project_id: user.scopes.project_id,
+        kind,
+        result,
+    };
+
+    match db::create_run(&state.db, input).await {
+        Ok(id) => Some(id),
+        Err(e) => {
+            tracing::warn!("Failed to store {} run for {}: {}", kind.as_str(), user.user_id, e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::post, Router};
+    use axum_test::TestServer;
+    use serde_json::json;
+
+    use crate::auth::KeyScopes;
+    use crate::db::{CreateProject, CreateUser, DbPool};
+    use crate::prover::{ProverResult, ProverStatus};
+
+    fn test_app(pool: DbPool, user: AuthenticatedUser) -> Router {
+        Router::new()
+            .route("/reports/markdown", post(markdown_report))
+            .layer(Extension(user))
+            .with_state(AppState::for_tests(pool))
+    }
+
+    fn caller(user_id: &str, project_id: Option<Uuid>) -> AuthenticatedUser {
+        AuthenticatedUser {
+            user_id: user_id.to_string(),
+            api_key_id: Uuid::new_v4(),
+            scopes: KeyScopes {
+                project_id,
+                ..KeyScopes::default()
+            },
+        }
+    }
+
+    fn prover_result(summary: &str) -> ProverResult {
+        ProverResult {
+            status: ProverStatus::Passed,
+            duration_ms: 10,
+            modules: vec![],
+            summary: summary.to_string(),
+            raw_output: None,
+            cached: false,
+            run_id: None,
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs Postgres; set TEST_DATABASE_URL and run with --ignored"]
+    async fn test_report_from_stored_runs() {
+        let pool = db::test_pool().await;
+        let state = AppState::for_tests(pool.clone());
+        let owner = db::upsert_user(
+            &pool,
+            CreateUser {
+                clerk_id: format!("user_{}", Uuid::new_v4()),
+                email: "dev@sentinel.test".to_string(),
+                name: None,
+            },
+        )
+        .await
+        .unwrap();
+        let project = db::create_project(
+            &pool,
+            owner.id,
+            CreateProject {
+                name: "vault".to_string(),
+                description: None,
+                network: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let user_id = owner.id.to_string();
+        let unbound = caller(&user_id, None);
+        let bound = caller(&user_id, Some(project.id));
+        let account_run = store_run(&state, Some(&unbound), RunKind::Prover, &prover_result("account run"))
+            .await
+            .unwrap();
+        let project_run = store_run(&state, Some(&bound), RunKind::Prover, &prover_result("project run"))
+            .await
+            .unwrap();
+        assert!(store_run(&state, None, RunKind::Prover, &prover_result("anonymous")).await.is_none());
+
+        let server = TestServer::new(test_app(pool.clone(), unbound)).unwrap();
+        let report: serde_json::Value = server
+            .post("/reports/markdown")
+            .json(&json!({ "prover_run_id": account_run }))
+            .await
+            .json();
+        assert!(report["markdown"].as_str().unwrap().contains("account run"));
+
+        // The run id names a prover run, not a batch run
+        server
+            .post("/reports/markdown")
+            .json(&json!({ "batch_run_id": account_run }))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        server
+            .post("/reports/markdown")
+            .json(&json!({ "prover_run_id": account_run, "prover": prover_result("inline") }))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        // Another user's runs are not found
+        let server = TestServer::new(test_app(pool.clone(), caller("0xwallet", None))).unwrap();
+        server
+            .post("/reports/markdown")
+            .json(&json!({ "prover_run_id": account_run }))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        // A project key only finds its project's runs
+        let server = TestServer::new(test_app(pool, bound)).unwrap();
+        server
+            .post("/reports/markdown")
+            .json(&json!({ "prover_run_id": account_run }))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        let report: serde_json::Value = server
+            .post("/reports/markdown")
+            .json(&json!({ "prover_run_id": project_run }))
+            .await
+            .json();
+        assert!(report["markdown"].as_str().unwrap().contains("project run"));
+    }
+}
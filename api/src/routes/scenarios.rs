@@ -0,0 +1,72 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, response::Response, Extension, Json};
+
+use super::reports::store_run;
+use crate::auth::{AuthenticatedUser, KeyScopes};
+use crate::db::RunKind;
+use crate::error::ApiError;
+use crate::progress::Progress;
+use crate::scenario_file::{
+    parse_scenario_file, resolve_scenario_file, ScenarioFileRequest, ScenarioFileResult,
+};
+use crate::usage::UsageMeter;
+use crate::AppState;
+
+pub async fn run_scenario_file(
+    State(state): State<AppState>,
+    meter: UsageMeter,
+    scopes: KeyScopes,
+    user: Option<Extension<AuthenticatedUser>>,
+    Json(request): Json<ScenarioFileRequest>,
+) -> Result<Response, ApiError> {
+    let resolved = parse_scenario_file(&request.content, request.format)
+        .map_err(|e| vec![e])
+        .and_then(|file| resolve_scenario_file(&request.content, &file));
+
+    let batch = match resolved {
+        Ok(batch) => batch,
+        Err(errors) => {
+            tracing::info!("Rejected scenario file with {} errors", errors.len());
+            let result = ScenarioFileResult {
+                valid: false,
+                errors,
+                scenarios: 0,
+                result: None,
+            };
+            return Ok((StatusCode::BAD_REQUEST, Json(result)).into_response());
+        }
+    };
+
+    state.networks.check(batch.networks())?;
+    scopes.check_networks(batch.networks())?;
+    meter.set_network(&batch.network);
+
+    let scenarios = batch.scenarios.len();
+    if request.validate_only {
+        return Ok(Json(ScenarioFileResult {
+            valid: true,
+            errors: Vec::new(),
+            scenarios,
+            result: None,
+        })
+        .into_response());
+    }
+
+    tracing::info!(
+        "Running scenario file: {} scenarios on {}",
+        scenarios,
+        batch.network
+    );
+
+    let mut result = state.simulation.execute_batch(batch, &Progress::none()).await?;
+    meter.add_gas(result.total_gas_used());
+    let user = user.map(|Extension(user)| user);
+    result.run_id = store_run(&state, user.as_ref(), RunKind::Batch, &result).await;
+
+    Ok(Json(ScenarioFileResult {
+        valid: true,
+        errors: Vec::new(),
+        scenarios,
+        result: Some(result),
+    })
+    .into_response())
+}
@@ -1,13 +1,22 @@
-use axum::{extract::State, Json};
+use axum::{
+    extract::State,
+    response::{IntoResponse, Response},
+    Json,
+};
 
+use crate::cache::CACHE_STATUS_HEADER;
+use crate::auth::KeyScopes;
 use crate::error::ApiError;
-use crate::simulation::{SimulationRequest, SimulationResult};
+use crate::simulation::SimulationRequest;
+use crate::usage::UsageMeter;
 use crate::AppState;
 
 pub async fn simulate_transaction(
     State(state): State<AppState>,
+    meter: UsageMeter,
+    scopes: KeyScopes,
     Json(request): Json<SimulationRequest>,
-) -> Result<Json<SimulationResult>, ApiError> {
+) -> Result<Response, ApiError> {
     tracing::info!(
         "Simulating transaction: {}::{}::{}",
         request.module_address,
@@ -15,13 +24,23 @@ pub async fn simulate_transaction(
         request.function_name
     );
 
-    let result = state.simulation.execute(request).await?;
+    state.networks.get(&request.network)?;
+    scopes.check_network(&request.network)?;
+    meter.set_network(&request.network);
+    let (result, cache_status) = state.simulation.execute_cached(request).await?;
+
+    meter.add_gas(result.gas_used);
 
     tracing::info!(
-        "Simulation completed: success={}, gas_used={}",
+        "Simulation completed: success={}, gas_used={}, cache={:?}",
         result.success,
-        result.gas_used
+        result.gas_used,
+        cache_status
     );
 
-    Ok(Json(result))
+    Ok((
+        [(CACHE_STATUS_HEADER, cache_status.header_value())],
+        Json(result),
+    )
+        .into_response())
 }
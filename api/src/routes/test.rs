@@ -0,0 +1,34 @@
+use axum::{response::Response, Json};
+
+use crate::error::ApiError;
+use crate::move_test::{TestExecutor, TestRequest};
+use crate::progress::{sse_response, Progress};
+use crate::reports::ReportFormat;
+
+pub async fn run_tests(
+    format: ReportFormat,
+    Json(request): Json<TestRequest>,
+) -> Result<Response, ApiError> {
+    tracing::info!("Running Move unit tests for {} file(s)", request.files.len());
+
+    let executor = TestExecutor::new();
+    let result = executor.execute(request, &Progress::none()).await?;
+
+    Ok(format.respond(&result))
+}
+
+/// Same as `run_tests`, streaming build, test and coverage phases as SSE
+pub async fn run_tests_stream(Json(request): Json<TestRequest>) -> Response {
+    tracing::info!("Streaming Move unit tests for {} file(s)", request.files.len());
+
+    let (progress, events) = Progress::channel();
+
+    tokio::spawn(async move {
+        let result = TestExecutor::new()
+            .execute(request, &progress)
+            .await;
+        progress.finish(result);
+    });
+
+    sse_response(events)
+}
@@ -1,11 +1,15 @@
 use axum::{extract::State, Json};
 
+use crate::auth::KeyScopes;
 use crate::error::ApiError;
 use crate::trace::{TraceRequest, TraceResult};
+use crate::usage::UsageMeter;
 use crate::AppState;
 
 pub async fn get_trace(
     State(state): State<AppState>,
+    meter: UsageMeter,
+    scopes: KeyScopes,
     Json(request): Json<TraceRequest>,
 ) -> Result<Json<TraceResult>, ApiError> {
     tracing::info!(
@@ -15,8 +19,13 @@ pub async fn get_trace(
         request.function_name
     );
 
+    state.networks.get(&request.network)?;
+    scopes.check_network(&request.network)?;
+    meter.set_network(&request.network);
     let result = state.trace.execute(request).await?;
 
+    meter.add_gas(result.total_gas);
+
     tracing::info!(
         "Trace completed: success={}, steps={}, total_gas={}",
         result.success,
@@ -0,0 +1,173 @@
+use axum::{
+    extract::{Query, State},
+    Extension, Json,
+};
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::auth::{user_tier, AuthenticatedUser};
+use crate::db::{self, KeyUsage, UsageBucket};
+use crate::error::ApiError;
+use crate::usage::{month_bounds, quota_status, QuotaStatus};
+use crate::AppState;
+
+/// Longest range a daily breakdown may cover
+const MAX_DAILY_RANGE_DAYS: i64 = 366;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsagePeriod {
+    #[default]
+    Daily,
+    Monthly,
+}
+
+impl UsagePeriod {
+    /// Postgres `date_trunc` field
+    fn trunc(&self) -> &'static str {
+        match self {
+            UsagePeriod::Daily => "day",
+            UsagePeriod::Monthly => "month",
+        }
+    }
+
+    /// Default start: the last 30 days, or the last 12 months
+    fn default_from(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            UsagePeriod::Daily => {
+                let day = (now - Duration::days(29)).date_naive();
+                Utc.from_utc_datetime(&day.and_hms_opt(0, 0, 0).unwrap())
+            }
+            UsagePeriod::Monthly => {
+                let (start, _) = month_bounds(now);
+                let months = start.year() * 12 + start.month0() as i32 - 11;
+                Utc.with_ymd_and_hms(months / 12, months as u32 % 12 + 1, 1, 0, 0, 0)
+                    .unwrap()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    #[serde(default)]
+    pub period: UsagePeriod,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct UsageTotals {
+    pub calls: i64,
+    pub units: i64,
+    pub errors: i64,
+    pub gas_used: i64,
+    pub prover_cpu_secs: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UsageReport {
+    pub period: UsagePeriod,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub totals: UsageTotals,
+    pub buckets: Vec<UsageBucket>,
+    /// Per API key over the whole range, heaviest first
+    pub keys: Vec<KeyUsage>,
+    /// This calendar month, whatever the requested range
+    pub quota: QuotaStatus,
+}
+
+fn totals(buckets: &[UsageBucket]) -> UsageTotals {
+    buckets.iter().fold(UsageTotals::default(), |mut totals, bucket| {
+        totals.calls += bucket.calls;
+        totals.units += bucket.units;
+        totals.errors += bucket.errors;
+        totals.gas_used += bucket.gas_used;
+        totals.prover_cpu_secs += bucket.prover_cpu_secs;
+        totals
+    })
+}
+
+/// GET /api/v1/usage - Usage aggregates, per-key breakdown and quota
+///
+/// `period` is `daily` (default, last 30 days) or `monthly` (last 12
+/// months); `from` and `to` are RFC 3339 timestamps. A key bound to a
+/// project only sees calls made with that project's keys; the quota is
+/// the account's, since every key spends it.
+pub async fn get_usage(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(query): Query<UsageQuery>,
+) -> Result<Json<UsageReport>, ApiError> {
+    let now = Utc::now();
+    let to = query.to.unwrap_or(now);
+    let from = query.from.unwrap_or_else(|| query.period.default_from(now));
+    if from >= to {
+        return Err(ApiError::BadRequest("from must be before to".to_string()));
+    }
+    if query.period == UsagePeriod::Daily && to - from > Duration::days(MAX_DAILY_RANGE_DAYS) {
+        return Err(ApiError::BadRequest(format!(
+            "Daily usage covers at most {} days; use period=monthly",
+            MAX_DAILY_RANGE_DAYS
+        )));
+    }
+
+    let db_error = |e: sqlx::Error| ApiError::Internal(format!("Failed to load usage: {}", e));
+    let project_id = user.scopes.project_id;
+    let buckets = db::get_usage_buckets(&state.db, &user.user_id, project_id, query.period.trunc(), from, to)
+        .await
+        .map_err(db_error)?;
+    let keys = db::get_usage_by_key(&state.db, &user.user_id, project_id, from, to)
+        .await
+        .map_err(db_error)?;
+    let tier = user_tier(&state, &user.user_id).await;
+    let quota = quota_status(&state.db, &user.user_id, tier).await?;
+
+    Ok(Json(UsageReport {
+        period: query.period,
+        from,
+        to,
+        totals: totals(&buckets),
+        buckets,
+        keys,
+        quota,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_ranges() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 15, 12, 0, 0).unwrap();
+        assert_eq!(
+            UsagePeriod::Daily.default_from(now),
+            Utc.with_ymd_and_hms(2026, 2, 14, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            UsagePeriod::Monthly.default_from(now),
+            Utc.with_ymd_and_hms(2025, 4, 1, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_totals() {
+        let bucket = |calls, gas| UsageBucket {
+            period_start: Utc::now(),
+            calls,
+            units: calls * 2,
+            errors: 1,
+            gas_used: gas,
+            prover_cpu_secs: 0.5,
+            avg_latency_ms: 10.0,
+        };
+        let totals = totals(&[bucket(3, 100), bucket(4, 50)]);
+        assert_eq!(totals.calls, 7);
+        assert_eq!(totals.units, 14);
+        assert_eq!(totals.errors, 2);
+        assert_eq!(totals.gas_used, 150);
+        assert_eq!(totals.prover_cpu_secs, 1.0);
+    }
+}
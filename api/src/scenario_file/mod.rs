@@ -0,0 +1,14 @@
+//! Declarative scenario files
+//!
+//! A versioned YAML or TOML document describing a batch of simulations with
+//! shared variables, named accounts and reusable fixtures. Files are parsed
+//! and resolved into a `BatchSimulationRequest`; every problem found along the
+//! way is reported with the line it occurs on.
+
+pub mod parser;
+pub mod resolve;
+pub mod types;
+
+pub use parser::parse_scenario_file;
+pub use resolve::resolve_scenario_file;
+pub use types::*;
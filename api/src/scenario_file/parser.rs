@@ -0,0 +1,110 @@
+use super::types::{ScenarioFile, ScenarioFileError, ScenarioFileFormat};
+
+/// Parses a scenario file, guessing the format from the contents if needed
+pub fn parse_scenario_file(
+    content: &str,
+    format: Option<ScenarioFileFormat>,
+) -> Result<ScenarioFile, ScenarioFileError> {
+    match format.unwrap_or_else(|| detect_format(content)) {
+        ScenarioFileFormat::Yaml => serde_yaml::from_str(content).map_err(|e| {
+            let location = e.location();
+            ScenarioFileError {
+                line: location.as_ref().map(|l| l.line()),
+                column: location.as_ref().map(|l| l.column()),
+                message: strip_location_suffix(&e.to_string()),
+            }
+        }),
+        ScenarioFileFormat::Toml => toml::from_str(content).map_err(|e| {
+            let (line, column) = match e.span() {
+                Some(span) => {
+                    let (line, column) = position_at(content, span.start);
+                    (Some(line), Some(column))
+                }
+                None => (None, None),
+            };
+            ScenarioFileError {
+                line,
+                column,
+                message: e.message().to_string(),
+            }
+        }),
+    }
+}
+
+/// TOML files have `key = value` pairs or `[table]` headers at the top level
+fn detect_format(content: &str) -> ScenarioFileFormat {
+    let looks_like_toml = content.lines().map(str::trim).any(|line| {
+        line.starts_with("[[") || line.starts_with("version =") || line.starts_with("version=")
+    });
+
+    if looks_like_toml {
+        ScenarioFileFormat::Toml
+    } else {
+        ScenarioFileFormat::Yaml
+    }
+}
+
+/// serde_yaml appends " at line X column Y", which is reported separately
+fn strip_location_suffix(message: &str) -> String {
+    match message.rfind(" at line ") {
+        Some(pos) => message[..pos].to_string(),
+        None => message.to_string(),
+    }
+}
+
+/// 1-based line and column of a byte offset
+pub fn position_at(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map(|l| l.chars().count()).unwrap_or(0) + 1;
+    (line, column)
+}
+
+/// Position of the first occurrence of `needle` at or after `from_line`
+pub fn locate(content: &str, needle: &str, from_line: usize) -> Option<(usize, usize)> {
+    let start: usize = content
+        .split_inclusive('\n')
+        .take(from_line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+
+    content[start..]
+        .find(needle)
+        .map(|offset| position_at(content, start + offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_yaml_and_toml() {
+        let yaml = "version: 1\nscenarios:\n  - name: a\n    sender: \"0x1\"\n";
+        let file = parse_scenario_file(yaml, None).unwrap();
+        assert_eq!(file.scenarios.len(), 1);
+
+        let toml = "version = 1\n\n[[scenarios]]\nname = \"a\"\nsender = \"0x1\"\n";
+        let file = parse_scenario_file(toml, None).unwrap();
+        assert_eq!(file.scenarios[0].name.as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn test_syntax_errors_are_located() {
+        let yaml = "version: 1\nscenarios:\n  - name: a\n    sendr: \"0x1\"\n";
+        let err = parse_scenario_file(yaml, Some(ScenarioFileFormat::Yaml)).unwrap_err();
+        assert_eq!(err.line, Some(4));
+        assert!(err.message.contains("sendr"));
+
+        let toml = "version = 1\n\n[[scenarios]]\nname = \"a\"\nmax_gas = \"lots\"\n";
+        let err = parse_scenario_file(toml, Some(ScenarioFileFormat::Toml)).unwrap_err();
+        assert_eq!(err.line, Some(5));
+    }
+
+    #[test]
+    fn test_locate() {
+        let content = "a: 1\nb: ${x}\nc: ${x}\n";
+        assert_eq!(locate(content, "${x}", 1), Some((2, 4)));
+        assert_eq!(locate(content, "${x}", 3), Some((3, 4)));
+        assert_eq!(locate(content, "${y}", 1), None);
+    }
+}
@@ -0,0 +1,483 @@
+//! Resolution of a parsed scenario file into a batch simulation request
+//!
+//! Fixtures are merged first, then `${variable}` placeholders and `@account`
+//! references are substituted. A placeholder that makes up a whole value is
+//! replaced by the variable's value as-is, so numbers and lists keep their
+//! type; placeholders inside longer strings are replaced textually.
+
+use regex::Regex;
+use serde_json::Value;
+use std::collections::HashSet;
+
+use super::parser::locate;
+use super::types::{
+    EventSpec, ScenarioFile, ScenarioFileError, ScenarioSpec, SCENARIO_FILE_VERSION,
+};
+use crate::simulation::{
+    BalanceChangeExpectation, BatchSimulationRequest, EventExpectation, ScenarioExpectations,
+    SimulationScenario, StateChangeExpectation, APTOS_COIN_TYPE,
+};
+
+const DEFAULT_NETWORK: &str = "testnet";
+
+/// Resolves the file, collecting every error instead of stopping at the first
+pub fn resolve_scenario_file(
+    content: &str,
+    file: &ScenarioFile,
+) -> Result<BatchSimulationRequest, Vec<ScenarioFileError>> {
+    let mut resolver = Resolver {
+        content,
+        file,
+        errors: Vec::new(),
+        placeholder: Regex::new(r"\$\{(\w+)\}").unwrap(),
+    };
+
+    let request = resolver.resolve();
+    if resolver.errors.is_empty() {
+        Ok(request)
+    } else {
+        Err(resolver.errors)
+    }
+}
+
+struct Resolver<'a> {
+    content: &'a str,
+    file: &'a ScenarioFile,
+    errors: Vec<ScenarioFileError>,
+    placeholder: Regex,
+}
+
+impl Resolver<'_> {
+    fn resolve(&mut self) -> BatchSimulationRequest {
+        if self.file.version != SCENARIO_FILE_VERSION {
+            self.error(
+                "version",
+                1,
+                format!(
+                    "Unsupported scenario file version {}, expected {}",
+                    self.file.version, SCENARIO_FILE_VERSION
+                ),
+            );
+        }
+
+        for (name, address) in &self.file.accounts {
+            if !is_address(address) {
+                self.error(address, 1, format!("Account `{}` has invalid address `{}`", name, address));
+            }
+        }
+        for (name, fixture) in &self.file.fixtures {
+            if fixture.fixture.is_some() {
+                self.error(name, 1, format!("Fixture `{}` cannot itself `use` another fixture", name));
+            }
+        }
+
+        if self.file.scenarios.is_empty() {
+            self.error("scenarios", 1, "File defines no scenarios".to_string());
+        }
+
+        let mut anchor = locate(self.content, "scenarios", 1).map(|(l, _)| l).unwrap_or(1);
+        let mut seen = HashSet::new();
+        let mut scenarios = Vec::new();
+
+        for (index, spec) in self.file.scenarios.iter().enumerate() {
+            // Scenarios are located by name, searching forward from the previous one
+            if let Some((line, _)) = spec.name.as_deref().and_then(|n| locate(self.content, n, anchor)) {
+                anchor = line;
+            }
+
+            if let Some(name) = &spec.name {
+                if !seen.insert(name.clone()) {
+                    self.error(name, anchor, format!("Duplicate scenario name `{}`", name));
+                }
+            }
+
+            if let Some(scenario) = self.resolve_scenario(index, spec, anchor) {
+                scenarios.push(scenario);
+            }
+        }
+
+        BatchSimulationRequest {
+            network: self.file.network.clone().unwrap_or_else(|| DEFAULT_NETWORK.to_string()),
+            scenarios,
+            options: self.file.run.clone(),
+        }
+    }
+
+    fn resolve_scenario(&mut self, index: usize, spec: &ScenarioSpec, anchor: usize) -> Option<SimulationScenario> {
+        let label = spec
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("scenarios[{}]", index));
+
+        let merged = match &spec.fixture {
+            Some(fixture) => match self.file.fixtures.get(fixture) {
+                Some(base) => merge(base, spec),
+                None => {
+                    self.error(fixture, anchor, format!("{}: unknown fixture `{}`", label, fixture));
+                    return None;
+                }
+            },
+            None => spec.clone(),
+        };
+
+        let errors_before = self.errors.len();
+
+        let name = self.required(merged.name.clone(), "name", &label, anchor);
+        let sender = self
+            .required(merged.sender.clone(), "sender", &label, anchor)
+            .and_then(|sender| self.resolve_address(&sender, &label, anchor));
+        let function = self
+            .required(merged.function.clone(), "function", &label, anchor)
+            .and_then(|function| self.resolve_function(&function, &label, anchor));
+
+        let type_args = merged
+            .type_args
+            .unwrap_or_default()
+            .iter()
+            .map(|t| self.interpolate_string(t, &label, anchor))
+            .collect();
+        let args = merged
+            .args
+            .unwrap_or_default()
+            .iter()
+            .map(|a| self.interpolate(a, &label, anchor))
+            .collect();
+        let network = merged
+            .network
+            .map(|n| self.interpolate_string(&n, &label, anchor));
+
+        let expect_spec = merged.expect.unwrap_or_default();
+        let mut state_changes = Vec::new();
+        for change in &expect_spec.state_changes {
+            let address = change
+                .address
+                .as_ref()
+                .and_then(|a| self.resolve_address(a, &label, anchor));
+            state_changes.push(StateChangeExpectation {
+                resource: self.interpolate_string(&change.resource, &label, anchor),
+                address,
+                change_type: change.change.clone(),
+                fields: change.fields.as_ref().map(|f| self.interpolate(f, &label, anchor)),
+            });
+        }
+        let events = expect_spec
+            .events
+            .iter()
+            .map(|e| match e {
+                EventSpec::Type(r#type) => EventExpectation {
+                    r#type: self.interpolate_string(r#type, &label, anchor),
+                    data: None,
+                },
+                EventSpec::Detailed { r#type, data } => EventExpectation {
+                    r#type: self.interpolate_string(r#type, &label, anchor),
+                    data: data.as_ref().map(|d| self.interpolate(d, &label, anchor)),
+                },
+            })
+            .collect();
+        let mut balance_changes = Vec::new();
+        for balance in &expect_spec.balance_changes {
+            let address = self.resolve_address(&balance.address, &label, anchor);
+            let coin_type = balance
+                .coin_type
+                .as_ref()
+                .map(|c| self.interpolate_string(c, &label, anchor))
+                .unwrap_or_else(|| APTOS_COIN_TYPE.to_string());
+            if let Some(address) = address {
+                balance_changes.push(BalanceChangeExpectation {
+                    address,
+                    coin_type,
+                    delta: balance.delta,
+                });
+            }
+        }
+        let returns = expect_spec
+            .returns
+            .as_ref()
+            .map(|r| self.interpolate(r, &label, anchor));
+
+        if self.errors.len() > errors_before {
+            return None;
+        }
+        let (module_address, module_name, function_name) = function?;
+
+        Some(SimulationScenario {
+            name: name?,
+            sender: sender?,
+            module_address,
+            module_name,
+            function_name,
+            type_args,
+            args,
+            max_gas: merged.max_gas.or(self.file.max_gas),
+            expect_success: expect_spec.success,
+            expect_error: expect_spec.error,
+            is_view: merged.view.unwrap_or(false),
+            network,
+            sequence: merged.sequence,
+            expect: ScenarioExpectations {
+                max_gas_used: expect_spec.max_gas_used,
+                min_gas_used: expect_spec.min_gas_used,
+                abort_code: expect_spec.abort_code,
+                abort: expect_spec.abort,
+                events,
+                state_changes,
+                balance_changes,
+                returns,
+            },
+        })
+    }
+
+    fn required(&mut self, value: Option<String>, field: &str, label: &str, anchor: usize) -> Option<String> {
+        if value.is_none() {
+            let needle = if label.starts_with("scenarios[") { "scenarios" } else { label };
+            self.error(needle, anchor, format!("{}: missing required field `{}`", label, field));
+        }
+        value
+    }
+
+    /// Splits `address::module::function`, resolving the address part
+    fn resolve_function(&mut self, function: &str, label: &str, anchor: usize) -> Option<(String, String, String)> {
+        let parts: Vec<&str> = function.split("::").collect();
+        if parts.len() != 3 || parts.iter().any(|p| p.is_empty()) {
+            self.error(
+                function,
+                anchor,
+                format!("{}: function `{}` must be `address::module::function`", label, function),
+            );
+            return None;
+        }
+
+        let address = self.resolve_address(parts[0], label, anchor)?;
+        Some((address, parts[1].to_string(), parts[2].to_string()))
+    }
+
+    fn resolve_address(&mut self, value: &str, label: &str, anchor: usize) -> Option<String> {
+        let resolved = self.interpolate_string(value, label, anchor);
+        if is_address(&resolved) {
+            Some(resolved)
+        } else {
+            // Unknown accounts and variables were already reported
+            if !value.starts_with('@') && !self.placeholder.is_match(value) {
+                self.error(value, anchor, format!("{}: `{}` is not a valid address", label, value));
+            }
+            None
+        }
+    }
+
+    fn interpolate(&mut self, value: &Value, label: &str, anchor: usize) -> Value {
+        match value {
+            Value::String(s) => self.interpolate_str(s, label, anchor),
+            Value::Array(items) => Value::Array(
+                items.iter().map(|v| self.interpolate(v, label, anchor)).collect(),
+            ),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), self.interpolate(v, label, anchor)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    fn interpolate_string(&mut self, value: &str, label: &str, anchor: usize) -> String {
+        match self.interpolate_str(value, label, anchor) {
+            Value::String(s) => s,
+            other => other.to_string(),
+        }
+    }
+
+    fn interpolate_str(&mut self, value: &str, label: &str, anchor: usize) -> Value {
+        if let Some(account) = value.strip_prefix('@') {
+            if account.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return match self.file.accounts.get(account) {
+                    Some(address) => Value::String(address.clone()),
+                    None => {
+                        self.error(value, anchor, format!("{}: unknown account `{}`", label, value));
+                        Value::String(value.to_string())
+                    }
+                };
+            }
+        }
+
+        let mut missing = Vec::new();
+        let whole = self
+            .placeholder
+            .captures(value)
+            .filter(|cap| cap.get(0).map(|m| m.as_str()) == Some(value))
+            .map(|cap| cap[1].to_string());
+
+        let result = match whole {
+            Some(name) => match self.file.variables.get(&name) {
+                Some(v) => v.clone(),
+                None => {
+                    missing.push(name);
+                    Value::String(value.to_string())
+                }
+            },
+            None => {
+                let variables = &self.file.variables;
+                let replaced = self.placeholder.replace_all(value, |cap: &regex::Captures| {
+                    match variables.get(&cap[1]) {
+                        Some(Value::String(s)) => s.clone(),
+                        Some(v) => v.to_string(),
+                        None => {
+                            missing.push(cap[1].to_string());
+                            cap[0].to_string()
+                        }
+                    }
+                });
+                Value::String(replaced.into_owned())
+            }
+        };
+
+        for name in missing {
+            self.error(
+                &format!("${{{}}}", name),
+                anchor,
+                format!("{}: undefined variable `{}`", label, name),
+            );
+        }
+
+        result
+    }
+
+    /// Records an error at the first occurrence of `needle` from the anchor line
+    fn error(&mut self, needle: &str, anchor: usize, message: String) {
+        let position = locate(self.content, needle, anchor).or_else(|| locate(self.content, needle, 1));
+        let error = ScenarioFileError {
+            line: position.map(|(line, _)| line),
+            column: position.map(|(_, column)| column),
+            message,
+        };
+        if !self.errors.contains(&error) {
+            self.errors.push(error);
+        }
+    }
+}
+
+/// Scenario fields override the fixture's; `expect` is replaced as a whole
+fn merge(base: &ScenarioSpec, spec: &ScenarioSpec) -> ScenarioSpec {
+    ScenarioSpec {
+        name: spec.name.clone().or_else(|| base.name.clone()),
+        fixture: None,
+        sender: spec.sender.clone().or_else(|| base.sender.clone()),
+        function: spec.function.clone().or_else(|| base.function.clone()),
+        type_args: spec.type_args.clone().or_else(|| base.type_args.clone()),
+        args: spec.args.clone().or_else(|| base.args.clone()),
+        network: spec.network.clone().or_else(|| base.network.clone()),
+        max_gas: spec.max_gas.or(base.max_gas),
+        view: spec.view.or(base.view),
+        sequence: spec.sequence.clone().or_else(|| base.sequence.clone()),
+        expect: spec.expect.clone().or_else(|| base.expect.clone()),
+    }
+}
+
+fn is_address(value: &str) -> bool {
+    value
+        .strip_prefix("0x")
+        .is_some_and(|hex| !hex.is_empty() && hex.len() <= 64 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario_file::parse_scenario_file;
+
+    const FILE: &str = r#"version: 1
+network: testnet
+max_gas: 200000
+variables:
+  amount: 1000
+  coin: "0x1::aptos_coin::AptosCoin"
+accounts:
+  alice: "0xa11ce"
+  vault: "0xbeef"
+fixtures:
+  deposit:
+    function: "@vault::vault::deposit"
+    type_args: ["${coin}"]
+    args: ["${amount}"]
+scenarios:
+  - name: alice deposits
+    use: deposit
+    sender: "@alice"
+    expect:
+      success: true
+      max_gas_used: 5000
+      events:
+        - "0xbeef::vault::DepositEvent"
+        - type: "0x1::coin::CoinWithdraw"
+          data: { amount: "${amount}" }
+      balance_changes:
+        - address: "@alice"
+          delta: -1000
+      state_changes:
+        - resource: "0xbeef::vault::Vault<${coin}>"
+          address: "@alice"
+          change: write
+  - name: deposit on mainnet
+    use: deposit
+    sender: "@alice"
+    network: mainnet
+    args: ["amount is ${amount}"]
+"#;
+
+    fn resolve(content: &str) -> Result<BatchSimulationRequest, Vec<ScenarioFileError>> {
+        let file = parse_scenario_file(content, None).unwrap();
+        resolve_scenario_file(content, &file)
+    }
+
+    #[test]
+    fn test_resolve_file() {
+        let request = resolve(FILE).unwrap();
+        assert_eq!(request.network, "testnet");
+        assert_eq!(request.scenarios.len(), 2);
+
+        let first = &request.scenarios[0];
+        assert_eq!(first.sender, "0xa11ce");
+        assert_eq!(first.module_address, "0xbeef");
+        assert_eq!(first.module_name, "vault");
+        assert_eq!(first.function_name, "deposit");
+        assert_eq!(first.type_args, vec!["0x1::aptos_coin::AptosCoin"]);
+        assert_eq!(first.args, vec![serde_json::json!(1000)]);
+        assert_eq!(first.max_gas, Some(200000));
+        assert_eq!(first.expect.max_gas_used, Some(5000));
+        assert_eq!(
+            first.expect.state_changes[0].resource,
+            "0xbeef::vault::Vault<0x1::aptos_coin::AptosCoin>"
+        );
+        assert_eq!(first.expect.state_changes[0].address.as_deref(), Some("0xa11ce"));
+        assert_eq!(first.expect.events[1].data, Some(serde_json::json!({"amount": 1000})));
+        assert_eq!(first.expect.balance_changes[0].address, "0xa11ce");
+        assert_eq!(first.expect.balance_changes[0].coin_type, APTOS_COIN_TYPE);
+
+        let second = &request.scenarios[1];
+        assert_eq!(second.network.as_deref(), Some("mainnet"));
+        assert_eq!(second.args, vec![serde_json::json!("amount is 1000")]);
+    }
+
+    #[test]
+    fn test_errors_are_collected_with_lines() {
+        let content = FILE
+            .replace("sender: \"@alice\"\n    network", "sender: \"@bob\"\n    network")
+            .replace("\"amount is ${amount}\"", "\"${amout}\"");
+        let errors = resolve(&content).unwrap_err();
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].line, Some(35));
+        assert!(errors[0].message.contains("unknown account `@bob`"));
+        assert_eq!(errors[1].line, Some(37));
+        assert!(errors[1].message.contains("undefined variable `amout`"));
+    }
+
+    #[test]
+    fn test_unknown_fixture_and_version() {
+        let content = FILE.replacen("version: 1", "version: 2", 1).replace("use: deposit\n    sender: \"@alice\"\n    network", "use: withdraw\n    sender: \"@alice\"\n    network");
+        let errors = resolve(&content).unwrap_err();
+
+        assert_eq!(errors[0].line, Some(1));
+        assert!(errors[0].message.contains("version 2"));
+        assert_eq!(errors[1].line, Some(34));
+        assert!(errors[1].message.contains("unknown fixture `withdraw`"));
+    }
+}
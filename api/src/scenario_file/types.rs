@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::simulation::{AbortExpectation, BatchOptions, BatchSimulationResult, ChangeType};
+
+/// Latest scenario file format version
+pub const SCENARIO_FILE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScenarioFileFormat {
+    Yaml,
+    Toml,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScenarioFileRequest {
+    /// Raw file contents
+    pub content: String,
+    /// Detected from the contents when omitted
+    #[serde(default)]
+    pub format: Option<ScenarioFileFormat>,
+    /// Only check the file, without running any simulations
+    #[serde(default)]
+    pub validate_only: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScenarioFileResult {
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ScenarioFileError>,
+    /// Number of scenarios defined in the file
+    pub scenarios: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<BatchSimulationResult>,
+}
+
+/// A problem in a scenario file, located by 1-based line and column
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ScenarioFileError {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for ScenarioFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "line {}, column {}: {}", line, column, self.message),
+            (Some(line), None) => write!(f, "line {}: {}", line, self.message),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+// File format
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioFile {
+    pub version: u32,
+    /// Default network for all scenarios
+    #[serde(default)]
+    pub network: Option<String>,
+    /// Default gas limit for all scenarios
+    #[serde(default)]
+    pub max_gas: Option<u64>,
+    /// Values substituted for `${name}` placeholders
+    #[serde(default)]
+    pub variables: BTreeMap<String, serde_json::Value>,
+    /// Addresses referenced as `@name`
+    #[serde(default)]
+    pub accounts: BTreeMap<String, String>,
+    /// Partial scenarios that others extend with `use`
+    #[serde(default)]
+    pub fixtures: BTreeMap<String, ScenarioSpec>,
+    /// Concurrency, timeout, fail-fast and retry settings for the batch
+    #[serde(default)]
+    pub run: BatchOptions,
+    #[serde(default)]
+    pub scenarios: Vec<ScenarioSpec>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioSpec {
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Fixture this scenario extends; its own fields take precedence
+    #[serde(default, rename = "use")]
+    pub fixture: Option<String>,
+    #[serde(default)]
+    pub sender: Option<String>,
+    /// Fully qualified function, e.g. "@vault::vault::deposit"
+    #[serde(default)]
+    pub function: Option<String>,
+    #[serde(default)]
+    pub type_args: Option<Vec<String>>,
+    #[serde(default)]
+    pub args: Option<Vec<serde_json::Value>>,
+    #[serde(default)]
+    pub network: Option<String>,
+    #[serde(default)]
+    pub max_gas: Option<u64>,
+    /// Call a view function instead of simulating a transaction
+    #[serde(default)]
+    pub view: Option<bool>,
+    /// Scenarios sharing a sequence run one at a time, in file order
+    #[serde(default)]
+    pub sequence: Option<String>,
+    #[serde(default)]
+    pub expect: Option<ExpectSpec>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExpectSpec {
+    #[serde(default)]
+    pub success: Option<bool>,
+    /// Substring of the VM status
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub abort_code: Option<u64>,
+    #[serde(default)]
+    pub abort: Option<AbortExpectation>,
+    /// Gas ceiling
+    #[serde(default)]
+    pub max_gas_used: Option<u64>,
+    /// Gas floor
+    #[serde(default)]
+    pub min_gas_used: Option<u64>,
+    #[serde(default)]
+    pub events: Vec<EventSpec>,
+    #[serde(default)]
+    pub state_changes: Vec<StateChangeSpec>,
+    #[serde(default)]
+    pub balance_changes: Vec<BalanceChangeSpec>,
+    /// Return values of a view scenario
+    #[serde(default)]
+    pub returns: Option<serde_json::Value>,
+}
+
+/// An event type, optionally with fields its data must contain
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum EventSpec {
+    Type(String),
+    Detailed {
+        r#type: String,
+        #[serde(default)]
+        data: Option<serde_json::Value>,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StateChangeSpec {
+    pub resource: String,
+    /// Address or `@account`
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default)]
+    pub change: Option<ChangeType>,
+    #[serde(default)]
+    pub fields: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BalanceChangeSpec {
+    /// Address or `@account`
+    pub address: String,
+    /// Defaults to the native coin
+    #[serde(default)]
+    pub coin_type: Option<String>,
+    pub delta: i128,
+}
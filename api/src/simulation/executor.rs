@@ -1,176 +1,156 @@
+use futures::stream::{self, StreamExt};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::expectations::{check_expectations, normalize_address, BalanceSnapshot};
 use super::types::{
-    BatchSimulationRequest, BatchSimulationResult, ChangeType, ScenarioResult, SimEvent,
+    BatchOptions, BatchSimulationRequest, BatchSimulationResult, ChangeType, ScenarioResult, SimEvent,
     SimulationRequest, SimulationResult, SimulationScenario, StateChange,
 };
+use crate::cache::{
+    cache_simulation, get_cached_simulation, hash_simulation_request, CacheBackend, CacheStatus,
+};
 use crate::config::Config;
 use crate::error::ApiError;
+use crate::movement::{
+    prefixed_address, CachedMovementClient, FunctionCall, MovementClient, SimulationTransaction,
+};
+use crate::progress::{Progress, ProgressEvent};
+
+/// Upper bound on `rpc_retries` per scenario
+const MAX_RPC_RETRIES: u32 = 5;
+
+/// Upper bound on `scenario_timeout_seconds`, so one request cannot hold
+/// batch slots indefinitely
+const MAX_SCENARIO_TIMEOUT_SECS: u64 = 300;
 
 pub struct SimulationExecutor {
-    http_client: reqwest::Client,
+    /// Cached lookups, or the same client as `uncached` without a cache
+    client: Arc<dyn MovementClient>,
+    /// Used for `no_cache` requests
+    uncached: Arc<dyn MovementClient>,
+    cache: Option<Arc<dyn CacheBackend>>,
     config: Config,
 }
 
 impl SimulationExecutor {
-    pub fn new(config: Config) -> Self {
+    pub fn new(config: Config, client: Arc<dyn MovementClient>) -> Self {
         Self {
-            http_client: reqwest::Client::new(),
+            uncached: client.clone(),
+            client,
+            cache: None,
             config,
         }
     }
 
-    /// Build a GET request with Shinami API key header if configured
-    fn build_get(&self, url: &str) -> reqwest::RequestBuilder {
-        let mut req = self.http_client.get(url);
-        if let Some(api_key) = self.config.get_shinami_api_key() {
-            req = req.header("X-Api-Key", api_key);
-        }
-        req
+    /// Caches pinned results, module ABIs and account lookups
+    pub fn with_cache(mut self, cache: Arc<dyn CacheBackend>) -> Self {
+        self.client = Arc::new(CachedMovementClient::new(self.uncached.clone(), cache.clone()));
+        self.cache = Some(cache);
+        self
     }
 
-    /// Build a POST request with Shinami API key header if configured
-    fn build_post(&self, url: &str) -> reqwest::RequestBuilder {
-        let mut req = self.http_client.post(url);
-        if let Some(api_key) = self.config.get_shinami_api_key() {
-            req = req.header("X-Api-Key", api_key);
+    /// Simulates through the result cache; only view calls pinned to a
+    /// ledger version are cached, since anything else depends on the latest state
+    pub async fn execute_cached(
+        &self,
+        request: SimulationRequest,
+    ) -> Result<(SimulationResult, CacheStatus), ApiError> {
+        let Some(cache) = self.cache.as_deref().filter(|_| request.ledger_version.is_some()) else {
+            return Ok((self.execute(request).await?, CacheStatus::Bypass));
+        };
+
+        let hash = hash_simulation_request(&request);
+        if !request.no_cache {
+            if let Some(json) = get_cached_simulation(cache, &request.network, &hash).await {
+                if let Ok(result) = serde_json::from_str(&json) {
+                    return Ok((result, CacheStatus::Hit));
+                }
+            }
         }
-        req
+
+        let status = if request.no_cache { CacheStatus::Bypass } else { CacheStatus::Miss };
+        let network = request.network.clone();
+        let result = self.execute(request).await?;
+
+        let json = serde_json::to_string(&result)?;
+        if let Err(e) = cache_simulation(cache, &network, &hash, &json, result.success).await {
+            tracing::warn!("Failed to cache simulation result: {}", e);
+        }
+
+        Ok((result, status))
     }
 
     pub async fn execute(&self, request: SimulationRequest) -> Result<SimulationResult, ApiError> {
-        let rpc_url = self.config.get_rpc_url(&request.network);
+        let client = if request.no_cache { &self.uncached } else { &self.client };
+        let mut call = FunctionCall::new(
+            &request.module_address,
+            &request.module_name,
+            &request.function_name,
+            &request.type_args,
+            &request.args,
+        );
 
         // If this is a view function, use the /v1/view endpoint
         if request.is_view {
-            return self.execute_view(&rpc_url, &request).await;
+            call.ledger_version = request.ledger_version;
+            return self.execute_view(client.as_ref(), &request.network, &call).await;
+        }
+        if request.ledger_version.is_some() {
+            return Err(ApiError::BadRequest(
+                "ledger_version can only be set for view functions; transactions simulate against the latest state"
+                    .to_string(),
+            ));
         }
 
-        // Convert numeric arguments to strings (Movement/Aptos API requires this for u64, u128, etc.)
-        let stringified_args: Vec<serde_json::Value> = request.args.iter().map(|arg| {
-            match arg {
-                serde_json::Value::Number(n) => serde_json::Value::String(n.to_string()),
-                other => other.clone(),
-            }
-        }).collect();
-
-        // Build the transaction payload
-        let payload = serde_json::json!({
-            "type": "entry_function_payload",
-            "function": format!(
-                "{}::{}::{}",
-                request.module_address,
-                request.module_name,
-                request.function_name
-            ),
-            "type_arguments": request.type_args,
-            "arguments": stringified_args,
-        });
-
-        // Normalize sender address
-        let sender_normalized = if request.sender.starts_with("0x") {
-            request.sender.clone()
-        } else {
-            format!("0x{}", request.sender)
-        };
+        // Accounts that don't exist yet simulate with sequence number 0
+        let sequence_number = client
+            .get_account(&request.network, &request.sender)
+            .await?
+            .map(|account| account.sequence_number)
+            .unwrap_or(0);
 
-        // Use provided public_key if available, otherwise fetch from account
-        let (public_key, sequence_number) = if let Some(ref pk) = request.public_key {
-            // Get sequence number from account
-            let (_, seq) = self.get_account_auth_info(&rpc_url, &sender_normalized).await?;
-            (pk.clone(), seq)
-        } else {
-            // Fetch account info to get authentication key and sequence number
-            self.get_account_auth_info(&rpc_url, &sender_normalized).await?
+        let transaction = SimulationTransaction {
+            sender: prefixed_address(&request.sender),
+            sequence_number,
+            max_gas_amount: request.max_gas,
+            gas_unit_price: 100,
+            call,
+            public_key: request.public_key.clone(),
         };
 
-        let dummy_signature = serde_json::json!({
-            "type": "ed25519_signature",
-            "public_key": public_key,
-            "signature": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"
-        });
-
-        // Build the simulation request body
-        let body = serde_json::json!({
-            "sender": sender_normalized,
-            "sequence_number": sequence_number,
-            "max_gas_amount": request.max_gas.to_string(),
-            "gas_unit_price": "100",
-            "expiration_timestamp_secs": (chrono::Utc::now().timestamp() + 600).to_string(),
-            "payload": payload,
-            "signature": dummy_signature,
-        });
-
-        // Make the simulation request with query params for gas estimation
-        // Note: We don't use estimate_max_gas_amount=true because Movement returns
-        // very low estimates that can fall below minimum gas requirements
-        let response = self
-            .build_post(&format!(
-                "{}/transactions/simulate?estimate_gas_unit_price=true&estimate_prioritized_gas_unit_price=false",
-                rpc_url
-            ))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ApiError::SimulationFailed(error_text));
-        }
-
-        let result: Vec<serde_json::Value> = response.json().await?;
-        let tx_result = result.first().ok_or_else(|| {
-            ApiError::SimulationFailed("Empty response from simulation".to_string())
-        })?;
-
-        self.parse_simulation_result(tx_result)
+        let tx_result = client.simulate(&request.network, &transaction).await?;
+        self.parse_simulation_result(&tx_result)
     }
 
     /// Execute a view function call (no signature required)
-    async fn execute_view(&self, rpc_url: &str, request: &SimulationRequest) -> Result<SimulationResult, ApiError> {
-        // Convert numeric arguments to strings
-        let stringified_args: Vec<serde_json::Value> = request.args.iter().map(|arg| {
-            match arg {
-                serde_json::Value::Number(n) => serde_json::Value::String(n.to_string()),
-                other => other.clone(),
+    async fn execute_view(
+        &self,
+        client: &dyn MovementClient,
+        network: &str,
+        call: &FunctionCall,
+    ) -> Result<SimulationResult, ApiError> {
+        let result = match client.view(network, call).await {
+            Ok(values) => values,
+            Err(ApiError::SimulationFailed(error_text)) => {
+                return Ok(SimulationResult {
+                    success: false,
+                    gas_used: 0,
+                    gas_unit_price: 0,
+                    vm_status: "VIEW_FUNCTION_ERROR".to_string(),
+                    state_changes: vec![],
+                    events: vec![],
+                    error: Some(super::types::SimulationError {
+                        code: "VIEW_FUNCTION_FAILED".to_string(),
+                        message: error_text,
+                        location: None,
+                    }),
+                });
             }
-        }).collect();
-
-        let body = serde_json::json!({
-            "function": format!(
-                "{}::{}::{}",
-                request.module_address,
-                request.module_name,
-                request.function_name
-            ),
-            "type_arguments": request.type_args,
-            "arguments": stringified_args,
-        });
-
-        let response = self
-            .build_post(&format!("{}/view", rpc_url))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            return Ok(SimulationResult {
-                success: false,
-                gas_used: 0,
-                gas_unit_price: 0,
-                vm_status: "VIEW_FUNCTION_ERROR".to_string(),
-                state_changes: vec![],
-                events: vec![],
-                error: Some(super::types::SimulationError {
-                    code: "VIEW_FUNCTION_FAILED".to_string(),
-                    message: error_text,
-                    location: None,
-                }),
-            });
-        }
-
-        let result: serde_json::Value = response.json().await?;
+            Err(e) => return Err(e),
+        };
 
         // View functions return the result directly (usually an array of return values)
         Ok(SimulationResult {
@@ -181,7 +161,7 @@ impl SimulationExecutor {
             state_changes: vec![],  // View functions don't change state
             events: vec![SimEvent {
                 r#type: "view_function_result".to_string(),
-                data: result,
+                data: serde_json::Value::Array(result),
                 sequence_number: 0,
             }],
             error: None,
@@ -279,37 +259,6 @@ impl SimulationExecutor {
         }).collect()
     }
 
-    /// Fetches account sequence number and returns a simulation-compatible public key.
-    /// For simulation, we use a well-known valid Ed25519 public key since the signature
-    /// is not actually verified - only the format needs to be valid.
-    async fn get_account_auth_info(&self, rpc_url: &str, address: &str) -> Result<(String, String), ApiError> {
-        // Use a well-known valid Ed25519 public key for simulation
-        // This is the Ed25519 base point which is always a valid curve point
-        // The actual key doesn't matter for simulation - only the format needs to be valid
-        let simulation_public_key = "0x3b6a27bcceb6a42d62a3a8d02a6f0d73653215771de243a63ac048a18b59da29";
-
-        let account_url = format!("{}/accounts/{}", rpc_url, address);
-
-        let response = self.build_get(&account_url)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            // Account may not exist - use default sequence number
-            return Ok((simulation_public_key.to_string(), "0".to_string()));
-        }
-
-        let account_info: serde_json::Value = response.json().await?;
-
-        // Extract sequence number
-        let sequence_number = account_info.get("sequence_number")
-            .and_then(|v| v.as_str())
-            .unwrap_or("0")
-            .to_string();
-
-        Ok((simulation_public_key.to_string(), sequence_number))
-    }
-
     fn parse_events(&self, result: &serde_json::Value) -> Vec<SimEvent> {
         let events = result.get("events")
             .and_then(|v| v.as_array())
@@ -332,68 +281,184 @@ impl SimulationExecutor {
         }).collect()
     }
 
+    /// Runs the batch, emitting each scenario's result as soon as it is known
     pub async fn execute_batch(
         &self,
         request: BatchSimulationRequest,
+        progress: &Progress,
     ) -> Result<BatchSimulationResult, ApiError> {
-        let mut results = vec![];
-        let mut passed = 0u32;
-        let mut failed = 0u32;
-        let mut max_gas = 0u64;
-
-        for scenario in request.scenarios {
-            let sim_request = SimulationRequest {
-                network: request.network.clone(),
-                sender: scenario.sender.clone(),
-                module_address: scenario.module_address.clone(),
-                module_name: scenario.module_name.clone(),
-                function_name: scenario.function_name.clone(),
-                type_args: scenario.type_args.clone(),
-                args: scenario.args.clone(),
-                max_gas: scenario.max_gas.unwrap_or(100_000),
-                is_view: false,  // Batch simulations are for entry functions
-                public_key: None,
-            };
+        let options = &request.options;
+        let concurrency = options
+            .concurrency
+            .unwrap_or(self.config.batch_max_concurrency)
+            .clamp(1, self.config.batch_max_concurrency);
+        let stop = AtomicBool::new(false);
+
+        let groups = sequence_groups(&request.scenarios);
+        let (stop, scenarios, network) = (&stop, &request.scenarios, &request.network);
+
+        // Each group runs its scenarios in order; groups run concurrently
+        let mut indexed: Vec<(usize, ScenarioResult)> = stream::iter(groups)
+            .map(|group| async move {
+                let mut results = Vec::with_capacity(group.len());
+                for index in group {
+                    let scenario = &scenarios[index];
+                    // A disconnected streaming client has no use for the rest
+                    let result = if (options.fail_fast && stop.load(Ordering::Relaxed))
+                        || progress.is_cancelled()
+                    {
+                        skipped_result(scenario)
+                    } else {
+                        self.run_scenario(network, scenario, options).await
+                    };
+                    if !result.passed && options.fail_fast {
+                        stop.store(true, Ordering::Relaxed);
+                    }
+                    progress.emit(ProgressEvent::Scenario {
+                        index,
+                        result: result.clone(),
+                    });
+                    results.push((index, result));
+                }
+                results
+            })
+            .buffer_unordered(concurrency)
+            .flat_map(stream::iter)
+            .collect()
+            .await;
 
-            let sim_result = self.execute(sim_request).await;
-            let scenario_result = self.evaluate_scenario(&scenario, sim_result);
+        indexed.sort_by_key(|(index, _)| *index);
+        let results: Vec<ScenarioResult> = indexed.into_iter().map(|(_, r)| r).collect();
 
-            if scenario_result.passed {
-                passed += 1;
-            } else {
-                failed += 1;
-            }
+        let total = results.len() as u32;
+        let passed = results.iter().filter(|r| r.passed).count() as u32;
+        let skipped = results.iter().filter(|r| r.skipped).count() as u32;
+        let failed = total - passed - skipped;
+
+        let mut gas: Vec<u64> = results.iter().filter(|r| !r.skipped).map(|r| r.gas_used).collect();
+        gas.sort_unstable();
 
-            max_gas = max_gas.max(scenario_result.gas_used);
-            results.push(scenario_result);
+        let mut summary = format!("{}/{} scenarios passed", passed, total);
+        if skipped > 0 {
+            summary.push_str(&format!(", {} skipped", skipped));
         }
 
-        let total = results.len() as u32;
         Ok(BatchSimulationResult {
             total,
             passed,
             failed,
+            skipped,
+            max_gas_used: gas.last().copied().unwrap_or(0),
+            gas_p50: percentile(&gas, 50),
+            gas_p95: percentile(&gas, 95),
             results,
-            max_gas_used: max_gas,
-            summary: format!("{}/{} scenarios passed", passed, total),
+            summary,
+            run_id: None,
         })
     }
 
+    /// Simulates one scenario, retrying RPC errors with exponential backoff
+    async fn run_scenario(
+        &self,
+        network: &str,
+        scenario: &SimulationScenario,
+        options: &BatchOptions,
+    ) -> ScenarioResult {
+        let network = scenario.network.clone().unwrap_or_else(|| network.to_string());
+        let retries = options.rpc_retries.min(MAX_RPC_RETRIES);
+
+        let run = async {
+            let balances_before = self.fetch_balances(&network, scenario).await;
+
+            let mut attempt = 0;
+            loop {
+                let sim_request = SimulationRequest {
+                    network: network.clone(),
+                    sender: scenario.sender.clone(),
+                    module_address: scenario.module_address.clone(),
+                    module_name: scenario.module_name.clone(),
+                    function_name: scenario.function_name.clone(),
+                    type_args: scenario.type_args.clone(),
+                    args: scenario.args.clone(),
+                    max_gas: scenario.max_gas.unwrap_or(100_000),
+                    is_view: scenario.is_view,
+                    public_key: None,
+                    ledger_version: None,
+                    no_cache: options.no_cache,
+                };
+
+                match self.execute(sim_request).await {
+                    Err(ApiError::RpcError(e)) if attempt < retries => {
+                        attempt += 1;
+                        tracing::warn!(
+                            "Scenario '{}' hit RPC error, retry {}/{}: {}",
+                            scenario.name,
+                            attempt,
+                            retries,
+                            e
+                        );
+                        tokio::time::sleep(Duration::from_millis(250 << attempt)).await;
+                    }
+                    result => return self.evaluate_scenario(scenario, result, &balances_before),
+                }
+            }
+        };
+
+        let timeout = scenario_timeout(options);
+        match tokio::time::timeout(timeout, run).await {
+            Ok(result) => result,
+            Err(_) => failed_result(
+                scenario,
+                format!("Timed out after {}s", timeout.as_secs()),
+            ),
+        }
+    }
+
+    /// Reads the balances a scenario's expectations compare against,
+    /// before the transaction is simulated
+    async fn fetch_balances(&self, network: &str, scenario: &SimulationScenario) -> BalanceSnapshot {
+        let mut balances = BalanceSnapshot::new();
+
+        for expected in &scenario.expect.balance_changes {
+            let address = normalize_address(&expected.address);
+            let coin_store = format!("0x1::coin::CoinStore<{}>", expected.coin_type);
+
+            let balance = match self.client.get_resource(network, &address, &coin_store).await {
+                // No CoinStore yet means a zero balance
+                Ok(None) => Some(0),
+                Ok(Some(data)) => data
+                    .get("coin")
+                    .and_then(|c| c.get("value"))
+                    .and_then(|v| v.as_str())
+                    .and_then(|v| v.parse().ok()),
+                Err(e) => {
+                    tracing::warn!("Balance lookup for {} failed: {}", address, e);
+                    None
+                }
+            };
+
+            if let Some(balance) = balance {
+                balances.insert((address, expected.coin_type.clone()), balance);
+            }
+        }
+
+        balances
+    }
+
     fn evaluate_scenario(
         &self,
         scenario: &SimulationScenario,
         result: Result<SimulationResult, ApiError>,
+        balances_before: &BalanceSnapshot,
     ) -> ScenarioResult {
         match result {
             Ok(sim) => {
-                let mut passed = true;
-                let mut failure_reason = None;
+                let mut failures = Vec::new();
 
                 // Check success expectation
                 if let Some(expect_success) = scenario.expect_success {
                     if sim.success != expect_success {
-                        passed = false;
-                        failure_reason = Some(format!(
+                        failures.push(format!(
                             "Expected success={}, got {}",
                             expect_success, sim.success
                         ));
@@ -404,42 +469,247 @@ impl SimulationExecutor {
                 if let Some(ref expect_error) = scenario.expect_error {
                     if let Some(ref error) = sim.error {
                         if !error.message.contains(expect_error) {
-                            passed = false;
-                            failure_reason = Some(format!(
+                            failures.push(format!(
                                 "Expected error containing '{}', got '{}'",
                                 expect_error, error.message
                             ));
                         }
                     } else {
-                        passed = false;
-                        failure_reason = Some(format!(
+                        failures.push(format!(
                             "Expected error '{}', but succeeded",
                             expect_error
                         ));
                     }
                 }
 
+                failures.extend(check_expectations(&scenario.expect, &sim, balances_before));
+
                 ScenarioResult {
                     name: scenario.name.clone(),
-                    passed,
+                    passed: failures.is_empty(),
+                    skipped: false,
                     gas_used: sim.gas_used,
                     expected_success: scenario.expect_success,
                     actual_success: sim.success,
                     expected_error: scenario.expect_error.clone(),
                     actual_error: sim.error.map(|e| e.message),
-                    failure_reason,
+                    failure_reason: (!failures.is_empty()).then(|| failures.join("; ")),
+                    failures,
                 }
             }
-            Err(e) => ScenarioResult {
-                name: scenario.name.clone(),
-                passed: false,
-                gas_used: 0,
-                expected_success: scenario.expect_success,
-                actual_success: false,
-                expected_error: scenario.expect_error.clone(),
-                actual_error: Some(e.to_string()),
-                failure_reason: Some(format!("Simulation error: {}", e)),
+            Err(e) => failed_result(scenario, format!("Simulation error: {}", e)),
+        }
+    }
+}
+
+/// Groups scenario indices so that scenarios sharing a `sequence` stay
+/// together in input order; every other scenario is a group of its own
+fn sequence_groups(scenarios: &[SimulationScenario]) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut by_sequence: HashMap<&str, usize> = HashMap::new();
+
+    for (index, scenario) in scenarios.iter().enumerate() {
+        match scenario.sequence.as_deref() {
+            Some(sequence) => match by_sequence.get(sequence) {
+                Some(&group) => groups[group].push(index),
+                None => {
+                    by_sequence.insert(sequence, groups.len());
+                    groups.push(vec![index]);
+                }
             },
+            None => groups.push(vec![index]),
         }
     }
+
+    groups
+}
+
+/// Time allowed for each scenario, within 1s..=MAX_SCENARIO_TIMEOUT_SECS
+fn scenario_timeout(options: &BatchOptions) -> Duration {
+    Duration::from_secs(options.scenario_timeout_seconds.clamp(1, MAX_SCENARIO_TIMEOUT_SECS))
+}
+
+/// Nearest-rank percentile of an ascending slice
+fn percentile(sorted: &[u64], p: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn failed_result(scenario: &SimulationScenario, reason: String) -> ScenarioResult {
+    ScenarioResult {
+        name: scenario.name.clone(),
+        passed: false,
+        skipped: false,
+        gas_used: 0,
+        expected_success: scenario.expect_success,
+        actual_success: false,
+        expected_error: scenario.expect_error.clone(),
+        actual_error: Some(reason.clone()),
+        failure_reason: Some(reason.clone()),
+        failures: vec![reason],
+    }
+}
+
+fn skipped_result(scenario: &SimulationScenario) -> ScenarioResult {
+    ScenarioResult {
+        skipped: true,
+        actual_error: None,
+        failure_reason: Some("Skipped after an earlier scenario failed".to_string()),
+        failures: vec![],
+        ..failed_result(scenario, String::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::movement::MockMovementClient;
+    use crate::simulation::{BalanceChangeExpectation, ScenarioExpectations, APTOS_COIN_TYPE};
+
+    fn config() -> Config {
+        Config::for_tests()
+    }
+
+    fn batch(scenarios: Vec<SimulationScenario>, options: BatchOptions) -> BatchSimulationRequest {
+        BatchSimulationRequest {
+            network: "testnet".to_string(),
+            scenarios,
+            options,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_batch_offline() {
+        let client = MockMovementClient::new()
+            .with_account("0xa11ce", 7)
+            .with_resource(
+                "0xa11ce",
+                "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>",
+                serde_json::json!({"coin": {"value": "1000"}}),
+            )
+            .with_simulation("0x1::m::f", Ok({
+                let mut tx = MockMovementClient::transaction(true, "Executed successfully", 400);
+                tx["changes"] = serde_json::json!([{
+                    "type": "write_resource",
+                    "address": "0xa11ce",
+                    "data": {
+                        "type": "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>",
+                        "data": {"coin": {"value": "960"}},
+                    },
+                }]);
+                tx
+            }))
+            .with_simulation(
+                "0x1::m::fail",
+                Ok(MockMovementClient::transaction(false, "Move abort in 0x1::m: E_LIMIT(0x3)", 100)),
+            );
+        let client = Arc::new(client);
+        let executor = SimulationExecutor::new(config(), client.clone());
+
+        let mut pays = scenario("pays", None);
+        pays.sender = "0xa11ce".to_string();
+        pays.expect = ScenarioExpectations {
+            balance_changes: vec![BalanceChangeExpectation {
+                address: "0xa11ce".to_string(),
+                coin_type: APTOS_COIN_TYPE.to_string(),
+                delta: -40,
+            }],
+            ..Default::default()
+        };
+        let mut aborts = scenario("aborts", None);
+        aborts.function_name = "fail".to_string();
+        aborts.expect_success = Some(true);
+
+        let result = executor
+            .execute_batch(batch(vec![pays, aborts], BatchOptions::default()), &Progress::none())
+            .await
+            .unwrap();
+
+        assert_eq!((result.passed, result.failed), (1, 1));
+        assert_eq!(result.results[0].name, "pays");
+        assert!(result.results[0].passed, "{:?}", result.results[0].failures);
+        assert_eq!(result.results[1].failures, vec!["Expected success=true, got false"]);
+        assert_eq!(result.max_gas_used, 400);
+
+        let sent = client.simulate_calls().into_iter().find(|t| t.call.function == "0x1::m::f").unwrap();
+        assert_eq!(sent.sequence_number, 7);
+    }
+
+    #[tokio::test]
+    async fn test_execute_batch_retries_rpc_errors() {
+        let client = Arc::new(
+            MockMovementClient::new()
+                .with_simulation("0x1::m::f", Err("connection reset".to_string()))
+                .with_simulation("0x1::m::f", Ok(MockMovementClient::transaction(true, "Executed successfully", 10))),
+        );
+        let executor = SimulationExecutor::new(config(), client.clone());
+
+        let options = BatchOptions {
+            rpc_retries: 1,
+            ..Default::default()
+        };
+        let result = executor
+            .execute_batch(batch(vec![scenario("a", None)], options), &Progress::none())
+            .await
+            .unwrap();
+
+        assert_eq!(result.passed, 1);
+        assert_eq!(client.simulate_calls().len(), 2);
+    }
+
+    fn scenario(name: &str, sequence: Option<&str>) -> SimulationScenario {
+        SimulationScenario {
+            name: name.to_string(),
+            sender: "0x1".to_string(),
+            module_address: "0x1".to_string(),
+            module_name: "m".to_string(),
+            function_name: "f".to_string(),
+            type_args: vec![],
+            args: vec![],
+            max_gas: None,
+            expect_success: None,
+            expect_error: None,
+            is_view: false,
+            network: None,
+            sequence: sequence.map(String::from),
+            expect: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_sequence_groups() {
+        let scenarios = vec![
+            scenario("a", None),
+            scenario("init", Some("vault")),
+            scenario("b", None),
+            scenario("deposit", Some("vault")),
+            scenario("withdraw", Some("vault")),
+        ];
+        assert_eq!(sequence_groups(&scenarios), vec![vec![0], vec![1, 3, 4], vec![2]]);
+    }
+
+    #[test]
+    fn test_percentile() {
+        let gas: Vec<u64> = (1..=20).map(|g| g * 100).collect();
+        assert_eq!(percentile(&gas, 50), 1000);
+        assert_eq!(percentile(&gas, 95), 1900);
+        assert_eq!(percentile(&[42], 95), 42);
+        assert_eq!(percentile(&[], 50), 0);
+    }
+
+    #[test]
+    fn test_scenario_timeout() {
+        let timeout = |secs| {
+            scenario_timeout(&BatchOptions {
+                scenario_timeout_seconds: secs,
+                ..BatchOptions::default()
+            })
+        };
+        assert_eq!(timeout(0), Duration::from_secs(1));
+        assert_eq!(timeout(30), Duration::from_secs(30));
+        assert_eq!(timeout(u64::MAX), Duration::from_secs(MAX_SCENARIO_TIMEOUT_SECS));
+    }
 }
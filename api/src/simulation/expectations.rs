@@ -0,0 +1,374 @@
+//! Evaluation of scenario expectations against simulation results
+
+use regex::Regex;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::types::{AbortExpectation, ScenarioExpectations, SimulationResult};
+
+/// A Move abort decoded from a VM status string
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveAbort {
+    /// Module that aborted, e.g. "0x1::coin"
+    pub module: Option<String>,
+    /// Error constant name, e.g. "EINSUFFICIENT_BALANCE"
+    pub name: Option<String>,
+    pub code: u64,
+}
+
+/// Decodes VM statuses such as
+/// `Move abort in 0x1::coin: EINSUFFICIENT_BALANCE(0x10006): Not enough coins`
+/// or `Move abort: code 65542`
+pub fn parse_abort(vm_status: &str) -> Option<MoveAbort> {
+    let detailed = Regex::new(
+        r"(?i)abort(?:ed)? in (0x[0-9a-f]+::\w+):\s*(?:(\w+)\((0x[0-9a-f]+|\d+)\)|(0x[0-9a-f]+|\d+))",
+    )
+    .unwrap();
+    let bare = Regex::new(r"(?i)abort(?:ed)?\b.*?\bcode:?\s*(0x[0-9a-f]+|\d+)").unwrap();
+
+    if let Some(cap) = detailed.captures(vm_status) {
+        let code = cap.get(3).or(cap.get(4))?.as_str();
+        return Some(MoveAbort {
+            module: Some(cap[1].to_string()),
+            name: cap.get(2).map(|m| m.as_str().to_string()),
+            code: parse_code(code)?,
+        });
+    }
+
+    bare.captures(vm_status).and_then(|cap| {
+        Some(MoveAbort {
+            module: None,
+            name: None,
+            code: parse_code(&cap[1])?,
+        })
+    })
+}
+
+fn parse_code(code: &str) -> Option<u64> {
+    match code.strip_prefix("0x").or_else(|| code.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => code.parse().ok(),
+    }
+}
+
+/// Normalizes an address for comparison (`0x0001` == `0x1`)
+pub fn normalize_address(address: &str) -> String {
+    let hex = address.trim().trim_start_matches("0x").trim_start_matches('0');
+    format!("0x{}", hex.to_lowercase())
+}
+
+/// Balances read before the simulation, keyed by normalized address and coin type
+pub type BalanceSnapshot = HashMap<(String, String), u128>;
+
+/// Checks every expectation, returning a message for each one that does not hold
+pub fn check_expectations(
+    expect: &ScenarioExpectations,
+    sim: &SimulationResult,
+    balances_before: &BalanceSnapshot,
+) -> Vec<String> {
+    let mut failures = Vec::new();
+
+    if let Some(ceiling) = expect.max_gas_used {
+        if sim.gas_used > ceiling {
+            failures.push(format!("Gas used {} exceeds ceiling {}", sim.gas_used, ceiling));
+        }
+    }
+    if let Some(floor) = expect.min_gas_used {
+        if sim.gas_used < floor {
+            failures.push(format!("Gas used {} is below floor {}", sim.gas_used, floor));
+        }
+    }
+
+    let abort = parse_abort(&sim.vm_status);
+    if let Some(expected) = expect.abort_code {
+        match &abort {
+            Some(abort) if abort.code == expected => {}
+            Some(abort) => failures.push(format!(
+                "Expected abort code {}, got {}",
+                expected, abort.code
+            )),
+            None => failures.push(format!(
+                "Expected abort code {}, got status '{}'",
+                expected, sim.vm_status
+            )),
+        }
+    }
+    if let Some(expected) = &expect.abort {
+        if let Some(reason) = check_abort(expected, abort.as_ref(), &sim.vm_status) {
+            failures.push(reason);
+        }
+    }
+
+    for event in &expect.events {
+        let found = sim.events.iter().any(|e| {
+            e.r#type == event.r#type
+                && event.data.as_ref().is_none_or(|data| json_matches(data, &e.data))
+        });
+        if !found {
+            failures.push(match &event.data {
+                Some(data) => format!("Expected event {} with data {} was not emitted", event.r#type, data),
+                None => format!("Expected event {} was not emitted", event.r#type),
+            });
+        }
+    }
+
+    for change in &expect.state_changes {
+        let found = sim.state_changes.iter().any(|c| {
+            c.resource == change.resource
+                && change
+                    .address
+                    .as_ref()
+                    .is_none_or(|a| normalize_address(a) == normalize_address(&c.address))
+                && change.change_type.as_ref().is_none_or(|t| *t == c.change_type)
+                && change.fields.as_ref().is_none_or(|fields| {
+                    c.after.as_ref().is_some_and(|after| json_matches(fields, after))
+                })
+        });
+        if !found {
+            failures.push(format!(
+                "Expected state change to {}{}{} not found",
+                change.resource,
+                change
+                    .address
+                    .as_ref()
+                    .map(|a| format!(" at {}", a))
+                    .unwrap_or_default(),
+                change
+                    .fields
+                    .as_ref()
+                    .map(|f| format!(" with fields {}", f))
+                    .unwrap_or_default()
+            ));
+        }
+    }
+
+    for balance in &expect.balance_changes {
+        let key = (normalize_address(&balance.address), balance.coin_type.clone());
+        let Some(&before) = balances_before.get(&key) else {
+            failures.push(format!(
+                "Could not read {} balance of {}",
+                balance.coin_type, balance.address
+            ));
+            continue;
+        };
+        let after = coin_balance_after(sim, &key.0, &balance.coin_type).unwrap_or(before);
+        let actual = after as i128 - before as i128;
+        if actual != balance.delta {
+            failures.push(format!(
+                "Expected {} balance of {} to change by {}, got {}",
+                balance.coin_type, balance.address, balance.delta, actual
+            ));
+        }
+    }
+
+    if let Some(expected) = &expect.returns {
+        match view_returns(sim) {
+            Some(actual) if json_matches(expected, actual) => {}
+            Some(actual) => failures.push(format!("Expected view to return {}, got {}", expected, actual)),
+            None => failures.push(format!("Expected view to return {}, but it returned nothing", expected)),
+        }
+    }
+
+    failures
+}
+
+fn check_abort(expected: &AbortExpectation, abort: Option<&MoveAbort>, vm_status: &str) -> Option<String> {
+    let describe = || {
+        [
+            expected.module.clone(),
+            expected.name.clone(),
+            expected.code.map(|c| c.to_string()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("::")
+    };
+
+    let Some(abort) = abort else {
+        return Some(format!("Expected abort {}, got status '{}'", describe(), vm_status));
+    };
+
+    let module_ok = expected.module.as_ref().is_none_or(|m| {
+        abort.module.as_ref().is_some_and(|actual| same_module(m, actual))
+    });
+    let name_ok = expected
+        .name
+        .as_ref()
+        .is_none_or(|n| abort.name.as_deref() == Some(n.as_str()));
+    let code_ok = expected.code.is_none_or(|c| c == abort.code);
+
+    if module_ok && name_ok && code_ok {
+        None
+    } else {
+        Some(format!(
+            "Expected abort {}, got {}",
+            describe(),
+            [
+                abort.module.clone(),
+                abort.name.clone(),
+                Some(abort.code.to_string()),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("::")
+        ))
+    }
+}
+
+/// Compares "0x1::coin" with "0x0000...01::coin"
+fn same_module(expected: &str, actual: &str) -> bool {
+    match (expected.split_once("::"), actual.split_once("::")) {
+        (Some((ea, em)), Some((aa, am))) => normalize_address(ea) == normalize_address(aa) && em == am,
+        _ => expected == actual,
+    }
+}
+
+/// Coin balance written by the transaction, if its CoinStore was touched
+fn coin_balance_after(sim: &SimulationResult, address: &str, coin_type: &str) -> Option<u128> {
+    let resource = format!("0x1::coin::CoinStore<{}>", coin_type);
+    sim.state_changes
+        .iter()
+        .find(|c| c.resource == resource && normalize_address(&c.address) == address)
+        .and_then(|c| c.after.as_ref())
+        .and_then(|after| after.get("coin")?.get("value")?.as_str()?.parse().ok())
+}
+
+/// View results are carried in a synthetic event by the executor
+fn view_returns(sim: &SimulationResult) -> Option<&Value> {
+    sim.events
+        .iter()
+        .find(|e| e.r#type == "view_function_result")
+        .map(|e| &e.data)
+}
+
+/// Matches `expected` as a subset of `actual`: objects may have extra keys,
+/// arrays must match element-wise, and numbers equal their string encoding
+/// since Move serializes u64 and wider integers as strings
+pub fn json_matches(expected: &Value, actual: &Value) -> bool {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => expected
+            .iter()
+            .all(|(key, value)| actual.get(key).is_some_and(|a| json_matches(value, a))),
+        (Value::Array(expected), Value::Array(actual)) => {
+            expected.len() == actual.len()
+                && expected.iter().zip(actual).all(|(e, a)| json_matches(e, a))
+        }
+        (Value::Number(n), Value::String(s)) | (Value::String(s), Value::Number(n)) => {
+            n.to_string() == *s
+        }
+        _ => expected == actual,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::{
+        BalanceChangeExpectation, ChangeType, EventExpectation, SimEvent, StateChange,
+        StateChangeExpectation, APTOS_COIN_TYPE,
+    };
+    use serde_json::json;
+
+    fn sim(vm_status: &str, gas_used: u64) -> SimulationResult {
+        SimulationResult {
+            success: vm_status == "Executed successfully",
+            gas_used,
+            gas_unit_price: 100,
+            vm_status: vm_status.to_string(),
+            state_changes: vec![StateChange {
+                address: "0x00a1".to_string(),
+                resource: "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>".to_string(),
+                change_type: ChangeType::Write,
+                before: None,
+                after: Some(json!({"coin": {"value": "700"}, "frozen": false})),
+            }],
+            events: vec![SimEvent {
+                r#type: "0x1::coin::DepositEvent".to_string(),
+                data: json!({"amount": "300"}),
+                sequence_number: 0,
+            }],
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_abort() {
+        assert_eq!(
+            parse_abort("Move abort in 0x1::coin: EINSUFFICIENT_BALANCE(0x10006): Not enough coins"),
+            Some(MoveAbort {
+                module: Some("0x1::coin".to_string()),
+                name: Some("EINSUFFICIENT_BALANCE".to_string()),
+                code: 0x10006,
+            })
+        );
+        assert_eq!(parse_abort("Move abort: code 42").map(|a| a.code), Some(42));
+        assert_eq!(parse_abort("Executed successfully"), None);
+    }
+
+    #[test]
+    fn test_json_matches() {
+        assert!(json_matches(&json!({"amount": 300}), &json!({"amount": "300", "to": "0x1"})));
+        assert!(json_matches(&json!(["1", true]), &json!([1, true])));
+        assert!(!json_matches(&json!(["1"]), &json!(["1", "2"])));
+        assert!(!json_matches(&json!({"amount": 301}), &json!({"amount": "300"})));
+    }
+
+    #[test]
+    fn test_check_expectations() {
+        let balances: BalanceSnapshot =
+            [(("0xa1".to_string(), APTOS_COIN_TYPE.to_string()), 1000)].into_iter().collect();
+        let mut expect = ScenarioExpectations {
+            max_gas_used: Some(1000),
+            min_gas_used: Some(100),
+            events: vec![EventExpectation {
+                r#type: "0x1::coin::DepositEvent".to_string(),
+                data: Some(json!({"amount": 300})),
+            }],
+            state_changes: vec![StateChangeExpectation {
+                resource: "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>".to_string(),
+                address: Some("0xa1".to_string()),
+                change_type: Some(ChangeType::Write),
+                fields: Some(json!({"frozen": false})),
+            }],
+            balance_changes: vec![BalanceChangeExpectation {
+                address: "0xa1".to_string(),
+                coin_type: APTOS_COIN_TYPE.to_string(),
+                delta: -300,
+            }],
+            ..Default::default()
+        };
+        assert!(check_expectations(&expect, &sim("Executed successfully", 900), &balances).is_empty());
+        assert_eq!(check_expectations(&expect, &sim("Executed successfully", 1100), &balances).len(), 1);
+        assert_eq!(check_expectations(&expect, &sim("Executed successfully", 50), &balances).len(), 1);
+
+        // Every failing assertion is reported
+        expect.events[0].data = Some(json!({"amount": 1}));
+        expect.balance_changes[0].delta = 5;
+        let failures = check_expectations(&expect, &sim("Executed successfully", 2000), &balances);
+        assert_eq!(failures.len(), 3);
+    }
+
+    #[test]
+    fn test_abort_expectations() {
+        let none = BalanceSnapshot::new();
+        let mut expect = ScenarioExpectations {
+            abort_code: Some(3),
+            ..Default::default()
+        };
+        assert!(check_expectations(&expect, &sim("Move abort in 0x1::m: E(3)", 10), &none).is_empty());
+        assert!(!check_expectations(&expect, &sim("Move abort in 0x1::m: E(4)", 10), &none).is_empty());
+
+        expect.abort_code = None;
+        expect.abort = Some(AbortExpectation {
+            module: Some("0x1::coin".to_string()),
+            name: Some("EINSUFFICIENT_BALANCE".to_string()),
+            code: None,
+        });
+        let status = "Move abort in 0x0000000000000000000000000000000000000000000000000000000000000001::coin: EINSUFFICIENT_BALANCE(0x10006): Not enough coins";
+        assert!(check_expectations(&expect, &sim(status, 10), &none).is_empty());
+        let failures = check_expectations(&expect, &sim("Move abort in 0x1::coin: ECOIN_STORE_NOT_PUBLISHED(0x60005)", 10), &none);
+        assert_eq!(failures, vec!["Expected abort 0x1::coin::EINSUFFICIENT_BALANCE, got 0x1::coin::ECOIN_STORE_NOT_PUBLISHED::393221"]);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod executor;
+pub mod expectations;
 pub mod types;
 
 pub use executor::SimulationExecutor;
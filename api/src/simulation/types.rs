@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct SimulationRequest {
@@ -21,13 +22,19 @@ pub struct SimulationRequest {
     /// Required for entry functions on Movement Network which validates auth
     #[serde(default)]
     pub public_key: Option<String>,
+    /// Evaluate a view function at this ledger version; pinned results are cached
+    #[serde(default)]
+    pub ledger_version: Option<u64>,
+    /// Skip cached results and lookups and go to the node
+    #[serde(default)]
+    pub no_cache: bool,
 }
 
 fn default_max_gas() -> u64 {
     100_000
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationResult {
     pub success: bool,
     pub gas_used: u64,
@@ -39,7 +46,7 @@ pub struct SimulationResult {
     pub error: Option<SimulationError>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StateChange {
     pub address: String,
     pub resource: String,
@@ -50,7 +57,7 @@ pub struct StateChange {
     pub after: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum ChangeType {
     Write,
@@ -58,14 +65,14 @@ pub enum ChangeType {
     Create,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimEvent {
     pub r#type: String,
     pub data: serde_json::Value,
     pub sequence_number: u64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationError {
     pub code: String,
     pub message: String,
@@ -73,7 +80,7 @@ pub struct SimulationError {
     pub location: Option<SourceLocation>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SourceLocation {
     pub module: String,
     pub function: String,
@@ -87,6 +94,51 @@ pub struct SourceLocation {
 pub struct BatchSimulationRequest {
     pub network: String,
     pub scenarios: Vec<SimulationScenario>,
+    #[serde(flatten)]
+    pub options: BatchOptions,
+}
+
+impl BatchSimulationRequest {
+    /// The batch network and every scenario override
+    pub fn networks(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.network.as_str())
+            .chain(self.scenarios.iter().filter_map(|s| s.network.as_deref()))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchOptions {
+    /// Scenarios simulated in parallel, capped by the server limit
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+    /// Time allowed for each scenario, including retries (max 300)
+    #[serde(default = "default_scenario_timeout")]
+    pub scenario_timeout_seconds: u64,
+    /// Skip scenarios that have not started once one fails
+    #[serde(default)]
+    pub fail_fast: bool,
+    /// Retries for scenarios that hit an RPC error (max 5)
+    #[serde(default)]
+    pub rpc_retries: u32,
+    /// Skip cached account and module lookups
+    #[serde(default)]
+    pub no_cache: bool,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: None,
+            scenario_timeout_seconds: default_scenario_timeout(),
+            fail_fast: false,
+            rpc_retries: 0,
+            no_cache: false,
+        }
+    }
+}
+
+fn default_scenario_timeout() -> u64 {
+    30
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -103,26 +155,140 @@ pub struct SimulationScenario {
     pub max_gas: Option<u64>,
     pub expect_success: Option<bool>,
     pub expect_error: Option<String>,
+    /// Call through the view endpoint instead of simulating a transaction
+    #[serde(default)]
+    pub is_view: bool,
+    /// Overrides the batch network for this scenario
+    #[serde(default)]
+    pub network: Option<String>,
+    /// Scenarios sharing a sequence run one at a time, in input order
+    #[serde(default)]
+    pub sequence: Option<String>,
+    /// Assertions on gas, aborts, events and state changes
+    #[serde(default)]
+    pub expect: ScenarioExpectations,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ScenarioExpectations {
+    /// Gas ceiling for the transaction
+    #[serde(default)]
+    pub max_gas_used: Option<u64>,
+    /// Gas floor, e.g. to catch a branch that silently stopped doing work
+    #[serde(default)]
+    pub min_gas_used: Option<u64>,
+    /// Expected Move abort code
+    #[serde(default)]
+    pub abort_code: Option<u64>,
+    /// Expected Move abort by module and error constant
+    #[serde(default)]
+    pub abort: Option<AbortExpectation>,
+    /// Events that must be emitted
+    #[serde(default)]
+    pub events: Vec<EventExpectation>,
+    /// Resource changes that must appear in the write set
+    #[serde(default)]
+    pub state_changes: Vec<StateChangeExpectation>,
+    /// Coin balance changes caused by the transaction
+    #[serde(default)]
+    pub balance_changes: Vec<BalanceChangeExpectation>,
+    /// Return values of a view function scenario
+    #[serde(default)]
+    pub returns: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AbortExpectation {
+    /// Aborting module, e.g. "0x1::coin"
+    #[serde(default)]
+    pub module: Option<String>,
+    /// Error constant, e.g. "EINSUFFICIENT_BALANCE"
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub code: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EventExpectation {
+    /// Fully qualified event type, e.g. "0x1::coin::DepositEvent"
+    pub r#type: String,
+    /// Fields the event data must contain; other fields are ignored
+    #[serde(default)]
+    pub data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StateChangeExpectation {
+    /// Fully qualified resource type
+    pub resource: String,
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default)]
+    pub change_type: Option<ChangeType>,
+    /// Fields the written resource must contain
+    #[serde(default)]
+    pub fields: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BalanceChangeExpectation {
+    pub address: String,
+    #[serde(default = "default_coin_type")]
+    pub coin_type: String,
+    /// Signed change in the smallest unit, gas fees included
+    pub delta: i128,
+}
+
+/// Native gas coin, the default for balance expectations
+pub const APTOS_COIN_TYPE: &str = "0x1::aptos_coin::AptosCoin";
+
+fn default_coin_type() -> String {
+    APTOS_COIN_TYPE.to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatchSimulationResult {
     pub total: u32,
     pub passed: u32,
     pub failed: u32,
+    /// Scenarios not run because an earlier one failed with `fail_fast`
+    #[serde(default)]
+    pub skipped: u32,
     pub results: Vec<ScenarioResult>,
     pub max_gas_used: u64,
+    /// Median gas used across the scenarios that ran
+    #[serde(default)]
+    pub gas_p50: u64,
+    #[serde(default)]
+    pub gas_p95: u64,
     pub summary: String,
+    /// Id to pass as `batch_run_id` to `/reports/markdown`; set once stored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<Uuid>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+impl BatchSimulationResult {
+    /// Gas used across every scenario that ran
+    pub fn total_gas_used(&self) -> u64 {
+        self.results.iter().map(|result| result.gas_used).sum()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScenarioResult {
     pub name: String,
     pub passed: bool,
+    #[serde(default)]
+    pub skipped: bool,
     pub gas_used: u64,
     pub expected_success: Option<bool>,
     pub actual_success: bool,
     pub expected_error: Option<String>,
     pub actual_error: Option<String>,
+    /// All failed assertions joined into one line
     pub failure_reason: Option<String>,
+    /// Every assertion that did not hold
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<String>,
 }
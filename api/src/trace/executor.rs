@@ -1,205 +1,82 @@
+use std::sync::Arc;
+
 use super::types::{ExecutionStep, LocalVariable, StackFrame, TraceRequest, TraceResult};
-use crate::config::Config;
 use crate::error::ApiError;
+use crate::movement::{prefixed_address, FunctionCall, MovementClient, SimulationTransaction};
 
 pub struct TraceExecutor {
-    http_client: reqwest::Client,
-    config: Config,
+    client: Arc<dyn MovementClient>,
 }
 
 impl TraceExecutor {
-    pub fn new(config: Config) -> Self {
-        Self {
-            http_client: reqwest::Client::new(),
-            config,
-        }
-    }
-
-    /// Build a GET request with Shinami API key header if configured
-    fn build_get(&self, url: &str) -> reqwest::RequestBuilder {
-        let mut req = self.http_client.get(url);
-        if let Some(api_key) = self.config.get_shinami_api_key() {
-            req = req.header("X-Api-Key", api_key);
-        }
-        req
-    }
-
-    /// Build a POST request with Shinami API key header if configured
-    fn build_post(&self, url: &str) -> reqwest::RequestBuilder {
-        let mut req = self.http_client.post(url);
-        if let Some(api_key) = self.config.get_shinami_api_key() {
-            req = req.header("X-Api-Key", api_key);
-        }
-        req
+    pub fn new(client: Arc<dyn MovementClient>) -> Self {
+        Self { client }
     }
 
     pub async fn execute(&self, request: TraceRequest) -> Result<TraceResult, ApiError> {
-        let rpc_url = self.config.get_rpc_url(&request.network);
-
-        // Convert numeric arguments to strings (Movement/Aptos API requires this for u64, u128, etc.)
-        let stringified_args: Vec<serde_json::Value> = request.args.iter().map(|arg| {
-            match arg {
-                serde_json::Value::Number(n) => serde_json::Value::String(n.to_string()),
-                other => other.clone(),
-            }
-        }).collect();
+        let call = FunctionCall::new(
+            &request.module_address,
+            &request.module_name,
+            &request.function_name,
+            &request.type_args,
+            &request.args,
+        );
 
         // First, try to get module info to check if function is entry or view
-        let is_view = self.check_if_view_function(&rpc_url, &request).await;
+        let is_view = self
+            .client
+            .is_view_function(
+                &request.network,
+                &request.module_address,
+                &request.module_name,
+                &request.function_name,
+            )
+            .await;
 
         if is_view {
-            return self.execute_view_trace(&rpc_url, &request, &stringified_args).await;
+            return self.execute_view_trace(&request, &call).await;
         }
 
-        // Build the transaction payload for entry functions
-        let function_path = format!(
-            "{}::{}::{}",
-            request.module_address,
-            request.module_name,
-            request.function_name
-        );
-        let payload = serde_json::json!({
-            "type": "entry_function_payload",
-            "function": function_path,
-            "type_arguments": request.type_args,
-            "arguments": stringified_args,
-        });
-
-        // Normalize sender address
-        let sender_normalized = if request.sender.starts_with("0x") {
-            request.sender.clone()
-        } else {
-            format!("0x{}", request.sender)
-        };
-
-        // Fetch account info to get authentication key and sequence number
-        let (public_key, sequence_number) = self.get_account_auth_info(&rpc_url, &sender_normalized).await?;
-
-        // Ed25519 signature for simulation (zeroed signature with real public key)
-        let dummy_signature = serde_json::json!({
-            "type": "ed25519_signature",
-            "public_key": public_key,
-            "signature": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"
-        });
-
-        // Build the simulation request body
-        let body = serde_json::json!({
-            "sender": sender_normalized,
-            "sequence_number": sequence_number,
-            "max_gas_amount": "100000",
-            "gas_unit_price": "100",
-            "expiration_timestamp_secs": (chrono::Utc::now().timestamp() + 600).to_string(),
-            "payload": payload,
-            "signature": dummy_signature,
-        });
-
-        // Make the simulation request with query params for gas estimation
-        let response = self
-            .build_post(&format!(
-                "{}/transactions/simulate?estimate_gas_unit_price=true&estimate_prioritized_gas_unit_price=false",
-                rpc_url
-            ))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ApiError::SimulationFailed(error_text));
-        }
-
-        let result: Vec<serde_json::Value> = response.json().await?;
-        let tx_result = result.first().ok_or_else(|| {
-            ApiError::SimulationFailed("Empty response from simulation".to_string())
-        })?;
-
-        self.construct_trace(&request, tx_result)
-    }
-
-    /// Check if a function is a view function by querying the module ABI
-    async fn check_if_view_function(&self, rpc_url: &str, request: &TraceRequest) -> bool {
-        let module_url = format!(
-            "{}/accounts/{}/module/{}",
-            rpc_url, request.module_address, request.module_name
-        );
-
-        let response = match self.build_get(&module_url).send().await {
-            Ok(r) => r,
-            Err(_) => return false,
-        };
-
-        if !response.status().is_success() {
-            return false;
-        }
+        // Fetch the sequence number; new accounts start at 0
+        let sequence_number = self
+            .client
+            .get_account(&request.network, &request.sender)
+            .await?
+            .map(|account| account.sequence_number)
+            .unwrap_or(0);
 
-        let module_info: serde_json::Value = match response.json().await {
-            Ok(v) => v,
-            Err(_) => return false,
+        let transaction = SimulationTransaction {
+            sender: prefixed_address(&request.sender),
+            sequence_number,
+            max_gas_amount: 100_000,
+            gas_unit_price: 100,
+            call,
+            public_key: None,
         };
 
-        // Check exposed_functions for the function
-        if let Some(abi) = module_info.get("abi") {
-            if let Some(functions) = abi.get("exposed_functions").and_then(|f| f.as_array()) {
-                for func in functions {
-                    if let Some(name) = func.get("name").and_then(|n| n.as_str()) {
-                        if name == request.function_name {
-                            // Check if it's a view function (is_view: true OR is_entry: false)
-                            let is_view = func.get("is_view").and_then(|v| v.as_bool()).unwrap_or(false);
-                            let is_entry = func.get("is_entry").and_then(|v| v.as_bool()).unwrap_or(false);
-                            return is_view || !is_entry;
-                        }
-                    }
-                }
-            }
-        }
-
-        false
+        let tx_result = self.client.simulate(&request.network, &transaction).await?;
+        self.construct_trace(&request, &tx_result)
     }
 
     /// Execute a view function and construct a trace from the result
     async fn execute_view_trace(
         &self,
-        rpc_url: &str,
         request: &TraceRequest,
-        stringified_args: &[serde_json::Value],
+        call: &FunctionCall,
     ) -> Result<TraceResult, ApiError> {
-        let body = serde_json::json!({
-            "function": format!(
-                "{}::{}::{}",
-                request.module_address,
-                request.module_name,
-                request.function_name
-            ),
-            "type_arguments": request.type_args,
-            "arguments": stringified_args,
-        });
-
-        let response = self
-            .build_post(&format!("{}/view", rpc_url))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ApiError::SimulationFailed(format!(
-                "View function failed: {}",
-                error_text
-            )));
-        }
-
-        let result: serde_json::Value = response.json().await?;
+        let result = self
+            .client
+            .view(&request.network, call)
+            .await
+            .map_err(|e| match e {
+                ApiError::SimulationFailed(error_text) => {
+                    ApiError::SimulationFailed(format!("View function failed: {}", error_text))
+                }
+                other => other,
+            })?;
 
         // Construct a trace for the view function
-        self.construct_view_trace(request, &result)
+        self.construct_view_trace(request, &serde_json::Value::Array(result))
     }
 
     /// Construct a trace for a view function execution
@@ -528,35 +405,46 @@ impl TraceExecutor {
 
         locals
     }
+}
 
-    /// Fetches account sequence number and returns a simulation-compatible public key.
-    /// For simulation, we use a well-known valid Ed25519 public key since the signature
-    /// is not actually verified - only the format needs to be valid.
-    async fn get_account_auth_info(&self, rpc_url: &str, address: &str) -> Result<(String, String), ApiError> {
-        // Use a well-known valid Ed25519 public key for simulation
-        // This is the Ed25519 base point which is always a valid curve point
-        // The actual key doesn't matter for simulation - only the format needs to be valid
-        let simulation_public_key = "0x3b6a27bcceb6a42d62a3a8d02a6f0d73653215771de243a63ac048a18b59da29";
-
-        let account_url = format!("{}/accounts/{}", rpc_url, address);
-
-        let response = self.build_get(&account_url)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            // Account may not exist - use default sequence number
-            return Ok((simulation_public_key.to_string(), "0".to_string()));
-        }
-
-        let account_info: serde_json::Value = response.json().await?;
-
-        // Extract sequence number
-        let sequence_number = account_info.get("sequence_number")
-            .and_then(|v| v.as_str())
-            .unwrap_or("0")
-            .to_string();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::movement::{MockMovementClient, MoveFunctionAbi, MoveModuleAbi};
+
+    #[tokio::test]
+    async fn test_view_function_is_traced_without_simulating() {
+        let module = MoveModuleAbi {
+            name: "counter".to_string(),
+            exposed_functions: vec![MoveFunctionAbi {
+                name: "get".to_string(),
+                is_view: true,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let client = Arc::new(
+            MockMovementClient::new()
+                .with_module("0xc0", module)
+                .with_view("0xc0::counter::get", vec![serde_json::json!("42")]),
+        );
+        let executor = TraceExecutor::new(client.clone());
+
+        let result = executor
+            .execute(TraceRequest {
+                network: "testnet".to_string(),
+                sender: "0x1".to_string(),
+                module_address: "0xc0".to_string(),
+                module_name: "counter".to_string(),
+                function_name: "get".to_string(),
+                type_args: vec![],
+                args: vec![],
+            })
+            .await
+            .unwrap();
 
-        Ok((simulation_public_key.to_string(), sequence_number))
+        assert!(result.success);
+        assert_eq!(result.steps.last().unwrap().locals[0].value, serde_json::json!(["42"]));
+        assert!(client.simulate_calls().is_empty());
     }
 }
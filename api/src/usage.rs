@@ -0,0 +1,325 @@
+//! Usage metering and monthly quotas
+//!
+//! `track_usage` records every authenticated call in `usage_events`, with
+//! the units `rate_limit` charged it; calls turned away before then cost
+//! nothing, so recounting the quota from the events matches the counter.
+//! Handlers add what they know about the work done (network, gas,
+//! prover time) through a `UsageMeter`; the event is written once the
+//! last reference to the meter is gone, so streaming handlers that keep
+//! working after the response has started are still fully accounted for.
+
+use axum::{
+    extract::{FromRequestParts, MatchedPath, Request, State},
+    http::request::Parts,
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use serde::Serialize;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::auth::{AuthenticatedUser, RateLimitTier};
+use crate::cache::{self, ttl, CacheBackend};
+use crate::db::{self, CreateUsageEvent, DbPool};
+use crate::error::ApiError;
+use crate::AppState;
+
+/// Collects usage for one call; cheap to clone into spawned tasks
+///
+/// Outside `track_usage` (e.g. in tests) the meter is detached and
+/// records nothing.
+#[derive(Clone, Default)]
+pub struct UsageMeter(Option<Arc<Meter>>);
+
+struct Meter {
+    pool: DbPool,
+    event: Mutex<CreateUsageEvent>,
+}
+
+impl UsageMeter {
+    fn start(pool: DbPool, event: CreateUsageEvent) -> Self {
+        Self(Some(Arc::new(Meter {
+            pool,
+            event: Mutex::new(event),
+        })))
+    }
+
+    fn update(&self, f: impl FnOnce(&mut CreateUsageEvent)) {
+        if let Some(meter) = &self.0 {
+            f(&mut meter.event.lock().unwrap_or_else(|e| e.into_inner()));
+        }
+    }
+
+    /// Units spent from the user's quota on this call
+    pub fn charge(&self, units: u32) {
+        self.update(|event| event.units = units);
+    }
+
+    pub fn set_network(&self, network: &str) {
+        self.update(|event| event.network = Some(network.to_string()));
+    }
+
+    pub fn add_gas(&self, gas: u64) {
+        self.update(|event| event.gas_used = Some(event.gas_used.unwrap_or(0) + gas));
+    }
+
+    /// Time the prover ran for; it is CPU-bound, so this is its CPU time
+    pub fn add_prover_time(&self, duration_ms: u64) {
+        let secs = duration_ms as f64 / 1000.0;
+        self.update(|event| event.prover_cpu_secs = Some(event.prover_cpu_secs.unwrap_or(0.0) + secs));
+    }
+}
+
+impl Drop for Meter {
+    fn drop(&mut self) {
+        let event = self.event.get_mut().unwrap_or_else(|e| e.into_inner()).clone();
+        let pool = self.pool.clone();
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move {
+                if let Err(e) = db::insert_usage_event(&pool, &event).await {
+                    tracing::warn!("Failed to record usage for {}: {}", event.user_id, e);
+                }
+            });
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for UsageMeter {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts.extensions.get::<UsageMeter>().cloned().unwrap_or_default())
+    }
+}
+
+/// Middleware recording a usage event for every authenticated call
+///
+/// Runs after `api_key_auth` and before `require_scopes` and `rate_limit`,
+/// so rejected calls are recorded too, with no units unless `rate_limit`
+/// charges them. Latency is the time until the response started.
+pub async fn track_usage(State(state): State<AppState>, mut request: Request, next: Next) -> Response {
+    let Some(user) = request.extensions().get::<AuthenticatedUser>().cloned() else {
+        return next.run(request).await;
+    };
+
+    let endpoint = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    let meter = UsageMeter::start(
+        state.db.clone(),
+        CreateUsageEvent {
+            user_id: user.user_id,
+            api_key_id: user.api_key_id,
+            units: 0,
+            endpoint,
+            network: None,
+            gas_used: None,
+            prover_cpu_secs: None,
+            status: 0,
+            latency_ms: 0,
+        },
+    );
+    request.extensions_mut().insert(meter.clone());
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+    let status = response.status().as_u16();
+    let latency_ms = started.elapsed().as_millis() as u64;
+    meter.update(|event| {
+        event.status = status;
+        event.latency_ms = latency_ms;
+    });
+    response
+}
+
+/// Where a user stands against their monthly quota
+#[derive(Debug, Clone, Serialize)]
+pub struct QuotaStatus {
+    pub tier: &'static str,
+    /// `None` when the tier is unmetered
+    pub limit: Option<u64>,
+    pub used: u64,
+    pub remaining: Option<u64>,
+    pub resets_at: DateTime<Utc>,
+}
+
+/// Start of the current calendar month (UTC) and of the next one
+pub fn month_bounds(now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = Utc
+        .with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+        .unwrap();
+    let (year, month) = if now.month() == 12 {
+        (now.year() + 1, 1)
+    } else {
+        (now.year(), now.month() + 1)
+    };
+    let end = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).unwrap();
+    (start, end)
+}
+
+/// Spends `units` of the user's monthly quota
+///
+/// The running total is kept in the cache and recounted from
+/// `usage_events` when it expires. Returns `None` for unmetered tiers and
+/// when the count is unavailable, in which case the call is let through.
+pub async fn consume_quota(
+    state: &AppState,
+    user_id: &str,
+    tier: RateLimitTier,
+    units: u32,
+) -> Result<Option<QuotaStatus>, ApiError> {
+    let Some(limit) = tier.monthly_quota() else {
+        return Ok(None);
+    };
+
+    let now = Utc::now();
+    let (start, end) = month_bounds(now);
+    let key = cache::quota_key(user_id, &start.format("%Y-%m").to_string());
+    let cache = state.cache.as_ref();
+
+    if let Ok(None) = cache.get(&key).await {
+        match db::get_usage_units_since(&state.db, user_id, start).await {
+            Ok(used) => {
+                if let Err(e) = cache.set(&key, &used.to_string(), ttl::QUOTA).await {
+                    tracing::warn!("Failed to cache quota usage for {}: {}", user_id, e);
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Failed to count quota usage for {}, allowing request: {}", user_id, e);
+                return Ok(None);
+            }
+        }
+    }
+
+    let used = match cache.incr(&key, units as i64, ttl::QUOTA).await {
+        Ok((used, _)) => used.max(0) as u64,
+        Err(e) => {
+            tracing::warn!("Quota check failed for {}, allowing request: {}", user_id, e);
+            return Ok(None);
+        }
+    };
+
+    if used > limit {
+        if let Err(e) = cache.incr(&key, -(units as i64), ttl::QUOTA).await {
+            tracing::warn!("Failed to release quota for {}: {}", user_id, e);
+        }
+        return Err(ApiError::QuotaExceeded((end - now).num_seconds().max(1) as u64));
+    }
+
+    Ok(Some(QuotaStatus {
+        tier: tier.as_str(),
+        limit: Some(limit),
+        used,
+        remaining: Some(limit - used),
+        resets_at: end,
+    }))
+}
+
+/// The user's quota standing this month, counted from `usage_events`
+pub async fn quota_status(
+    pool: &DbPool,
+    user_id: &str,
+    tier: RateLimitTier,
+) -> Result<QuotaStatus, ApiError> {
+    let (start, end) = month_bounds(Utc::now());
+    let used = db::get_usage_units_since(pool, user_id, start)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to count usage: {}", e)))?
+        .max(0) as u64;
+    let limit = tier.monthly_quota();
+
+    Ok(QuotaStatus {
+        tier: tier.as_str(),
+        limit,
+        used,
+        remaining: limit.map(|limit| limit.saturating_sub(used)),
+        resets_at: end,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_month_bounds() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 18, 13, 5, 0).unwrap();
+        let (start, end) = month_bounds(now);
+        assert_eq!(start, Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap());
+        assert_eq!(end, Utc.with_ymd_and_hms(2026, 11, 1, 0, 0, 0).unwrap());
+
+        let (start, end) = month_bounds(Utc.with_ymd_and_hms(2026, 12, 31, 23, 59, 59).unwrap());
+        assert_eq!(start.month(), 12);
+        assert_eq!(end, Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs Postgres; set TEST_DATABASE_URL and run with --ignored"]
+    async fn test_only_charged_calls_use_quota() {
+        use axum::{http::StatusCode, middleware, routing::post, Router};
+        use axum_test::TestServer;
+        use uuid::Uuid;
+
+        use crate::auth::{rate_limit, require_scopes, EndpointScope, KeyScopes};
+
+        let pool = db::test_pool().await;
+        let user = AuthenticatedUser {
+            user_id: format!("user_{}", Uuid::new_v4()),
+            api_key_id: Uuid::new_v4(),
+            scopes: KeyScopes {
+                endpoints: Some(vec![EndpointScope::Simulate]),
+                ..KeyScopes::default()
+            },
+        };
+        let user_id = user.user_id.clone();
+        // Stands in for api_key_auth
+        let sign_in = move |mut request: Request, next: Next| {
+            let user = user.clone();
+            async move {
+                request.extensions_mut().insert(user);
+                next.run(request).await
+            }
+        };
+        let state = AppState::for_tests(pool.clone());
+        let app = Router::new()
+            .route("/simulate", post(|| async { "ok" }))
+            .route("/prove", post(|| async { "ok" }))
+            .route_layer(middleware::from_fn_with_state(state.clone(), rate_limit))
+            .route_layer(middleware::from_fn(require_scopes))
+            .route_layer(middleware::from_fn_with_state(state.clone(), track_usage))
+            .layer(middleware::from_fn(sign_in))
+            .with_state(state);
+        let server = TestServer::new(app).unwrap();
+
+        server.post("/prove").await.assert_status(StatusCode::FORBIDDEN);
+        server.post("/simulate").await.assert_status_ok();
+
+        // Events are written in the background once their meters drop
+        let (from, to) = month_bounds(Utc::now());
+        let mut keys = Vec::new();
+        for _ in 0..50 {
+            keys = db::get_usage_by_key(&pool, &user_id, None, from, to).await.unwrap();
+            if keys.first().is_some_and(|key| key.calls == 2) {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(keys[0].calls, 2);
+        assert_eq!(keys[0].errors, 1);
+        // The forbidden prover call was recorded but not charged
+        assert_eq!(db::get_usage_units_since(&pool, &user_id, from).await.unwrap(), 1);
+    }
+
+    #[test]
+    fn test_detached_meter_records_nothing() {
+        let meter = UsageMeter::default();
+        meter.set_network("testnet");
+        meter.add_gas(100);
+        meter.add_prover_time(1500);
+        assert!(meter.0.is_none());
+    }
+}
@@ -15,11 +15,17 @@ export async function DELETE(
 
   const supabase = getSupabase();
 
-  const { error } = await supabase
-    .from("sentinel_api_keys")
-    .delete()
+  // Revoke rather than delete, so the key's history is kept
+  const { data: revoked, error } = await supabase
+    .from("api_keys")
+    .update({
+      revoked_at: new Date().toISOString(),
+      revoked_reason: "Deleted from the dashboard",
+    })
     .eq("id", id)
-    .eq("wallet_address", walletAddress);
+    .eq("wallet_address", walletAddress)
+    .is("revoked_at", null)
+    .select("id");
 
   if (error) {
     return NextResponse.json(
@@ -28,5 +34,13 @@ export async function DELETE(
     );
   }
 
+  if (!revoked || revoked.length === 0) {
+    return NextResponse.json({ error: "API key not found" }, { status: 404 });
+  }
+
+  await supabase
+    .from("key_events")
+    .insert({ api_key_id: id, event: "revoked", detail: "Deleted from the dashboard" });
+
   return NextResponse.json({ success: true });
 }
@@ -20,10 +20,12 @@ export async function GET(request: NextRequest) {
 
   const supabase = getSupabase();
 
+  // Keys live in api_keys, shared with the API; revoked keys are hidden
   const { data: keys, error } = await supabase
-    .from("sentinel_api_keys")
+    .from("api_keys")
     .select("id, name, key_prefix, last_used_at, created_at")
     .eq("wallet_address", walletAddress)
+    .is("revoked_at", null)
     .order("created_at", { ascending: false });
 
   if (error) {
@@ -62,11 +64,13 @@ export async function POST(request: NextRequest) {
 
   const supabase = getSupabase();
 
-  // Check rate limit
+  // Check rate limit (usable keys only, as the API counts them)
   const { count } = await supabase
-    .from("sentinel_api_keys")
+    .from("api_keys")
     .select("*", { count: "exact", head: true })
-    .eq("wallet_address", walletAddress);
+    .eq("wallet_address", walletAddress)
+    .is("revoked_at", null)
+    .or(`expires_at.is.null,expires_at.gt.${new Date().toISOString()}`);
 
   if (count && count >= 10) {
     return NextResponse.json(
@@ -78,7 +82,7 @@ export async function POST(request: NextRequest) {
   const { key, keyPrefix, keyHash } = generateApiKey();
 
   const { data: newKey, error } = await supabase
-    .from("sentinel_api_keys")
+    .from("api_keys")
     .insert({
       wallet_address: walletAddress,
       name,
@@ -96,6 +100,10 @@ export async function POST(request: NextRequest) {
     );
   }
 
+  await supabase
+    .from("key_events")
+    .insert({ api_key_id: newKey.id, event: "created", detail: "Created from the dashboard" });
+
   return NextResponse.json(
     {
       ...newKey,
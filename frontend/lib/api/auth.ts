@@ -8,6 +8,7 @@ export function getWalletFromRequest(request: NextRequest): string | null {
 }
 
 // Get wallet address from API key (looks up key hash in database)
+// The API updates last_used_at when it authenticates the key
 export async function getWalletFromApiKey(
   apiKey: string | null
 ): Promise<string | null> {
@@ -17,20 +18,19 @@ export async function getWalletFromApiKey(
   const keyHash = createHash("sha256").update(apiKey).digest("hex");
 
   const { data, error } = await supabase
-    .from("sentinel_api_keys")
-    .select("wallet_address")
+    .from("api_keys")
+    .select("wallet_address, expires_at, revoked_at")
     .eq("key_hash", keyHash)
-    .single();
+    .maybeSingle();
 
   if (error || !data) {
     return null;
   }
 
-  // Update last_used_at timestamp
-  await supabase
-    .from("sentinel_api_keys")
-    .update({ last_used_at: new Date().toISOString() })
-    .eq("key_hash", keyHash);
+  // Revoked and expired keys no longer belong to anyone
+  if (data.revoked_at || (data.expires_at && new Date(data.expires_at) <= new Date())) {
+    return null;
+  }
 
   return data.wallet_address;
 }
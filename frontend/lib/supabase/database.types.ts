@@ -229,6 +229,96 @@ export interface Database {
           },
         ];
       };
+      api_keys: {
+        Row: {
+          id: string;
+          user_id: string | null;
+          wallet_address: string | null;
+          name: string;
+          key_hash: string;
+          key_prefix: string;
+          last_used_at: string | null;
+          created_at: string;
+          expires_at: string | null;
+          revoked_at: string | null;
+          revoked_reason: string | null;
+          allowed_endpoints: string[] | null;
+          allowed_networks: string[] | null;
+          project_id: string | null;
+          access: string;
+          superseded_by: string | null;
+          replaces: string | null;
+        };
+        Insert: {
+          id?: string;
+          user_id?: string | null;
+          wallet_address?: string | null;
+          name: string;
+          key_hash: string;
+          key_prefix: string;
+          last_used_at?: string | null;
+          created_at?: string;
+          expires_at?: string | null;
+          revoked_at?: string | null;
+          revoked_reason?: string | null;
+          allowed_endpoints?: string[] | null;
+          allowed_networks?: string[] | null;
+          project_id?: string | null;
+          access?: string;
+          superseded_by?: string | null;
+          replaces?: string | null;
+        };
+        Update: {
+          id?: string;
+          user_id?: string | null;
+          wallet_address?: string | null;
+          name?: string;
+          key_hash?: string;
+          key_prefix?: string;
+          last_used_at?: string | null;
+          created_at?: string;
+          expires_at?: string | null;
+          revoked_at?: string | null;
+          revoked_reason?: string | null;
+          allowed_endpoints?: string[] | null;
+          allowed_networks?: string[] | null;
+          project_id?: string | null;
+          access?: string;
+          superseded_by?: string | null;
+          replaces?: string | null;
+        };
+        Relationships: [];
+      };
+      key_events: {
+        Row: {
+          id: number;
+          api_key_id: string;
+          event: string;
+          ip_address: string | null;
+          user_agent: string | null;
+          detail: string | null;
+          created_at: string;
+        };
+        Insert: {
+          id?: number;
+          api_key_id: string;
+          event: string;
+          ip_address?: string | null;
+          user_agent?: string | null;
+          detail?: string | null;
+          created_at?: string;
+        };
+        Update: {
+          id?: number;
+          api_key_id?: string;
+          event?: string;
+          ip_address?: string | null;
+          user_agent?: string | null;
+          detail?: string | null;
+          created_at?: string;
+        };
+        Relationships: [];
+      };
       sentinel_teams: {
         Row: {
           id: string;